@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+# Rebuilds zip_centroids.csv from the GeoNames US postal code file (CC BY 4.0), which carries
+# the place name and state for each ZIP alongside its centroid.
+# Usage: data/fetch_zip_centroids.sh [STATE_ZIP_PREFIXES]
+#   STATE_ZIP_PREFIXES   optional regex of ZIP prefixes to keep, e.g. '^87[0-7]' for northern
+#                        New Mexico (default: the service area bundled with the app)
+set -euo pipefail
+
+prefixes="${1:-^87[0-7]}"
+out="$(cd "$(dirname "$0")" && pwd)/zip_centroids.csv"
+url="https://download.geonames.org/export/zip/US.zip"
+
+work="$(mktemp -d)"
+trap 'rm -rf "$work"' EXIT
+
+curl -fsSL "$url" -o "$work/us.zip"
+unzip -q -o "$work/us.zip" US.txt -d "$work"
+
+# country, zip, place, state name, state code, county, county code, admin3, admin3 code,
+# latitude, longitude, accuracy (tab separated).
+{
+  echo "zip,city,state,latitude,longitude"
+  awk -F'\t' -v prefixes="$prefixes" '$2 ~ prefixes {
+    gsub(/"/, "", $3)
+    printf "%s,\"%s\",%s,%s,%s\n", $2, $3, $5, $10, $11
+  }' "$work/US.txt" | sort
+} > "$out"
+
+echo "Wrote $(($(wc -l < "$out") - 1)) centroids to $out"
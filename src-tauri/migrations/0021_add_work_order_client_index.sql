@@ -0,0 +1,2 @@
+-- Per-client delivery summaries group work orders by client
+CREATE INDEX IF NOT EXISTS idx_work_orders_client_id ON work_orders(client_id) WHERE is_deleted = 0;
@@ -0,0 +1,21 @@
+-- Per-season cord allotments (optionally scaled by household size)
+ALTER TABLE clients ADD COLUMN household_size INTEGER;
+
+CREATE TABLE IF NOT EXISTS allotment_rules (
+  id TEXT PRIMARY KEY NOT NULL,
+  name TEXT NOT NULL,
+  season TEXT, -- e.g. '2025-2026'; NULL applies to every season
+  max_cords_per_household REAL NOT NULL,
+  extra_cords_per_member REAL NOT NULL DEFAULT 0, -- added for each member beyond the first
+  max_cords_cap REAL,
+  enforcement TEXT NOT NULL DEFAULT 'reject', -- 'reject' or 'warn'
+  is_active INTEGER NOT NULL DEFAULT 1,
+  created_by_user_id TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_synced_at TEXT,
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_allotment_rules_season ON allotment_rules(season) WHERE is_deleted = 0;
@@ -0,0 +1,39 @@
+-- Waitlist of approved clients waiting for wood, ranked by a configurable score
+CREATE TABLE IF NOT EXISTS waitlist_entries (
+  id TEXT PRIMARY KEY NOT NULL,
+  client_id TEXT NOT NULL,
+  requested_cords REAL NOT NULL,
+  priority_flags_json TEXT NOT NULL DEFAULT '[]', -- e.g. ["elderly","medical","no_other_heat"]
+  distance_miles REAL, -- falls back to clients.default_mileage
+  notes TEXT,
+  status TEXT NOT NULL DEFAULT 'waiting', -- 'waiting', 'promoted' or 'removed'
+  requested_at TEXT NOT NULL DEFAULT (datetime('now')),
+  promoted_at TEXT,
+  work_order_id TEXT,
+  removed_reason TEXT,
+  created_by_user_id TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_synced_at TEXT,
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0,
+  FOREIGN KEY (client_id) REFERENCES clients(id),
+  FOREIGN KEY (work_order_id) REFERENCES work_orders(id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_waitlist_one_waiting_per_client
+  ON waitlist_entries(client_id) WHERE status = 'waiting' AND is_deleted = 0;
+
+-- Single-row ranking formula: score = days_waiting * weight_days_waiting
+--   + sum(flag weights) + distance_miles * weight_per_mile
+CREATE TABLE IF NOT EXISTS waitlist_settings (
+  id TEXT PRIMARY KEY NOT NULL,
+  weight_days_waiting REAL NOT NULL DEFAULT 1.0,
+  weight_priority_flag REAL NOT NULL DEFAULT 30.0, -- default weight for any flag
+  flag_weights_json TEXT NOT NULL DEFAULT '{}', -- per-flag overrides, e.g. {"medical": 60}
+  weight_per_mile REAL NOT NULL DEFAULT -0.5, -- negative favours nearby households
+  updated_by TEXT,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO waitlist_settings (id) VALUES ('default');
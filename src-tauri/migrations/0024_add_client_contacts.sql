@@ -0,0 +1,22 @@
+-- Interaction log (calls, visits, letters, SMS) kept separate from clients.notes
+CREATE TABLE IF NOT EXISTS client_contacts (
+  id TEXT PRIMARY KEY NOT NULL,
+  client_id TEXT NOT NULL,
+  contact_type TEXT NOT NULL, -- 'call', 'visit', 'letter' or 'sms'
+  direction TEXT NOT NULL, -- 'inbound' or 'outbound'
+  contacted_at TEXT NOT NULL DEFAULT (datetime('now')),
+  summary TEXT,
+  outcome TEXT,
+  follow_up_date TEXT,
+  staff_user_id TEXT,
+  staff_display TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_synced_at TEXT,
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0,
+  FOREIGN KEY (client_id) REFERENCES clients(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_client_contacts_client ON client_contacts(client_id, contacted_at) WHERE is_deleted = 0;
+CREATE INDEX IF NOT EXISTS idx_audit_logs_entity ON audit_logs(entity, entity_id);
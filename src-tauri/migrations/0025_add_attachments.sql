@@ -0,0 +1,22 @@
+-- Attachment metadata; file contents live in the app data dir under their SHA-256 name
+CREATE TABLE IF NOT EXISTS attachments (
+  id TEXT PRIMARY KEY NOT NULL,
+  entity TEXT NOT NULL, -- 'clients' or 'work_orders'
+  entity_id TEXT NOT NULL,
+  attachment_type TEXT NOT NULL, -- 'proof_of_residence', 'onboarding_form', 'delivery_photo', 'other'
+  file_name TEXT NOT NULL,
+  mime_type TEXT,
+  size_bytes INTEGER NOT NULL,
+  sha256 TEXT NOT NULL,
+  contains_pii INTEGER NOT NULL DEFAULT 1,
+  uploaded_by_user_id TEXT,
+  uploaded_by_display TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_synced_at TEXT,
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_entity ON attachments(entity, entity_id) WHERE is_deleted = 0;
+CREATE INDEX IF NOT EXISTS idx_attachments_sha256 ON attachments(sha256);
@@ -0,0 +1,16 @@
+-- Right-to-erasure support: anonymized clients and their certificates of erasure
+ALTER TABLE clients ADD COLUMN anonymized_at TEXT;
+
+CREATE TABLE IF NOT EXISTS erasure_certificates (
+  id TEXT PRIMARY KEY NOT NULL,
+  client_id TEXT NOT NULL,
+  anonymized_label TEXT NOT NULL,
+  reason TEXT,
+  performed_by TEXT NOT NULL,
+  performed_at TEXT NOT NULL,
+  records_json TEXT NOT NULL, -- [{ "table": ..., "rows": ... }]
+  digest TEXT NOT NULL, -- SHA-256 of the certificate body
+  created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_erasure_certificates_client ON erasure_certificates(client_id);
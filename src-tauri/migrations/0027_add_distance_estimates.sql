@@ -0,0 +1,27 @@
+-- Offline ZIP/city centroids, yard location and per-client road-distance estimates
+CREATE TABLE IF NOT EXISTS zip_centroids (
+  zip TEXT PRIMARY KEY NOT NULL,
+  city TEXT,
+  state TEXT,
+  latitude REAL NOT NULL,
+  longitude REAL NOT NULL,
+  source TEXT, -- 'bundled' or 'import'
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_zip_centroids_city ON zip_centroids(lower(city), state);
+
+CREATE TABLE IF NOT EXISTS distance_settings (
+  id TEXT PRIMARY KEY NOT NULL,
+  yard_label TEXT,
+  yard_latitude REAL,
+  yard_longitude REAL,
+  road_factor REAL NOT NULL DEFAULT 1.3, -- straight-line miles x factor ~ road miles
+  updated_by TEXT,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO distance_settings (id) VALUES ('default');
+
+ALTER TABLE clients ADD COLUMN estimated_mileage REAL;
+ALTER TABLE clients ADD COLUMN estimated_mileage_source TEXT; -- 'zip' or 'city'
@@ -0,0 +1,22 @@
+-- Managed registry of referring agencies; clients link to it from their free-text referral fields
+CREATE TABLE IF NOT EXISTS agencies (
+  id TEXT PRIMARY KEY NOT NULL,
+  name TEXT NOT NULL,
+  aliases_json TEXT NOT NULL DEFAULT '[]', -- other spellings that should map to this agency
+  contact_name TEXT,
+  telephone TEXT,
+  email TEXT,
+  address TEXT,
+  notes TEXT,
+  is_active INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_synced_at TEXT,
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_agencies_name ON agencies(lower(name)) WHERE is_deleted = 0;
+
+ALTER TABLE clients ADD COLUMN referring_agency_id TEXT REFERENCES agencies(id);
+CREATE INDEX IF NOT EXISTS idx_clients_referring_agency ON clients(referring_agency_id);
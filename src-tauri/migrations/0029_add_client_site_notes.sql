@@ -0,0 +1,5 @@
+-- Driver-facing site notes, kept apart from the confidential clients.notes
+ALTER TABLE clients ADD COLUMN site_hazards TEXT; -- e.g. loose dog, steep icy driveway
+ALTER TABLE clients ADD COLUMN site_drop_location TEXT; -- e.g. stack wood by shed
+ALTER TABLE clients ADD COLUMN site_vehicle_access TEXT; -- e.g. no trailers, low branches
+ALTER TABLE clients ADD COLUMN site_preferred_contact_time TEXT;
@@ -0,0 +1,35 @@
+-- Alternate contacts for when a delivery can't be completed, and how each client prefers to be reached
+CREATE TABLE IF NOT EXISTS client_emergency_contacts (
+  id TEXT PRIMARY KEY NOT NULL,
+  client_id TEXT NOT NULL,
+  name TEXT NOT NULL,
+  relationship TEXT,
+  telephone TEXT,
+  alt_telephone TEXT,
+  email TEXT,
+  notes TEXT,
+  priority INTEGER NOT NULL DEFAULT 1, -- 1 is called first
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_synced_at TEXT,
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0,
+  FOREIGN KEY (client_id) REFERENCES clients(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_client_emergency_contacts_client
+  ON client_emergency_contacts(client_id, priority) WHERE is_deleted = 0;
+
+CREATE TABLE IF NOT EXISTS client_communication_preferences (
+  client_id TEXT PRIMARY KEY NOT NULL,
+  preferred_language TEXT,
+  best_channel TEXT, -- 'call', 'sms', 'email', 'letter' or 'visit'
+  do_not_call_json TEXT NOT NULL DEFAULT '[]', -- [{"days":["mon"],"start":"20:00","end":"08:00"}]
+  accessibility_needs TEXT,
+  updated_by TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  last_synced_at TEXT,
+  version INTEGER NOT NULL DEFAULT 0,
+  FOREIGN KEY (client_id) REFERENCES clients(id)
+);
@@ -0,0 +1,28 @@
+-- One row per status change, so an order's lifecycle can be replayed with timestamps
+CREATE TABLE IF NOT EXISTS work_order_status_history (
+  id TEXT PRIMARY KEY NOT NULL,
+  work_order_id TEXT NOT NULL,
+  from_status TEXT, -- NULL for the status an order was created with
+  to_status TEXT NOT NULL,
+  changed_at TEXT NOT NULL DEFAULT (datetime('now')),
+  changed_by TEXT,
+  role TEXT,
+  reason TEXT,
+  FOREIGN KEY (work_order_id) REFERENCES work_orders(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_order_status_history_order
+  ON work_order_status_history(work_order_id, changed_at);
+
+-- Backfill from the field-level audit trail; orders without any recorded change get their
+-- current status as of creation.
+INSERT INTO work_order_status_history (id, work_order_id, from_status, to_status, changed_at, changed_by, role)
+SELECT lower(hex(randomblob(16))), entity_id, old_value, new_value, created_at, actor, role
+FROM audit_logs
+WHERE entity = 'work_orders' AND field = 'status' AND new_value IS NOT NULL
+  AND entity_id IN (SELECT id FROM work_orders);
+
+INSERT INTO work_order_status_history (id, work_order_id, from_status, to_status, changed_at, changed_by)
+SELECT lower(hex(randomblob(16))), w.id, NULL, w.status, w.created_at, w.created_by_display
+FROM work_orders w
+WHERE NOT EXISTS (SELECT 1 FROM work_order_status_history h WHERE h.work_order_id = w.id);
@@ -0,0 +1,5 @@
+-- Why and when an order was cancelled; the reason code is one of CANCEL_REASON_CODES in main.rs
+ALTER TABLE work_orders ADD COLUMN cancel_reason_code TEXT;
+ALTER TABLE work_orders ADD COLUMN cancel_reason_note TEXT;
+ALTER TABLE work_orders ADD COLUMN cancelled_at TEXT;
+ALTER TABLE work_orders ADD COLUMN cancelled_by TEXT;
@@ -0,0 +1,42 @@
+-- Who is assigned to each work order, keyed by users.id. assignees_json stays as the display list
+-- (it may also hold free-text helpers who have no user record).
+CREATE TABLE IF NOT EXISTS work_order_assignments (
+  id TEXT PRIMARY KEY NOT NULL,
+  work_order_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  assignment_role TEXT NOT NULL DEFAULT 'helper', -- 'driver', 'helper' or 'lead'
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  created_by TEXT,
+  UNIQUE (work_order_id, user_id),
+  FOREIGN KEY (work_order_id) REFERENCES work_orders(id),
+  FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_work_order_assignments_user
+  ON work_order_assignments(user_id, work_order_id);
+CREATE INDEX IF NOT EXISTS idx_auth_users_username_lower
+  ON auth_users(lower(username)) WHERE is_deleted = 0;
+
+-- Entries were usernames or display names; match either, case-insensitively.
+INSERT OR IGNORE INTO work_order_assignments (id, work_order_id, user_id, assignment_role)
+SELECT
+  lower(hex(randomblob(16))),
+  w.id,
+  u.id,
+  CASE
+    WHEN u.is_driver = 1 OR trim(COALESCE(u.driver_license_status, '')) != '' THEN 'driver'
+    WHEN lower(u.role) = 'lead' THEN 'lead'
+    ELSE 'helper'
+  END
+FROM work_orders w
+JOIN json_each(CASE WHEN json_valid(w.assignees_json) THEN w.assignees_json ELSE '[]' END) j
+JOIN users u
+  ON u.is_deleted = 0
+ AND (
+   lower(u.name) = lower(trim(j.value))
+   OR EXISTS (
+     SELECT 1 FROM auth_users a
+     WHERE a.user_id = u.id AND a.is_deleted = 0 AND lower(a.username) = lower(trim(j.value))
+   )
+ )
+WHERE w.is_deleted = 0;
@@ -0,0 +1,35 @@
+-- Background jobs. Each row switches a built-in job on or off and sets how often it runs;
+-- locked_at/locked_by is the single-instance lock taken for the length of a run.
+CREATE TABLE IF NOT EXISTS scheduled_jobs (
+  job_key TEXT PRIMARY KEY NOT NULL,
+  description TEXT,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  interval_minutes INTEGER NOT NULL DEFAULT 60,
+  run_at_startup INTEGER NOT NULL DEFAULT 0,
+  last_started_at TEXT,
+  last_finished_at TEXT,
+  last_outcome TEXT, -- 'succeeded' or 'failed'
+  locked_at TEXT,
+  locked_by TEXT,
+  updated_by TEXT,
+  updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO scheduled_jobs (job_key, description, interval_minutes, run_at_startup)
+VALUES
+  ('reconcile_schedule', 'Move dated orders to scheduled and add missing delivery events', 60, 1),
+  ('driver_license_expiry', 'Report drivers whose license has expired or expires within 30 days', 1440, 0);
+
+CREATE TABLE IF NOT EXISTS job_runs (
+  id TEXT PRIMARY KEY NOT NULL,
+  job_key TEXT NOT NULL,
+  started_at TEXT NOT NULL DEFAULT (datetime('now')),
+  finished_at TEXT,
+  outcome TEXT NOT NULL DEFAULT 'running', -- 'running', 'succeeded' or 'failed'
+  output TEXT,
+  triggered_by TEXT,
+  FOREIGN KEY (job_key) REFERENCES scheduled_jobs(job_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_runs_job
+  ON job_runs(job_key, started_at);
@@ -0,0 +1,15 @@
+-- Cords actually delivered. On an event it is what the crew unloaded on that trip; on the order it
+-- is the total settled against inventory when the order was completed.
+ALTER TABLE delivery_events ADD COLUMN delivered_cords REAL;
+ALTER TABLE work_orders ADD COLUMN delivered_cords REAL;
+
+-- A backorder carries the undelivered balance of a short delivery.
+ALTER TABLE work_orders ADD COLUMN backorder_of_id TEXT REFERENCES work_orders(id);
+
+CREATE INDEX IF NOT EXISTS idx_work_orders_backorder_of
+  ON work_orders(backorder_of_id) WHERE backorder_of_id IS NOT NULL;
+
+-- Orders completed before this were settled at their full size.
+UPDATE work_orders
+SET delivered_cords = delivery_size_cords
+WHERE lower(status) = 'completed' AND delivery_size_cords IS NOT NULL;
@@ -0,0 +1,33 @@
+-- Households that get a load on a fixed cadence through the heating season. The generator turns
+-- each definition into dated work orders a few weeks ahead.
+CREATE TABLE IF NOT EXISTS standing_orders (
+  id TEXT PRIMARY KEY NOT NULL,
+  client_id TEXT NOT NULL,
+  cadence_weeks INTEGER NOT NULL DEFAULT 2,
+  preferred_weekday TEXT NOT NULL, -- 'mon'..'sun'
+  delivery_size_label TEXT,
+  delivery_size_cords REAL NOT NULL,
+  window_start TEXT NOT NULL, -- first date a load may go out
+  window_end TEXT NOT NULL, -- last date a load may go out
+  notes TEXT,
+  is_active INTEGER NOT NULL DEFAULT 1,
+  created_by_user_id TEXT,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0,
+  FOREIGN KEY (client_id) REFERENCES clients(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_standing_orders_client
+  ON standing_orders(client_id) WHERE is_deleted = 0;
+
+-- Which definition an order came from; one order per definition and date.
+ALTER TABLE work_orders ADD COLUMN standing_order_id TEXT REFERENCES standing_orders(id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_work_orders_standing_date
+  ON work_orders(standing_order_id, scheduled_date)
+  WHERE standing_order_id IS NOT NULL AND is_deleted = 0;
+
+INSERT OR IGNORE INTO scheduled_jobs (job_key, description, interval_minutes, run_at_startup)
+VALUES ('generate_standing_orders', 'Create work orders for standing orders due in the next four weeks', 1440, 0);
@@ -0,0 +1,14 @@
+-- Trucks and trailers the crew delivers with. A day's capacity is the cords these can carry over
+-- their trips, limited to as many vehicles as there are drivers available that day.
+CREATE TABLE IF NOT EXISTS vehicles (
+  id TEXT PRIMARY KEY NOT NULL,
+  name TEXT NOT NULL,
+  capacity_cords REAL NOT NULL, -- cords per trip
+  trips_per_day INTEGER NOT NULL DEFAULT 1,
+  notes TEXT,
+  is_active INTEGER NOT NULL DEFAULT 1,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+  version INTEGER NOT NULL DEFAULT 0,
+  is_deleted INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,34 @@
+-- Who is on a schedule event that has no work order, keyed by users.id. Events for an order take
+-- their crew from work_order_assignments; assigned_user_ids_json stays as the display list.
+CREATE TABLE IF NOT EXISTS delivery_event_assignments (
+  id TEXT PRIMARY KEY NOT NULL,
+  delivery_event_id TEXT NOT NULL,
+  user_id TEXT NOT NULL,
+  created_at TEXT NOT NULL DEFAULT (datetime('now')),
+  created_by TEXT,
+  UNIQUE (delivery_event_id, user_id),
+  FOREIGN KEY (delivery_event_id) REFERENCES delivery_events(id),
+  FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_delivery_event_assignments_user
+  ON delivery_event_assignments(user_id, delivery_event_id);
+
+-- Entries were user ids, usernames or display names; match any, case-insensitively.
+INSERT OR IGNORE INTO delivery_event_assignments (id, delivery_event_id, user_id)
+SELECT lower(hex(randomblob(16))), e.id, u.id
+FROM delivery_events e
+JOIN json_each(
+  CASE WHEN json_valid(e.assigned_user_ids_json) THEN e.assigned_user_ids_json ELSE '[]' END
+) j
+JOIN users u
+  ON u.is_deleted = 0
+ AND (
+   u.id = trim(j.value)
+   OR lower(u.name) = lower(trim(j.value))
+   OR EXISTS (
+     SELECT 1 FROM auth_users a
+     WHERE a.user_id = u.id AND a.is_deleted = 0 AND lower(a.username) = lower(trim(j.value))
+   )
+ )
+WHERE e.is_deleted = 0 AND e.work_order_id IS NULL;
@@ -0,0 +1,18 @@
+-- Delivered orders now keep their reservation until completion takes the wood off hand. Orders
+-- already delivered released theirs under the old rules, so reserve their cords again; otherwise
+-- completing them would release the same cords twice.
+UPDATE inventory_items
+SET reserved_quantity = reserved_quantity + (
+      SELECT COALESCE(SUM(delivery_size_cords), 0)
+      FROM work_orders
+      WHERE is_deleted = 0 AND lower(status) = 'delivered'
+    ),
+    updated_at = datetime('now')
+WHERE id = (
+  SELECT id
+  FROM inventory_items
+  WHERE is_deleted = 0
+    AND (lower(unit) LIKE '%cord%' OR lower(name) LIKE '%wood%')
+  ORDER BY created_at ASC
+  LIMIT 1
+);
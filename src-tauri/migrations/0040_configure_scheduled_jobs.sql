@@ -0,0 +1,22 @@
+-- Each scheduled job names the built-in handler it runs and that handler's settings, so admins can
+-- tune a job (or schedule a handler twice) without a code change.
+ALTER TABLE scheduled_jobs ADD COLUMN handler TEXT;
+ALTER TABLE scheduled_jobs ADD COLUMN settings_json TEXT NOT NULL DEFAULT '{}';
+
+UPDATE scheduled_jobs SET handler = job_key WHERE handler IS NULL;
+
+UPDATE scheduled_jobs
+SET settings_json = '{"warning_days":30}'
+WHERE job_key = 'driver_license_expiry';
+
+UPDATE scheduled_jobs
+SET settings_json = '{"horizon_days":28}'
+WHERE job_key = 'generate_standing_orders';
+
+INSERT OR IGNORE INTO scheduled_jobs
+  (job_key, description, handler, settings_json, interval_minutes, run_at_startup)
+VALUES
+  ('database_backup', 'Copy the database into the backups folder, keeping the newest copies',
+   'database_backup', '{"keep":14}', 1440, 0),
+  ('data_retention', 'Delete old job history and long-expired messages of the day',
+   'data_retention', '{"job_run_days":90,"motd_days":30}', 1440, 0);
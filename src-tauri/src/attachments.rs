@@ -0,0 +1,144 @@
+//! Content-addressed file store for client and work order attachments.
+//! Files live under `<app data>/attachments/<first two hash chars>/<sha256 hex>`, so identical
+//! uploads share one blob and a file's name doubles as its integrity check.
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn is_hash_name(name: &str) -> bool {
+    name.len() == 64
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase())
+}
+
+pub fn blob_path(root: &Path, hash: &str) -> Result<PathBuf, String> {
+    if !is_hash_name(hash) {
+        return Err(format!("Invalid attachment hash '{}'", hash));
+    }
+    Ok(root.join(&hash[..2]).join(hash))
+}
+
+/// Writes `bytes` into the store (no-op when the blob already exists) and returns its hash.
+pub fn store_blob(root: &Path, bytes: &[u8]) -> Result<String, String> {
+    let hash = sha256_hex(bytes);
+    let path = blob_path(root, &hash)?;
+    if path.exists() {
+        return Ok(hash);
+    }
+    let dir = path.parent().expect("blob path has a shard directory");
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    // Write to a temp name first so a crash never leaves a truncated blob under a valid hash.
+    let tmp = dir.join(format!("{}.tmp", hash));
+    fs::write(&tmp, bytes).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+    Ok(hash)
+}
+
+pub fn read_blob(root: &Path, hash: &str) -> Result<Vec<u8>, String> {
+    let path = blob_path(root, hash)?;
+    fs::read(&path).map_err(|e| format!("Attachment file {} unreadable: {}", hash, e))
+}
+
+pub enum BlobState {
+    Ok,
+    Missing,
+    Corrupted,
+}
+
+pub fn verify_blob(root: &Path, hash: &str) -> Result<BlobState, String> {
+    let path = blob_path(root, hash)?;
+    let mut file = match fs::File::open(&path) {
+        Ok(f) => f,
+        Err(_) => return Ok(BlobState::Missing),
+    };
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    let actual: String = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Ok(if actual == hash {
+        BlobState::Ok
+    } else {
+        BlobState::Corrupted
+    })
+}
+
+/// Every blob in the store as (hash, size in bytes). Leftover temp files are reported too so
+/// orphan cleanup can remove them.
+pub fn list_blobs(root: &Path) -> Result<Vec<(String, u64)>, String> {
+    let mut blobs = Vec::new();
+    let shards = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(blobs),
+    };
+    for shard in shards {
+        let shard = shard.map_err(|e| e.to_string())?;
+        if !shard.file_type().map_err(|e| e.to_string())?.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(shard.path()).map_err(|e| e.to_string())? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let size = entry.metadata().map_err(|e| e.to_string())?.len();
+            blobs.push((entry.file_name().to_string_lossy().to_string(), size));
+        }
+    }
+    Ok(blobs)
+}
+
+/// Removes a file previously returned by `list_blobs` (a hash or a leftover temp name).
+pub fn remove_blob_file(root: &Path, name: &str) -> Result<(), String> {
+    let shard = name
+        .get(..2)
+        .ok_or_else(|| format!("Invalid blob name '{}'", name))?;
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(format!("Invalid blob name '{}'", name));
+    }
+    fs::remove_file(root.join(shard).join(name)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stores_deduplicates_and_verifies() {
+        let root = std::env::temp_dir().join(format!("fwb-attachments-{}", uuid::Uuid::new_v4()));
+        let hash = store_blob(&root, b"proof of residence").unwrap();
+        assert_eq!(store_blob(&root, b"proof of residence").unwrap(), hash);
+        assert_eq!(list_blobs(&root).unwrap(), vec![(hash.clone(), 18)]);
+        assert_eq!(read_blob(&root, &hash).unwrap(), b"proof of residence");
+        assert!(matches!(verify_blob(&root, &hash).unwrap(), BlobState::Ok));
+
+        fs::write(blob_path(&root, &hash).unwrap(), b"tampered").unwrap();
+        assert!(matches!(
+            verify_blob(&root, &hash).unwrap(),
+            BlobState::Corrupted
+        ));
+        remove_blob_file(&root, &hash).unwrap();
+        assert!(matches!(
+            verify_blob(&root, &hash).unwrap(),
+            BlobState::Missing
+        ));
+        assert!(blob_path(&root, "../etc/passwd").is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+//! Offline distance estimates from ZIP/city centroids.
+//! Straight-line (haversine) miles are multiplied by a configurable road factor, since
+//! rural roads rarely run straight to a house.
+
+/// The dataset compiled into the app: ZIPs in the northern New Mexico service area with their
+/// town and state. `data/fetch_zip_centroids.sh` rebuilds it from the GeoNames postal code file.
+pub const BUNDLED_ZIP_CENTROIDS: &str = include_str!("../data/zip_centroids.csv");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Centroid {
+    pub zip: String,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+pub fn haversine_miles(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    const EARTH_RADIUS_MILES: f64 = 3958.8;
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_MILES * a.sqrt().asin()
+}
+
+/// Estimated one-way road miles, rounded to a tenth of a mile.
+pub fn estimate_road_miles(yard: (f64, f64), destination: (f64, f64), road_factor: f64) -> f64 {
+    let miles = haversine_miles(yard.0, yard.1, destination.0, destination.1) * road_factor;
+    (miles * 10.0).round() / 10.0
+}
+
+/// Parses either the app's `zip,city,state,latitude,longitude` CSV or a Census Gazetteer ZCTA
+/// file (tab-separated `GEOID ... INTPTLAT INTPTLONG`). Header names are matched
+/// case-insensitively; rows with unusable coordinates are skipped and counted.
+pub fn parse_centroids(text: &str) -> Result<(Vec<Centroid>, usize), String> {
+    let first_line = text.lines().next().unwrap_or_default();
+    let delimiter = if first_line.contains('\t') {
+        b'\t'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let find = |names: &[&str]| headers.iter().position(|h| names.contains(&h.as_str()));
+    let zip_col = find(&[
+        "zip",
+        "zipcode",
+        "zip_code",
+        "postal_code",
+        "geoid",
+        "zcta5",
+    ])
+    .ok_or_else(|| "Centroid file needs a zip (or GEOID) column".to_string())?;
+    let lat_col = find(&["latitude", "lat", "intptlat"])
+        .ok_or_else(|| "Centroid file needs a latitude (or INTPTLAT) column".to_string())?;
+    let lon_col = find(&["longitude", "lon", "lng", "intptlong"])
+        .ok_or_else(|| "Centroid file needs a longitude (or INTPTLONG) column".to_string())?;
+    let city_col = find(&["city", "primary_city", "place"]);
+    let state_col = find(&["state", "state_code", "usps"]);
+
+    let mut centroids = Vec::new();
+    let mut skipped = 0;
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let field = |col: Option<usize>| {
+            col.and_then(|c| record.get(c))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let zip = field(Some(zip_col)).unwrap_or_default();
+        let latitude = field(Some(lat_col)).and_then(|v| v.parse::<f64>().ok());
+        let longitude = field(Some(lon_col)).and_then(|v| v.parse::<f64>().ok());
+        match (latitude, longitude) {
+            (Some(latitude), Some(longitude))
+                if zip.len() == 5
+                    && zip.bytes().all(|b| b.is_ascii_digit())
+                    && (-90.0..=90.0).contains(&latitude)
+                    && (-180.0..=180.0).contains(&longitude) =>
+            {
+                centroids.push(Centroid {
+                    zip,
+                    city: field(city_col).map(|c| crate::validation::init_cap_city(&c)),
+                    state: field(state_col).map(|s| s.to_uppercase()),
+                    latitude,
+                    longitude,
+                });
+            }
+            _ => skipped += 1,
+        }
+    }
+    Ok((centroids, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_datasets_and_estimates_miles() {
+        // One degree of latitude is ~69.1 miles anywhere on the globe.
+        let straight = haversine_miles(35.0, -106.0, 36.0, -106.0);
+        assert!((straight - 69.09).abs() < 0.05);
+        assert_eq!(
+            estimate_road_miles((35.0, -106.0), (36.0, -106.0), 1.3),
+            89.8
+        );
+
+        let (rows, skipped) = parse_centroids(
+            "zip,city,state,latitude,longitude\n12345,SOME TOWN,nm,35.5,-105.9\n1234,Bad,NM,1,1\n",
+        )
+        .unwrap();
+        assert_eq!(skipped, 1);
+        assert_eq!(rows[0].city.as_deref(), Some("Some Town"));
+        assert_eq!(rows[0].state.as_deref(), Some("NM"));
+
+        let (gazetteer, _) = parse_centroids(
+            "GEOID\tALAND\tAWATER\tALAND_SQMI\tAWATER_SQMI\tINTPTLAT\tINTPTLONG\n\
+             54321\t1\t0\t0\t0\t35.25\t-106.5\n",
+        )
+        .unwrap();
+        assert_eq!(
+            (gazetteer[0].latitude, gazetteer[0].city.clone()),
+            (35.25, None)
+        );
+        assert!(parse_centroids(BUNDLED_ZIP_CENTROIDS).is_ok());
+    }
+}
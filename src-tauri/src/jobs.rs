@@ -0,0 +1,714 @@
+//! Background jobs run on the Tauri async runtime. Jobs are rows in `scheduled_jobs`: each names one
+//! of the built-in handlers below along with its settings, interval and whether it is enabled, and
+//! every run is kept in `job_runs`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+use uuid::Uuid;
+
+type JobFuture<'a> = Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+
+/// What a handler gets for one run: the job's settings from `settings_json`, the app data folder
+/// and the role and actor to audit as.
+pub struct JobContext<'a> {
+    pub pool: &'a SqlitePool,
+    pub data_dir: &'a Path,
+    pub job_key: &'a str,
+    pub settings: Value,
+    pub role: &'a str,
+    pub actor: &'a str,
+}
+
+impl JobContext<'_> {
+    fn setting(&self, name: &str, default: i64) -> i64 {
+        self.settings
+            .get(name)
+            .and_then(Value::as_i64)
+            .unwrap_or(default)
+    }
+}
+
+/// Built-in work a job can run. `run` returns the text kept as the run's output.
+pub struct Handler {
+    pub key: &'static str,
+    run: for<'a> fn(&'a JobContext<'a>) -> JobFuture<'a>,
+}
+
+/// Every handler a `scheduled_jobs` row may name in its `handler` column.
+pub const HANDLERS: &[Handler] = &[
+    Handler {
+        key: "reconcile_schedule",
+        run: |ctx| Box::pin(reconcile_schedule(ctx)),
+    },
+    Handler {
+        key: "driver_license_expiry",
+        run: |ctx| Box::pin(driver_license_expiry(ctx)),
+    },
+    Handler {
+        key: "generate_standing_orders",
+        run: |ctx| Box::pin(generate_standing_orders(ctx)),
+    },
+    Handler {
+        key: "database_backup",
+        run: |ctx| Box::pin(database_backup(ctx)),
+    },
+    Handler {
+        key: "data_retention",
+        run: |ctx| Box::pin(data_retention(ctx)),
+    },
+];
+
+fn find_handler(handler: &str) -> Option<&'static Handler> {
+    HANDLERS.iter().find(|known| known.key == handler)
+}
+
+/// How often the scheduler looks for due jobs.
+const TICK: Duration = Duration::from_secs(60);
+/// A lock older than this is assumed to belong to a run that died with the app.
+const LOCK_TIMEOUT_MINUTES: i64 = 120;
+const LICENSE_WARNING_DAYS: i64 = 30;
+const BACKUPS_KEPT: i64 = 14;
+const JOB_RUN_RETENTION_DAYS: i64 = 90;
+const MOTD_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct ScheduledJob {
+    pub job_key: String,
+    pub handler: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub interval_minutes: i64,
+    pub run_at_startup: bool,
+    pub settings_json: String,
+    pub last_started_at: Option<String>,
+    pub last_finished_at: Option<String>,
+    pub last_outcome: Option<String>,
+    pub locked_at: Option<String>,
+    pub locked_by: Option<String>,
+}
+
+/// Changes an existing job, or adds a new one when `job_key` is not configured yet and `handler`
+/// names a built-in handler.
+#[derive(Debug, Deserialize)]
+pub struct ScheduledJobInput {
+    pub job_key: String,
+    pub handler: Option<String>,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub interval_minutes: i64,
+    pub run_at_startup: Option<bool>,
+    pub settings: Option<Value>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct JobRun {
+    pub id: String,
+    pub job_key: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub outcome: String,
+    pub output: Option<String>,
+    pub triggered_by: Option<String>,
+}
+
+pub async fn list_jobs(pool: &SqlitePool) -> Result<Vec<ScheduledJob>, String> {
+    sqlx::query_as::<_, ScheduledJob>(
+        r#"
+        SELECT job_key, COALESCE(handler, job_key) AS handler, description, enabled,
+               interval_minutes, run_at_startup, settings_json,
+               last_started_at, last_finished_at, last_outcome, locked_at, locked_by
+        FROM scheduled_jobs
+        ORDER BY job_key
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+async fn find_job(pool: &SqlitePool, job_key: &str) -> Result<Option<ScheduledJob>, String> {
+    Ok(list_jobs(pool)
+        .await?
+        .into_iter()
+        .find(|job| job.job_key == job_key))
+}
+
+pub async fn list_runs(
+    pool: &SqlitePool,
+    job_key: Option<&str>,
+    limit: i64,
+) -> Result<Vec<JobRun>, String> {
+    sqlx::query_as::<_, JobRun>(
+        r#"
+        SELECT id, job_key, started_at, finished_at, outcome, output, triggered_by
+        FROM job_runs
+        WHERE ?1 IS NULL OR job_key = ?1
+        ORDER BY started_at DESC, rowid DESC
+        LIMIT ?2
+        "#,
+    )
+    .bind(job_key)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the previous settings so the caller can audit the change, or `None` for a new job.
+pub async fn update_job(
+    pool: &SqlitePool,
+    input: &ScheduledJobInput,
+    actor: &str,
+) -> Result<Option<ScheduledJob>, String> {
+    if input.interval_minutes < 1 {
+        return Err("Jobs must run at most once a minute".to_string());
+    }
+    let settings_json = match &input.settings {
+        Some(settings) if !settings.is_object() => {
+            return Err("Job settings must be a JSON object".to_string());
+        }
+        Some(settings) => Some(settings.to_string()),
+        None => None,
+    };
+    let previous = find_job(pool, &input.job_key).await?;
+    let Some(previous) = previous else {
+        let handler = input
+            .handler
+            .as_deref()
+            .ok_or_else(|| format!("Unknown job '{}'", input.job_key))?;
+        if find_handler(handler).is_none() {
+            return Err(format!("Unknown job handler '{}'", handler));
+        }
+        if input.job_key.trim().is_empty() {
+            return Err("Jobs need a key".to_string());
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_jobs
+              (job_key, description, handler, settings_json, enabled, interval_minutes,
+               run_at_startup, updated_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(input.job_key.trim())
+        .bind(&input.description)
+        .bind(handler)
+        .bind(settings_json.as_deref().unwrap_or("{}"))
+        .bind(input.enabled)
+        .bind(input.interval_minutes)
+        .bind(input.run_at_startup.unwrap_or(false))
+        .bind(actor)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        return Ok(None);
+    };
+    if let Some(handler) = input.handler.as_deref() {
+        if handler != previous.handler {
+            return Err(format!(
+                "Job '{}' runs '{}'; add a new job to run '{}'",
+                input.job_key, previous.handler, handler
+            ));
+        }
+    }
+    sqlx::query(
+        r#"
+        UPDATE scheduled_jobs
+        SET enabled = ?, interval_minutes = ?, run_at_startup = ?,
+            description = COALESCE(?, description),
+            settings_json = COALESCE(?, settings_json),
+            updated_by = ?, updated_at = datetime('now')
+        WHERE job_key = ?
+        "#,
+    )
+    .bind(input.enabled)
+    .bind(input.interval_minutes)
+    .bind(input.run_at_startup.unwrap_or(previous.run_at_startup))
+    .bind(&input.description)
+    .bind(&settings_json)
+    .bind(actor)
+    .bind(&input.job_key)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(Some(previous))
+}
+
+/// Enabled jobs whose interval has passed; on launch, also those marked to run at startup.
+pub async fn due_jobs(pool: &SqlitePool, at_startup: bool) -> Result<Vec<String>, String> {
+    let jobs: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT job_key, COALESCE(handler, job_key)
+        FROM scheduled_jobs
+        WHERE enabled = 1
+          AND (
+            last_started_at IS NULL
+            OR datetime(last_started_at, '+' || interval_minutes || ' minutes') <= datetime('now')
+            OR (?1 AND run_at_startup = 1)
+          )
+        ORDER BY job_key
+        "#,
+    )
+    .bind(at_startup)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(jobs
+        .into_iter()
+        .filter(|(_, handler)| find_handler(handler).is_some())
+        .map(|(job_key, _)| job_key)
+        .collect())
+}
+
+/// Takes the job's lock in a single UPDATE, so two callers (or two app instances on the same
+/// database) can never both start the same job.
+async fn claim(pool: &SqlitePool, job_key: &str, owner: &str) -> Result<bool, String> {
+    let claimed = sqlx::query(
+        r#"
+        UPDATE scheduled_jobs
+        SET locked_at = datetime('now'), locked_by = ?, last_started_at = datetime('now')
+        WHERE job_key = ?
+          AND (locked_at IS NULL OR locked_at <= datetime('now', ?))
+        "#,
+    )
+    .bind(owner)
+    .bind(job_key)
+    .bind(format!("-{} minutes", LOCK_TIMEOUT_MINUTES))
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(claimed.rows_affected() == 1)
+}
+
+async fn reconcile_schedule(ctx: &JobContext<'_>) -> Result<String, String> {
+    let report = crate::run_schedule_reconciliation(ctx.pool, ctx.role, ctx.actor).await?;
+    serde_json::to_string(&report).map_err(|e| e.to_string())
+}
+
+async fn generate_standing_orders(ctx: &JobContext<'_>) -> Result<String, String> {
+    let report = crate::generate_standing_orders_through(
+        ctx.pool,
+        chrono::Local::now().date_naive(),
+        ctx.setting("horizon_days", crate::STANDING_ORDER_HORIZON_DAYS),
+        ctx.role,
+        ctx.actor,
+    )
+    .await?;
+    serde_json::to_string(&report).map_err(|e| e.to_string())
+}
+
+/// Runs one job now, recording the run. Fails without running if the job is already in progress.
+pub async fn run_job(
+    pool: &SqlitePool,
+    data_dir: &Path,
+    job_key: &str,
+    role: &str,
+    actor: &str,
+) -> Result<JobRun, String> {
+    let job = find_job(pool, job_key)
+        .await?
+        .ok_or_else(|| format!("Unknown job '{}'", job_key))?;
+    let handler = find_handler(&job.handler)
+        .ok_or_else(|| format!("Unknown job handler '{}'", job.handler))?;
+    let settings: Value = serde_json::from_str(&job.settings_json)
+        .map_err(|e| format!("Job '{}' has invalid settings: {}", job_key, e))?;
+    if !claim(pool, job_key, actor).await? {
+        return Err(format!("Job '{}' is already running", job_key));
+    }
+    let run_id = Uuid::new_v4().to_string();
+    let started = sqlx::query("INSERT INTO job_runs (id, job_key, triggered_by) VALUES (?, ?, ?)")
+        .bind(&run_id)
+        .bind(job_key)
+        .bind(actor)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string());
+
+    let context = JobContext {
+        pool,
+        data_dir,
+        job_key,
+        settings,
+        role,
+        actor,
+    };
+    let result = match started {
+        Ok(_) => (handler.run)(&context).await,
+        Err(e) => Err(e),
+    };
+    let (outcome, output) = match result {
+        Ok(output) => ("succeeded", output),
+        Err(error) => ("failed", error),
+    };
+    sqlx::query(
+        r#"
+        UPDATE job_runs
+        SET finished_at = datetime('now'), outcome = ?, output = ?
+        WHERE id = ?
+        "#,
+    )
+    .bind(outcome)
+    .bind(&output)
+    .bind(&run_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        UPDATE scheduled_jobs
+        SET locked_at = NULL, locked_by = NULL,
+            last_finished_at = datetime('now'), last_outcome = ?
+        WHERE job_key = ?
+        "#,
+    )
+    .bind(outcome)
+    .bind(job_key)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query_as::<_, JobRun>(
+        r#"
+        SELECT id, job_key, started_at, finished_at, outcome, output, triggered_by
+        FROM job_runs
+        WHERE id = ?
+        "#,
+    )
+    .bind(&run_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Starts the scheduler loop. Runs are best effort: a failure is recorded in `job_runs` and the
+/// job is tried again when it is next due.
+pub fn start(pool: SqlitePool, data_dir: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        let mut at_startup = true;
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            if let Ok(keys) = due_jobs(&pool, at_startup).await {
+                for key in keys {
+                    let _ = run_job(&pool, &data_dir, &key, "system", "scheduler").await;
+                }
+            }
+            at_startup = false;
+        }
+    });
+}
+
+/// Lists driver licenses that have expired or expire soon, and keeps them posted as a message of
+/// the day (one per job, replaced on every run and withdrawn once nothing is due).
+async fn driver_license_expiry(ctx: &JobContext<'_>) -> Result<String, String> {
+    #[derive(FromRow)]
+    struct ExpiringLicense {
+        name: String,
+        driver_license_expires_on: String,
+        expired: bool,
+    }
+
+    let warning_days = ctx.setting("warning_days", LICENSE_WARNING_DAYS);
+    let rows = sqlx::query_as::<_, ExpiringLicense>(
+        r#"
+        SELECT name, driver_license_expires_on,
+               date(driver_license_expires_on) < date('now') AS expired
+        FROM users
+        WHERE is_deleted = 0
+          AND is_driver = 1
+          AND driver_license_expires_on IS NOT NULL
+          AND date(driver_license_expires_on) <= date('now', ?)
+        ORDER BY date(driver_license_expires_on), name
+        "#,
+    )
+    .bind(format!("+{} days", warning_days))
+    .fetch_all(ctx.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let motd_id = format!("job:{}", ctx.job_key);
+    if rows.is_empty() {
+        sqlx::query(
+            "UPDATE motd SET is_deleted = 1, updated_at = datetime('now') WHERE id = ? AND is_deleted = 0",
+        )
+        .bind(&motd_id)
+        .execute(ctx.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        return Ok(format!(
+            "No driver licenses expire within {} days",
+            warning_days
+        ));
+    }
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let verb = if row.expired { "expired" } else { "expires" };
+            format!("{}: {} {}", row.name, verb, row.driver_license_expires_on)
+        })
+        .collect();
+    sqlx::query(
+        r#"
+        INSERT INTO motd (id, message, active_from, active_to, created_by_user_id)
+        VALUES (?, ?, NULL, NULL, NULL)
+        ON CONFLICT(id) DO UPDATE SET
+          message = excluded.message,
+          is_deleted = 0,
+          updated_at = datetime('now'),
+          version = version + 1
+        "#,
+    )
+    .bind(&motd_id)
+    .bind(format!("Driver licenses to renew: {}", lines.join("; ")))
+    .execute(ctx.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(lines.join("\n"))
+}
+
+/// Copies the database into `<app data>/backups` with `VACUUM INTO`, which is safe while the app
+/// is using it, then deletes all but the newest `keep` copies.
+async fn database_backup(ctx: &JobContext<'_>) -> Result<String, String> {
+    let keep = ctx.setting("keep", BACKUPS_KEPT).max(1) as usize;
+    let dir = ctx.data_dir.join("backups");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "backup-{}.sqlite",
+        chrono::Local::now().format("%Y%m%d-%H%M%S%.3f")
+    ));
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(ctx.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("backup-") && name.ends_with(".sqlite"))
+        })
+        .collect();
+    backups.sort();
+    let removed = backups.len().saturating_sub(keep);
+    for old in &backups[..removed] {
+        std::fs::remove_file(old).map_err(|e| e.to_string())?;
+    }
+    Ok(format!(
+        "Wrote {}; removed {} older backup(s)",
+        path.display(),
+        removed
+    ))
+}
+
+/// Deletes finished job runs and messages of the day that ended (or were deleted) longer ago
+/// than the configured number of days. Audit logs are never pruned here.
+async fn data_retention(ctx: &JobContext<'_>) -> Result<String, String> {
+    let job_run_days = ctx.setting("job_run_days", JOB_RUN_RETENTION_DAYS);
+    let motd_days = ctx.setting("motd_days", MOTD_RETENTION_DAYS);
+    let runs = sqlx::query(
+        r#"
+        DELETE FROM job_runs
+        WHERE finished_at IS NOT NULL
+          AND datetime(started_at) < datetime('now', ?)
+        "#,
+    )
+    .bind(format!("-{} days", job_run_days))
+    .execute(ctx.pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    let messages = sqlx::query(
+        r#"
+        DELETE FROM motd
+        WHERE (is_deleted = 1 AND datetime(updated_at) < datetime('now', ?1))
+           OR (active_to IS NOT NULL AND datetime(active_to) < datetime('now', ?1))
+        "#,
+    )
+    .bind(format!("-{} days", motd_days))
+    .execute(ctx.pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    Ok(format!(
+        "Deleted {} job run(s) older than {} days and {} message(s) of the day older than {} days",
+        runs, job_run_days, messages, motd_days
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn jobs_run_once_at_a_time_and_keep_history() {
+        // VACUUM INTO from an in-memory database writes another in-memory database, so the
+        // backup needs a database on disk.
+        let data_dir = std::env::temp_dir().join(format!("jobs-test-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let options = sqlx::sqlite::SqliteConnectOptions::new()
+            .filename(data_dir.join("app.db"))
+            .create_if_missing(true);
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, name, role, is_driver, driver_license_expires_on)
+            VALUES ('u1', 'Ada Lovelace', 'volunteer', 1, date('now', '-1 day')),
+                   ('u2', 'Grace Hopper', 'volunteer', 1, date('now', '+1 year'));
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(
+            due_jobs(&pool, false).await.unwrap(),
+            vec![
+                "data_retention",
+                "database_backup",
+                "driver_license_expiry",
+                "generate_standing_orders",
+                "reconcile_schedule"
+            ]
+        );
+        let run = run_job(&pool, &data_dir, "driver_license_expiry", "admin", "sam")
+            .await
+            .unwrap();
+        assert_eq!(run.outcome, "succeeded");
+        let output = run.output.unwrap();
+        assert!(output.contains("Ada Lovelace: expired"));
+        assert!(!output.contains("Grace Hopper"));
+        // The reminder is posted where everyone sees it, and withdrawn once nothing is due.
+        let motd: String = sqlx::query_scalar(
+            "SELECT message FROM motd WHERE id = 'job:driver_license_expiry' AND is_deleted = 0",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert!(motd.contains("Ada Lovelace: expired"));
+        sqlx::query(
+            "UPDATE users SET driver_license_expires_on = date('now', '+2 years') WHERE id = 'u1'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        run_job(&pool, &data_dir, "driver_license_expiry", "admin", "sam")
+            .await
+            .unwrap();
+        let shown: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM motd WHERE id = 'job:driver_license_expiry' AND is_deleted = 0",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(shown, 0);
+
+        let run = run_job(&pool, &data_dir, "generate_standing_orders", "admin", "sam")
+            .await
+            .unwrap();
+        assert_eq!(run.outcome, "succeeded");
+
+        // Settings come from the job's row: keep only the newest backup.
+        let backup = ScheduledJobInput {
+            job_key: "database_backup".to_string(),
+            handler: None,
+            description: None,
+            enabled: true,
+            interval_minutes: 1440,
+            run_at_startup: None,
+            settings: Some(serde_json::json!({ "keep": 1 })),
+        };
+        let previous = update_job(&pool, &backup, "sam").await.unwrap().unwrap();
+        assert_eq!(previous.settings_json, r#"{"keep":14}"#);
+        for _ in 0..2 {
+            let run = run_job(&pool, &data_dir, "database_backup", "admin", "sam")
+                .await
+                .unwrap();
+            assert_eq!(run.outcome, "succeeded", "{:?}", run.output);
+        }
+        let backups = std::fs::read_dir(data_dir.join("backups")).unwrap().count();
+        assert_eq!(backups, 1);
+
+        sqlx::query(
+            "INSERT INTO job_runs (id, job_key, started_at, finished_at, outcome) VALUES ('old', 'reconcile_schedule', datetime('now', '-200 days'), datetime('now', '-200 days'), 'succeeded')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let run = run_job(&pool, &data_dir, "data_retention", "admin", "sam")
+            .await
+            .unwrap();
+        assert!(run.output.unwrap().starts_with("Deleted 1 job run(s)"));
+        assert_eq!(
+            due_jobs(&pool, false).await.unwrap(),
+            vec!["reconcile_schedule"]
+        );
+
+        // Someone else holds the lock; a lock left behind by a crashed run eventually expires.
+        sqlx::query(
+            "UPDATE scheduled_jobs SET locked_at = datetime('now'), locked_by = 'other' WHERE job_key = 'reconcile_schedule'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(
+            run_job(&pool, &data_dir, "reconcile_schedule", "admin", "sam")
+                .await
+                .is_err()
+        );
+        sqlx::query(
+            "UPDATE scheduled_jobs SET locked_at = datetime('now', '-1 day') WHERE job_key = 'reconcile_schedule'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let run = run_job(&pool, &data_dir, "reconcile_schedule", "admin", "sam")
+            .await
+            .unwrap();
+        assert_eq!(run.outcome, "succeeded");
+        assert!(due_jobs(&pool, false).await.unwrap().is_empty());
+        assert_eq!(
+            due_jobs(&pool, true).await.unwrap(),
+            vec!["reconcile_schedule"]
+        );
+
+        // New jobs are added as configuration, but only for handlers that exist.
+        let mut weekly = ScheduledJobInput {
+            job_key: "weekly_backup".to_string(),
+            handler: Some("offsite_backup".to_string()),
+            description: Some("Weekly copy".to_string()),
+            enabled: true,
+            interval_minutes: 10080,
+            run_at_startup: None,
+            settings: Some(serde_json::json!({ "keep": 4 })),
+        };
+        assert!(update_job(&pool, &weekly, "sam").await.is_err());
+        weekly.handler = Some("database_backup".to_string());
+        assert!(update_job(&pool, &weekly, "sam").await.unwrap().is_none());
+        assert_eq!(due_jobs(&pool, false).await.unwrap(), vec!["weekly_backup"]);
+
+        assert!(run_job(&pool, &data_dir, "backup", "admin", "sam")
+            .await
+            .is_err());
+        let runs = list_runs(&pool, Some("reconcile_schedule"), 10)
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+        let jobs = list_jobs(&pool).await.unwrap();
+        assert!(jobs.iter().all(|job| job.locked_at.is_none()));
+        pool.close().await;
+        let _ = std::fs::remove_dir_all(&data_dir);
+    }
+}
@@ -0,0 +1,282 @@
+//! Mailing list rendering: CSV, vCard and Avery-style label sheets (PDF).
+//! The PDF writer is intentionally minimal (Helvetica text only) so labels need no extra crates.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MailingAddress {
+    pub client_id: String,
+    pub name: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub line1: String,
+    pub line2: Option<String>,
+    pub city: String,
+    pub state: String,
+    pub postal_code: String,
+}
+
+impl MailingAddress {
+    fn label_lines(&self) -> Vec<String> {
+        let mut lines = vec![self.name.clone(), self.line1.clone()];
+        if let Some(line2) = self.line2.as_deref().filter(|l| !l.trim().is_empty()) {
+            lines.push(line2.to_string());
+        }
+        lines.push(format!(
+            "{}, {} {}",
+            self.city, self.state, self.postal_code
+        ));
+        lines
+    }
+}
+
+/// Sheet geometry in PDF points (1/72 inch) on US Letter paper.
+pub struct LabelTemplate {
+    pub columns: usize,
+    pub rows: usize,
+    pub left_margin: f64,
+    pub top_margin: f64,
+    pub column_pitch: f64,
+    pub row_pitch: f64,
+    pub font_size: f64,
+}
+
+pub fn label_template(name: &str) -> Option<LabelTemplate> {
+    match name {
+        // 1" x 2-5/8", 30 per sheet
+        "5160" => Some(LabelTemplate {
+            columns: 3,
+            rows: 10,
+            left_margin: 13.5,
+            top_margin: 36.0,
+            column_pitch: 198.0,
+            row_pitch: 72.0,
+            font_size: 9.0,
+        }),
+        // 2" x 4", 10 per sheet
+        "5163" => Some(LabelTemplate {
+            columns: 2,
+            rows: 5,
+            left_margin: 11.25,
+            top_margin: 36.0,
+            column_pitch: 297.0,
+            row_pitch: 144.0,
+            font_size: 12.0,
+        }),
+        _ => None,
+    }
+}
+
+pub fn to_csv(addresses: &[MailingAddress]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record([
+            "name",
+            "address_line1",
+            "address_line2",
+            "city",
+            "state",
+            "postal_code",
+        ])
+        .map_err(|e| e.to_string())?;
+    for a in addresses {
+        writer
+            .write_record([
+                a.name.as_str(),
+                a.line1.as_str(),
+                a.line2.as_deref().unwrap_or(""),
+                a.city.as_str(),
+                a.state.as_str(),
+                a.postal_code.as_str(),
+            ])
+            .map_err(|e| e.to_string())?;
+    }
+    writer.into_inner().map_err(|e| e.to_string())
+}
+
+fn vcard_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+pub fn to_vcard(addresses: &[MailingAddress]) -> Vec<u8> {
+    let mut out = String::new();
+    for a in addresses {
+        let street = match a.line2.as_deref().filter(|l| !l.trim().is_empty()) {
+            Some(line2) => format!("{}\\n{}", vcard_escape(&a.line1), vcard_escape(line2)),
+            None => vcard_escape(&a.line1),
+        };
+        out.push_str("BEGIN:VCARD\r\nVERSION:3.0\r\n");
+        out.push_str(&format!(
+            "N:{};{};;;\r\n",
+            vcard_escape(a.last_name.as_deref().unwrap_or("")),
+            vcard_escape(a.first_name.as_deref().unwrap_or(""))
+        ));
+        out.push_str(&format!("FN:{}\r\n", vcard_escape(&a.name)));
+        out.push_str(&format!(
+            "ADR;TYPE=postal:;;{};{};{};{};USA\r\n",
+            street,
+            vcard_escape(&a.city),
+            vcard_escape(&a.state),
+            vcard_escape(&a.postal_code)
+        ));
+        out.push_str("END:VCARD\r\n");
+    }
+    out.into_bytes()
+}
+
+/// Encodes text as a PDF literal string body in WinAnsi (Latin-1 subset); other characters
+/// become '?'.
+fn pdf_text(value: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '(' | ')' | '\\' => {
+                out.push(b'\\');
+                out.push(ch as u8);
+            }
+            c if (' '..='~').contains(&c) || ('\u{a0}'..='\u{ff}').contains(&c) => {
+                out.push(c as u8)
+            }
+            _ => out.push(b'?'),
+        }
+    }
+    out
+}
+
+pub fn to_label_pdf(addresses: &[MailingAddress], template: &LabelTemplate) -> Vec<u8> {
+    const PAGE_HEIGHT: f64 = 792.0;
+    const PAGE_WIDTH: f64 = 612.0;
+    let per_page = template.columns * template.rows;
+    let pages: Vec<&[MailingAddress]> = if addresses.is_empty() {
+        vec![&[]]
+    } else {
+        addresses.chunks(per_page).collect()
+    };
+
+    // Object layout: 1 catalog, 2 pages, 3 font, then (page, content) pairs.
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 4 + i * 2).collect();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+    objects.push(
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    );
+    objects.push(
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+    );
+
+    let leading = template.font_size + 2.0;
+    for (page_index, page) in pages.iter().enumerate() {
+        let mut stream: Vec<u8> = Vec::new();
+        for (slot, address) in page.iter().enumerate() {
+            let column = slot % template.columns;
+            let row = slot / template.columns;
+            let x = template.left_margin + column as f64 * template.column_pitch + 9.0;
+            let top = PAGE_HEIGHT - template.top_margin - row as f64 * template.row_pitch;
+            let lines = address.label_lines();
+            // Vertically centre the block inside the label.
+            let block = leading * lines.len() as f64;
+            let first_baseline = top - (template.row_pitch - block) / 2.0 - template.font_size;
+            stream.extend_from_slice(
+                format!(
+                    "BT /F1 {} Tf {} TL {:.2} {:.2} Td\n",
+                    template.font_size, leading, x, first_baseline
+                )
+                .as_bytes(),
+            );
+            for (i, line) in lines.iter().enumerate() {
+                if i > 0 {
+                    stream.extend_from_slice(b"T* ");
+                }
+                stream.push(b'(');
+                stream.extend_from_slice(&pdf_text(line));
+                stream.extend_from_slice(b") Tj\n");
+            }
+            stream.extend_from_slice(b"ET\n");
+        }
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_ids[page_index] + 1
+            )
+            .into_bytes(),
+        );
+        let mut content = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        content.extend_from_slice(&stream);
+        content.extend_from_slice(b"\nendstream");
+        objects.push(content);
+    }
+
+    let mut pdf: Vec<u8> = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, body) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(body);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref_start = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_start
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(name: &str) -> MailingAddress {
+        MailingAddress {
+            client_id: "c1".to_string(),
+            name: name.to_string(),
+            first_name: Some("Ada".to_string()),
+            last_name: Some("Lovelace".to_string()),
+            line1: "1 Elm St; Apt (B)".to_string(),
+            line2: None,
+            city: "Santa Fe".to_string(),
+            state: "NM".to_string(),
+            postal_code: "87501".to_string(),
+        }
+    }
+
+    #[test]
+    fn renders_all_formats() {
+        let list: Vec<MailingAddress> =
+            (0..31).map(|i| address(&format!("Client {}", i))).collect();
+
+        let csv = String::from_utf8(to_csv(&list[..1]).unwrap()).unwrap();
+        assert!(csv.starts_with("name,address_line1"));
+        let vcard = String::from_utf8(to_vcard(&list[..1])).unwrap();
+        assert!(vcard.contains("ADR;TYPE=postal:;;1 Elm St\\; Apt (B);Santa Fe;NM;87501;USA"));
+
+        let pdf = to_label_pdf(&list, &label_template("5160").unwrap());
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4") && text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("(1 Elm St; Apt \\(B\\)) Tj"));
+    }
+}
@@ -1,7 +1,13 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod attachments;
 mod db;
+mod geo;
+mod jobs;
+mod mailing;
 mod sync;
+mod validation;
+mod work_order_status;
 
 use anyhow::Result;
 use db::init_pool;
@@ -9,10 +15,11 @@ use sync::{SyncRecord, SyncService};
 use bcrypt::{hash, verify, DEFAULT_COST};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Sqlite, SqlitePool, Transaction};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use tauri::{Manager, State};
 use uuid::Uuid;
+use work_order_status::{Requirement, StatusActor, WorkOrderStatus};
 
 async fn audit_db(pool: &SqlitePool, event: &str, role: &str, actor: &str) {
     let _ = sqlx::query(
@@ -75,6 +82,8 @@ async fn audit_change(
 #[derive(Clone)]
 struct AppState {
     pool: SqlitePool,
+    data_dir: PathBuf,
+    attachments_dir: PathBuf,
 }
 
 fn role_rank(role: &str) -> i32 {
@@ -91,6 +100,128 @@ fn is_staff_like(role: &str) -> bool {
     role == "staff" || role == "employee"
 }
 
+/// Leads create orders too, so they can override an allotment limit as they book.
+fn can_create_work_orders(role: &str) -> bool {
+    role == "admin" || role == "lead" || is_staff_like(role)
+}
+
+fn can_view_client_pii(role: &str, hipaa_certified: bool) -> bool {
+    role == "admin" || (role == "lead" && hipaa_certified)
+}
+
+/// What a caller may see in a list command. Worked out once from the session, then bound into
+/// the list query so SQLite drops the rows and blanks the columns.
+#[derive(Debug, Default)]
+struct ListScope {
+    // Set when the caller only sees orders (or their clients/events) assigned to this username.
+    assigned_to: Option<String>,
+    hide_contact: bool,
+    hide_gate: bool,
+    hide_notes: bool,
+    hide_address: bool,
+    hide_site_notes: bool,
+}
+
+impl ListScope {
+    fn for_work_orders(role: &str, username: &str, hipaa: bool, is_driver: bool) -> Self {
+        if role == "volunteer" || is_driver {
+            let volunteer = role == "volunteer";
+            ListScope {
+                assigned_to: Some(username.to_string()),
+                hide_contact: volunteer,
+                hide_gate: volunteer,
+                hide_notes: volunteer,
+                hide_address: volunteer,
+                hide_site_notes: false,
+            }
+        } else if (is_staff_like(role) || role == "lead") && !(role == "lead" && hipaa) {
+            ListScope {
+                hide_contact: true,
+                hide_gate: true,
+                hide_notes: true,
+                ..ListScope::default()
+            }
+        } else {
+            ListScope::default()
+        }
+    }
+
+    fn for_clients(role: &str, username: &str, hipaa: bool, is_driver: bool) -> Self {
+        if is_driver {
+            // Drivers do not need gate codes or notes from intake.
+            ListScope {
+                assigned_to: Some(username.to_string()),
+                hide_gate: true,
+                hide_notes: true,
+                ..ListScope::default()
+            }
+        } else if !can_view_client_pii(role, hipaa) {
+            ListScope {
+                hide_contact: true,
+                hide_gate: true,
+                hide_address: true,
+                // Site notes go to the crew on an order; volunteers get them via list_work_orders.
+                hide_site_notes: !(role == "lead" || is_staff_like(role)),
+                ..ListScope::default()
+            }
+        } else {
+            ListScope::default()
+        }
+    }
+
+    fn for_delivery_events(role: &str, username: &str, hipaa: bool, is_driver: bool) -> Self {
+        if is_driver || role == "volunteer" {
+            ListScope {
+                assigned_to: Some(username.to_string()),
+                ..ListScope::default()
+            }
+        } else {
+            // Order events are titled with the client's name.
+            ListScope {
+                hide_contact: !can_view_client_pii(role, hipaa),
+                ..ListScope::default()
+            }
+        }
+    }
+}
+
+/// The heating season (July 1 through June 30) a date falls in, labelled e.g. "2025-2026".
+fn heating_season_for_date(date: &str) -> Option<String> {
+    use chrono::Datelike;
+    let day = chrono::NaiveDate::parse_from_str(date.get(..10)?, "%Y-%m-%d").ok()?;
+    let start_year = if day.month() >= 7 {
+        day.year()
+    } else {
+        day.year() - 1
+    };
+    Some(format!("{}-{}", start_year, start_year + 1))
+}
+
+/// Inclusive start and exclusive end dates of a season given as "2025-2026" or just "2025".
+fn heating_season_bounds(season: &str) -> Result<(String, String), String> {
+    let season = season.trim();
+    let invalid = || {
+        format!(
+            "Invalid heating season '{}' (expected e.g. 2025-2026)",
+            season
+        )
+    };
+    let (start, end) = match season.split_once('-') {
+        Some((a, b)) => (
+            a.parse::<i32>().map_err(|_| invalid())?,
+            b.parse::<i32>().map_err(|_| invalid())?,
+        ),
+        None => {
+            let a = season.parse::<i32>().map_err(|_| invalid())?;
+            (a, a + 1)
+        }
+    };
+    if end != start + 1 {
+        return Err(invalid());
+    }
+    Ok((format!("{}-07-01", start), format!("{}-07-01", end)))
+}
+
 fn resolve_database_url() -> String {
     // Prefer explicit env var if provided (absolute path recommended).
     if let Ok(url) = std::env::var("DATABASE_URL") {
@@ -275,6 +406,37 @@ async fn seed_default_logins(pool: &SqlitePool) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Access and safety notes for whoever delivers. Unlike `notes` (medical/personal, PII-gated)
+/// these are shown to the drivers and helpers assigned to the client's orders.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromRow)]
+struct SiteNotes {
+    #[sqlx(rename = "site_hazards")]
+    hazards: Option<String>,
+    #[sqlx(rename = "site_drop_location")]
+    drop_location: Option<String>,
+    #[sqlx(rename = "site_vehicle_access")]
+    vehicle_access: Option<String>,
+    #[sqlx(rename = "site_preferred_contact_time")]
+    preferred_contact_time: Option<String>,
+}
+
+impl SiteNotes {
+    fn normalized(&self) -> SiteNotes {
+        let clean = |v: &Option<String>| {
+            v.as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        SiteNotes {
+            hazards: clean(&self.hazards),
+            drop_location: clean(&self.drop_location),
+            vehicle_access: clean(&self.vehicle_access),
+            preferred_contact_time: clean(&self.preferred_contact_time),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ClientInput {
     client_title: Option<String>,
@@ -304,6 +466,8 @@ struct ClientInput {
     wood_size_label: Option<String>,
     wood_size_other: Option<String>,
     directions: Option<String>,
+    household_size: Option<i64>,
+    site_notes: Option<SiteNotes>,
     created_by_user_id: Option<String>,
 }
 
@@ -337,6 +501,9 @@ struct ClientUpdateInput {
     wood_size_label: Option<String>,
     wood_size_other: Option<String>,
     directions: Option<String>,
+    // Left unchanged when omitted so older clients of this command keep the stored value.
+    household_size: Option<i64>,
+    site_notes: Option<SiteNotes>,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -349,6 +516,7 @@ struct ClientRow {
     date_of_onboarding: Option<String>,
     how_did_they_hear_about_us: Option<String>,
     referring_agency: Option<String>,
+    referring_agency_id: Option<String>,
     denial_reason: Option<String>,
     physical_address_line1: String,
     physical_address_line2: Option<String>,
@@ -367,6 +535,11 @@ struct ClientRow {
     directions: Option<String>,
     created_at: String,
     default_mileage: Option<f64>,
+    household_size: Option<i64>,
+    estimated_mileage: Option<f64>,
+    estimated_mileage_source: Option<String>,
+    #[sqlx(flatten)]
+    site_notes: SiteNotes,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -378,6 +551,100 @@ struct ClientConflictRow {
     physical_address_state: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct ClientImportInput {
+    csv_text: String,
+    // Client field name -> CSV header; unmapped fields fall back to a header of the same name.
+    column_map: Option<HashMap<String, String>>,
+    dry_run: Option<bool>,
+    created_by_user_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ClientImportRowResult {
+    row_number: u64,
+    name: String,
+    status: String, // "ready", "imported", "duplicate", "error"
+    errors: Vec<String>,
+    client_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ClientImportReport {
+    dry_run: bool,
+    committed: bool,
+    total_rows: usize,
+    ready_count: usize,
+    duplicate_count: usize,
+    error_count: usize,
+    rows: Vec<ClientImportRowResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MailingExportInput {
+    client_ids: Option<Vec<String>>,
+    approval_status: Option<String>,
+    season: Option<String>,
+    format: String, // "csv", "vcard", "labels_pdf"
+    label_template: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MailingExportResult {
+    file_name: String,
+    mime_type: String,
+    record_count: usize,
+    content: Vec<u8>,
+}
+
+#[derive(Debug, FromRow)]
+struct MailingClientRow {
+    id: String,
+    name: String,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    physical_address_line1: String,
+    physical_address_line2: Option<String>,
+    physical_address_city: String,
+    physical_address_state: String,
+    physical_address_postal_code: String,
+    mailing_address_line1: Option<String>,
+    mailing_address_line2: Option<String>,
+    mailing_address_city: Option<String>,
+    mailing_address_state: Option<String>,
+    mailing_address_postal_code: Option<String>,
+}
+
+const CLIENT_IMPORT_FIELDS: [&str; 27] = [
+    "name",
+    "first_name",
+    "last_name",
+    "client_title",
+    "physical_address_line1",
+    "physical_address_line2",
+    "physical_address_city",
+    "physical_address_state",
+    "physical_address_postal_code",
+    "mailing_address_line1",
+    "mailing_address_line2",
+    "mailing_address_city",
+    "mailing_address_state",
+    "mailing_address_postal_code",
+    "telephone",
+    "email",
+    "date_of_onboarding",
+    "how_did_they_hear_about_us",
+    "referring_agency",
+    "approval_status",
+    "denial_reason",
+    "gate_combo",
+    "notes",
+    "wood_size_label",
+    "wood_size_other",
+    "directions",
+    "household_size",
+];
+
 #[derive(Debug, Deserialize)]
 struct InventoryInput {
     name: String,
@@ -456,6 +723,11 @@ struct WorkOrderInput {
     created_by_user_id: Option<String>,
     created_by_display: Option<String>,
     paired_order_id: Option<String>,
+    allotment_override_reason: Option<String>,
+    // Set only when the order is created as the backorder of a short delivery.
+    backorder_of_id: Option<String>,
+    // Set only on orders created by the standing order generator.
+    standing_order_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -488,6 +760,11 @@ struct WorkOrderRow {
     created_at: Option<String>,
     paired_order_id: Option<String>,
     client_id: Option<String>,
+    delivered_cords: Option<f64>,
+    backorder_of_id: Option<String>,
+    // Read live from the client so a hazard noted after scheduling still reaches the crew.
+    #[sqlx(flatten)]
+    site_notes: SiteNotes,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -554,6 +831,7 @@ struct DeliveryEventRow {
     work_order_id: Option<String>,
     color_code: Option<String>,
     assigned_user_ids_json: Option<String>,
+    delivered_cords: Option<f64>,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -605,6 +883,7 @@ async fn create_client(state: State<'_, AppState>, input: ClientInput) -> Result
     let id = Uuid::new_v4().to_string();
     let approval_status = input
         .approval_status
+        .clone()
         .unwrap_or_else(|| "pending".to_string());
     audit_db(&state.pool, "create_client", "unknown", "unknown").await;
 
@@ -668,6 +947,37 @@ async fn create_client(state: State<'_, AppState>, input: ClientInput) -> Result
         }
     }
 
+    insert_client(
+        &state.pool,
+        &id,
+        &input,
+        &name,
+        &first_name,
+        &last_name,
+        &approval_status,
+    )
+    .await?;
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    link_client_agencies(&mut conn, Some(std::slice::from_ref(&id))).await?;
+    drop(conn);
+    // Best effort: a missing centroid or yard just leaves the estimate empty.
+    let _ = refresh_client_distance_estimates(&state.pool, Some(std::slice::from_ref(&id))).await;
+
+    Ok(id)
+}
+
+async fn insert_client<'e, E>(
+    executor: E,
+    id: &str,
+    input: &ClientInput,
+    name: &str,
+    first_name: &str,
+    last_name: &str,
+    approval_status: &str,
+) -> Result<(), String>
+where
+    E: sqlx::Executor<'e, Database = Sqlite>,
+{
     let query = r#"
         INSERT INTO clients (
             id, client_title, name,
@@ -680,7 +990,8 @@ async fn create_client(state: State<'_, AppState>, input: ClientInput) -> Result
             how_did_they_hear_about_us, referring_agency, approval_status,
             denial_reason, gate_combo, notes,
             wood_size_label, wood_size_other, directions,
-            created_by_user_id
+            household_size, created_by_user_id,
+            site_hazards, site_drop_location, site_vehicle_access, site_preferred_contact_time
         )
         VALUES (
             ?, ?, ?,
@@ -693,16 +1004,22 @@ async fn create_client(state: State<'_, AppState>, input: ClientInput) -> Result
             ?, ?, ?,
             ?, ?, ?,
             ?, ?, ?,
-            ?
+            ?, ?,
+            ?, ?, ?, ?
         )
     "#;
+    let site = input
+        .site_notes
+        .as_ref()
+        .map(SiteNotes::normalized)
+        .unwrap_or_default();
 
     sqlx::query(query)
-        .bind(&id)
+        .bind(id)
         .bind(&input.client_title)
-        .bind(&name)
-        .bind(&first_name)
-        .bind(&last_name)
+        .bind(name)
+        .bind(first_name)
+        .bind(last_name)
         .bind(&input.physical_address_line1)
         .bind(&input.physical_address_line2)
         .bind(&input.physical_address_city)
@@ -718,19 +1035,92 @@ async fn create_client(state: State<'_, AppState>, input: ClientInput) -> Result
         .bind(&input.date_of_onboarding)
         .bind(&input.how_did_they_hear_about_us)
         .bind(&input.referring_agency)
-        .bind(&approval_status)
+        .bind(approval_status)
         .bind(&input.denial_reason)
         .bind(&input.gate_combo)
         .bind(&input.notes)
         .bind(&input.wood_size_label)
         .bind(&input.wood_size_other)
         .bind(&input.directions)
+        .bind(input.household_size)
         .bind(&input.created_by_user_id)
-        .execute(&state.pool)
+        .bind(&site.hazards)
+        .bind(&site.drop_location)
+        .bind(&site.vehicle_access)
+        .bind(&site.preferred_contact_time)
+        .execute(executor)
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(id)
+    Ok(())
+}
+
+async fn load_client_rows(pool: &SqlitePool, scope: &ListScope) -> Result<Vec<ClientRow>, String> {
+    sqlx::query_as::<_, ClientRow>(
+        r#"
+        SELECT
+            c.id,
+            c.name,
+            CASE WHEN ?1 THEN NULL ELSE c.email END AS email,
+            CASE WHEN ?1 THEN NULL ELSE c.telephone END AS telephone,
+            c.approval_status,
+            c.date_of_onboarding,
+            c.how_did_they_hear_about_us,
+            c.referring_agency,
+            c.referring_agency_id,
+            c.denial_reason,
+            CASE WHEN ?4 THEN 'Hidden' ELSE c.physical_address_line1 END AS physical_address_line1,
+            c.physical_address_line2,
+            CASE WHEN ?4 THEN 'Hidden' ELSE c.physical_address_city END AS physical_address_city,
+            CASE WHEN ?4 THEN 'Hidden' ELSE c.physical_address_state END AS physical_address_state,
+            CASE WHEN ?4 THEN 'Hidden' ELSE c.physical_address_postal_code END
+                AS physical_address_postal_code,
+            c.mailing_address_line1,
+            c.mailing_address_line2,
+            c.mailing_address_city,
+            c.mailing_address_state,
+            c.mailing_address_postal_code,
+            CASE WHEN ?2 THEN NULL ELSE c.gate_combo END AS gate_combo,
+            CASE WHEN ?3 THEN NULL ELSE c.notes END AS notes,
+            c.wood_size_label,
+            c.wood_size_other,
+            c.directions,
+            c.created_at,
+            c.default_mileage,
+            c.household_size,
+            c.estimated_mileage,
+            c.estimated_mileage_source,
+            CASE WHEN ?5 THEN NULL ELSE c.site_hazards END AS site_hazards,
+            CASE WHEN ?5 THEN NULL ELSE c.site_drop_location END AS site_drop_location,
+            CASE WHEN ?5 THEN NULL ELSE c.site_vehicle_access END AS site_vehicle_access,
+            CASE WHEN ?5 THEN NULL ELSE c.site_preferred_contact_time END
+                AS site_preferred_contact_time
+        FROM clients c
+        WHERE c.is_deleted = 0
+          AND (
+            ?6 IS NULL
+            OR c.id IN (
+                SELECT w.client_id
+                FROM auth_users au
+                JOIN work_order_assignments a ON a.user_id = au.user_id
+                JOIN work_orders w ON w.id = a.work_order_id
+                WHERE au.is_deleted = 0
+                  AND lower(au.username) = lower(?6)
+                  AND w.is_deleted = 0
+            )
+          )
+        ORDER BY COALESCE(c.date_of_onboarding, c.created_at) ASC
+        "#,
+    )
+    .bind(scope.hide_contact)
+    .bind(scope.hide_gate)
+    .bind(scope.hide_notes)
+    .bind(scope.hide_address)
+    .bind(scope.hide_site_notes)
+    .bind(&scope.assigned_to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -741,104 +1131,16 @@ async fn list_clients(
     hipaa_certified: Option<bool>,
     is_driver: Option<bool>,
 ) -> Result<Vec<ClientRow>, String> {
-    let mut rows = sqlx::query_as::<_, ClientRow>(
-        r#"
-        SELECT
-            id,
-            name,
-            email,
-            telephone,
-            approval_status,
-            date_of_onboarding,
-            how_did_they_hear_about_us,
-            referring_agency,
-            denial_reason,
-            physical_address_line1,
-            physical_address_line2,
-            physical_address_city,
-            physical_address_state,
-            physical_address_postal_code,
-            mailing_address_line1,
-            mailing_address_line2,
-            mailing_address_city,
-            mailing_address_state,
-            mailing_address_postal_code,
-            gate_combo,
-            notes,
-            wood_size_label,
-            wood_size_other,
-            directions,
-            created_at,
-            default_mileage
-        FROM clients
-        WHERE is_deleted = 0
-        ORDER BY COALESCE(date_of_onboarding, created_at) ASC
-        "#,
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
     let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
     let username_val = username.unwrap_or_default();
-    let username_lower = username_val.to_lowercase();
-    let is_hipaa = hipaa_certified.unwrap_or(false);
-    let driver_capable = is_driver.unwrap_or(false);
+    let scope = ListScope::for_clients(
+        &role_val,
+        &username_val,
+        hipaa_certified.unwrap_or(false),
+        is_driver.unwrap_or(false),
+    );
     audit_db(&state.pool, "list_clients", &role_val, "unknown").await;
-    if driver_capable {
-        // Drivers only see clients tied to work orders they are assigned to.
-        let assignments = sqlx::query!(
-            r#"
-            SELECT client_id, assignees_json
-            FROM work_orders
-            WHERE is_deleted = 0
-              AND client_id IS NOT NULL
-            "#
-        )
-        .fetch_all(&state.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        let mut allowed_ids: HashSet<String> = HashSet::new();
-        for row in assignments {
-            let client_id = row.client_id.clone();
-            if !client_id.is_empty() {
-                let assignees: Vec<String> =
-                    serde_json::from_str(row.assignees_json.as_deref().unwrap_or("[]"))
-                        .unwrap_or_default();
-                let matched = assignees
-                    .iter()
-                    .map(|a| a.to_lowercase())
-                    .any(|a| a == username_lower);
-                if matched {
-                    allowed_ids.insert(client_id);
-                }
-            }
-        }
-
-        rows = rows
-            .into_iter()
-            .filter(|c| allowed_ids.contains(&c.id))
-            .map(|mut c| {
-                // Drivers do not need gate codes or notes from intake.
-                c.gate_combo = None;
-                c.notes = None;
-                c
-            })
-            .collect();
-    } else if !(role_val == "admin" || (role_val == "lead" && is_hipaa)) {
-        rows.iter_mut().for_each(|c| {
-            c.email = None;
-            c.telephone = None;
-            c.gate_combo = None;
-            c.physical_address_line1 = String::from("Hidden");
-            c.physical_address_city = String::from("Hidden");
-            c.physical_address_state = String::from("Hidden");
-            c.physical_address_postal_code = String::from("Hidden");
-        });
-    }
-
-    Ok(rows)
+    load_client_rows(&state.pool, &scope).await
 }
 
 #[tauri::command]
@@ -847,7 +1149,8 @@ async fn update_client(
     input: ClientUpdateInput,
     role: Option<String>,
     actor: Option<String>,
-) -> Result<(), String> {
+    hipaa_certified: Option<bool>,
+) -> Result<ClientUpdateResult, String> {
     let approval_status = input
         .approval_status
         .unwrap_or_else(|| "pending".to_string());
@@ -882,7 +1185,7 @@ async fn update_client(
             telephone, email, date_of_onboarding,
             how_did_they_hear_about_us, referring_agency,
             approval_status, denial_reason, gate_combo, notes,
-            wood_size_label, wood_size_other, directions
+            wood_size_label, wood_size_other, directions, household_size
         FROM clients
         WHERE id = ? AND is_deleted = 0
         "#,
@@ -892,6 +1195,23 @@ async fn update_client(
     .await
     .map_err(|e| e.to_string())?;
 
+    // Site notes are optional in the payload; omitting them keeps what is stored.
+    let site_notes = input.site_notes.as_ref().map(SiteNotes::normalized);
+    let previous_site = match &site_notes {
+        Some(_) => sqlx::query_as::<_, SiteNotes>(
+            r#"
+            SELECT site_hazards, site_drop_location, site_vehicle_access, site_preferred_contact_time
+            FROM clients
+            WHERE id = ?
+            "#,
+        )
+        .bind(&input.id)
+        .fetch_optional(&state.pool)
+        .await
+        .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
     let query = r#"
         UPDATE clients
         SET client_title = ?,
@@ -920,6 +1240,7 @@ async fn update_client(
             wood_size_label = ?,
             wood_size_other = ?,
             directions = ?,
+            household_size = COALESCE(?, household_size),
             updated_at = datetime('now')
         WHERE id = ?
     "#;
@@ -951,10 +1272,39 @@ async fn update_client(
         .bind(&input.wood_size_label)
         .bind(&input.wood_size_other)
         .bind(&input.directions)
+        .bind(input.household_size)
+        .bind(&input.id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if let Some(site) = &site_notes {
+        sqlx::query(
+            r#"
+            UPDATE clients
+            SET site_hazards = ?, site_drop_location = ?, site_vehicle_access = ?,
+                site_preferred_contact_time = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&site.hazards)
+        .bind(&site.drop_location)
+        .bind(&site.vehicle_access)
+        .bind(&site.preferred_contact_time)
         .bind(&input.id)
         .execute(&state.pool)
         .await
         .map_err(|e| e.to_string())?;
+    }
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    link_client_agencies(&mut conn, Some(std::slice::from_ref(&input.id))).await?;
+    // Reported rather than applied: the caller decides whether open orders follow the client.
+    let mut stale_orders = find_stale_client_orders(&mut conn, &input.id).await?;
+    drop(conn);
+    if !can_view_client_pii(&role_val.to_lowercase(), hipaa_certified.unwrap_or(false)) {
+        redact_stale_orders(&mut stale_orders);
+    }
+    let _ =
+        refresh_client_distance_estimates(&state.pool, Some(std::slice::from_ref(&input.id))).await;
 
     if let Some(prev) = existing {
         let log_field = |field: &str, old_val: Option<String>, new_val: Option<String>| {
@@ -1011,9 +1361,30 @@ async fn update_client(
         log_field("wood_size_label", prev.wood_size_label, input.wood_size_label.clone());
         log_field("wood_size_other", prev.wood_size_other, input.wood_size_other.clone());
         log_field("directions", prev.directions, input.directions.clone());
+        if input.household_size.is_some() {
+            log_field(
+                "household_size",
+                prev.household_size.map(|v| v.to_string()),
+                input.household_size.map(|v| v.to_string()),
+            );
+        }
+        if let (Some(old), Some(new)) = (previous_site, site_notes) {
+            log_field("site_hazards", old.hazards, new.hazards);
+            log_field("site_drop_location", old.drop_location, new.drop_location);
+            log_field(
+                "site_vehicle_access",
+                old.vehicle_access,
+                new.vehicle_access,
+            );
+            log_field(
+                "site_preferred_contact_time",
+                old.preferred_contact_time,
+                new.preferred_contact_time,
+            );
+        }
     }
 
-    Ok(())
+    Ok(ClientUpdateResult { stale_orders })
 }
 
 #[tauri::command]
@@ -1034,2305 +1405,3790 @@ async fn delete_client(state: State<'_, AppState>, id: String) -> Result<(), Str
     Ok(())
 }
 
-#[tauri::command]
-async fn delete_user(
-    state: State<'_, AppState>,
+/// Client fields `create_work_order` copies onto each order, as (work order column, value) pairs.
+#[derive(Debug, FromRow)]
+struct CopiedClientFields {
+    client_title: Option<String>,
+    client_name: Option<String>,
+    physical_address_line1: Option<String>,
+    physical_address_line2: Option<String>,
+    physical_address_city: Option<String>,
+    physical_address_state: Option<String>,
+    physical_address_postal_code: Option<String>,
+    mailing_address_line1: Option<String>,
+    mailing_address_line2: Option<String>,
+    mailing_address_city: Option<String>,
+    mailing_address_state: Option<String>,
+    mailing_address_postal_code: Option<String>,
+    telephone: Option<String>,
+    email: Option<String>,
+    gate_combo: Option<String>,
+    directions: Option<String>,
+}
+
+impl CopiedClientFields {
+    fn pairs(&self) -> [(&'static str, Option<&str>); 16] {
+        [
+            ("client_title", self.client_title.as_deref()),
+            ("client_name", self.client_name.as_deref()),
+            (
+                "physical_address_line1",
+                self.physical_address_line1.as_deref(),
+            ),
+            (
+                "physical_address_line2",
+                self.physical_address_line2.as_deref(),
+            ),
+            (
+                "physical_address_city",
+                self.physical_address_city.as_deref(),
+            ),
+            (
+                "physical_address_state",
+                self.physical_address_state.as_deref(),
+            ),
+            (
+                "physical_address_postal_code",
+                self.physical_address_postal_code.as_deref(),
+            ),
+            (
+                "mailing_address_line1",
+                self.mailing_address_line1.as_deref(),
+            ),
+            (
+                "mailing_address_line2",
+                self.mailing_address_line2.as_deref(),
+            ),
+            ("mailing_address_city", self.mailing_address_city.as_deref()),
+            (
+                "mailing_address_state",
+                self.mailing_address_state.as_deref(),
+            ),
+            (
+                "mailing_address_postal_code",
+                self.mailing_address_postal_code.as_deref(),
+            ),
+            ("telephone", self.telephone.as_deref()),
+            ("email", self.email.as_deref()),
+            ("gate_combo", self.gate_combo.as_deref()),
+            ("directions", self.directions.as_deref()),
+        ]
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct OpenOrderCopyRow {
     id: String,
-    role: Option<String>,
-    actor: Option<String>,
-) -> Result<(), String> {
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
-    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    if role_val != "admin" && role_val != "lead" {
-        return Err("Only admins or leads can delete users".to_string());
+    status: String,
+    scheduled_date: Option<String>,
+    #[sqlx(flatten)]
+    fields: CopiedClientFields,
+}
+
+#[derive(Debug, Serialize)]
+struct StaleOrderField {
+    field: String,
+    order_value: Option<String>,
+    client_value: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct StaleWorkOrder {
+    work_order_id: String,
+    status: String,
+    scheduled_date: Option<String>,
+    fields: Vec<StaleOrderField>,
+}
+
+/// Blanks contact details and gate codes and hides addresses, as `list_clients` does for
+/// callers without PII access. The field names stay so the caller still sees what is stale.
+fn redact_stale_orders(orders: &mut [StaleWorkOrder]) {
+    for field in orders.iter_mut().flat_map(|o| o.fields.iter_mut()) {
+        let hidden = match field.field.as_str() {
+            "telephone" | "email" | "gate_combo" => None,
+            f if f.starts_with("physical_address_") || f.starts_with("mailing_address_") => {
+                Some("Hidden".to_string())
+            }
+            _ => continue,
+        };
+        field.order_value = hidden.clone();
+        field.client_value = hidden;
     }
-    audit_db(&state.pool, "delete_user", &role_val, &actor_val).await;
-    sqlx::query(
+}
+
+#[derive(Debug, Serialize)]
+struct ClientUpdateResult {
+    // Open orders still carrying an old copy of the client's address or contact details.
+    stale_orders: Vec<StaleWorkOrder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientOrderSyncInput {
+    client_id: String,
+    // Defaults to every stale open order / every copied field.
+    work_order_ids: Option<Vec<String>>,
+    fields: Option<Vec<String>>,
+}
+
+/// Delivered, completed, picked-up and cancelled orders keep the address they were served at.
+async fn find_stale_client_orders(
+    conn: &mut sqlx::SqliteConnection,
+    client_id: &str,
+) -> Result<Vec<StaleWorkOrder>, String> {
+    let client = sqlx::query_as::<_, CopiedClientFields>(
         r#"
-        UPDATE users
-        SET is_deleted = 1,
-            updated_at = datetime('now')
-        WHERE id = ?
+        SELECT
+            client_title, name AS client_name,
+            physical_address_line1, physical_address_line2, physical_address_city,
+            physical_address_state, physical_address_postal_code,
+            mailing_address_line1, mailing_address_line2, mailing_address_city,
+            mailing_address_state, mailing_address_postal_code,
+            telephone, email, gate_combo, directions
+        FROM clients
+        WHERE id = ? AND is_deleted = 0
         "#,
     )
-    .bind(&id)
-    .execute(&state.pool)
+    .bind(client_id)
+    .fetch_optional(&mut *conn)
     .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query(
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Client not found".to_string())?;
+    let orders = sqlx::query_as::<_, OpenOrderCopyRow>(
         r#"
-        UPDATE auth_users
-        SET is_deleted = 1,
-            updated_at = datetime('now')
-        WHERE user_id = ?
+        SELECT
+            id, status, scheduled_date,
+            client_title, client_name,
+            physical_address_line1, physical_address_line2, physical_address_city,
+            physical_address_state, physical_address_postal_code,
+            mailing_address_line1, mailing_address_line2, mailing_address_city,
+            mailing_address_state, mailing_address_postal_code,
+            telephone, email, gate_combo, directions
+        FROM work_orders
+        WHERE client_id = ?
+          AND is_deleted = 0
+          AND lower(status) NOT IN ('delivered', 'completed', 'picked_up', 'cancelled')
+        ORDER BY scheduled_date IS NULL, scheduled_date, created_at
         "#,
     )
-    .bind(&id)
-    .execute(&state.pool)
+    .bind(client_id)
+    .fetch_all(&mut *conn)
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(())
+    // Blank and NULL are the same to a driver; so is surrounding whitespace.
+    fn normalize(v: Option<&str>) -> Option<&str> {
+        v.map(str::trim).filter(|s| !s.is_empty())
+    }
+    let client_pairs = client.pairs();
+    Ok(orders
+        .into_iter()
+        .filter_map(|order| {
+            let fields: Vec<StaleOrderField> = order
+                .fields
+                .pairs()
+                .iter()
+                .zip(client_pairs.iter())
+                .filter(|((_, order_value), (_, client_value))| {
+                    normalize(*order_value) != normalize(*client_value)
+                })
+                .map(
+                    |((field, order_value), (_, client_value))| StaleOrderField {
+                        field: field.to_string(),
+                        order_value: order_value.map(str::to_string),
+                        client_value: client_value.map(str::to_string),
+                    },
+                )
+                .collect();
+            (!fields.is_empty()).then_some(StaleWorkOrder {
+                work_order_id: order.id,
+                status: order.status,
+                scheduled_date: order.scheduled_date,
+                fields,
+            })
+        })
+        .collect())
 }
 
 #[tauri::command]
-async fn check_client_conflict(
+async fn list_stale_client_orders(
     state: State<'_, AppState>,
-    name: String,
-) -> Result<Vec<ClientConflictRow>, String> {
-    let rows = sqlx::query_as::<_, ClientConflictRow>(
-        r#"
-        SELECT
-            id,
-            name,
-            physical_address_line1,
-            physical_address_city,
-            physical_address_state
-        FROM clients
-        WHERE is_deleted = 0
-          AND (
-            lower(name) = lower(?1)
-            OR lower(name) LIKE '%' || lower(?1) || '%'
-            OR lower(?1) LIKE '%' || lower(name) || '%'
-          )
-        "#,
+    client_id: String,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<Vec<StaleWorkOrder>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "list_stale_client_orders",
+        &role_val,
+        &actor_val,
     )
-    .bind(&name)
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(rows)
+    .await;
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    let mut stale = find_stale_client_orders(&mut conn, &client_id).await?;
+    if !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false)) {
+        redact_stale_orders(&mut stale);
+    }
+    Ok(stale)
 }
 
+/// Copies the client's current details onto its open work orders and returns what changed.
 #[tauri::command]
-async fn create_inventory_item(
+async fn sync_client_to_open_orders(
     state: State<'_, AppState>,
-    input: InventoryInput,
+    input: ClientOrderSyncInput,
     role: Option<String>,
     actor: Option<String>,
-) -> Result<String, String> {
-    let id = Uuid::new_v4().to_string();
+    hipaa_certified: Option<bool>,
+) -> Result<Vec<StaleWorkOrder>, String> {
     let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
     let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    if role_val != "admin" && role_val != "lead" {
-        return Err("Only leads or admins can add inventory.".to_string());
+    audit_db(
+        &state.pool,
+        "sync_client_to_open_orders",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    sync_client_orders(
+        &state.pool,
+        input,
+        &role_val,
+        &actor_val,
+        hipaa_certified.unwrap_or(false),
+    )
+    .await
+}
+
+async fn sync_client_orders(
+    pool: &SqlitePool,
+    input: ClientOrderSyncInput,
+    role_val: &str,
+    actor_val: &str,
+    hipaa: bool,
+) -> Result<Vec<StaleWorkOrder>, String> {
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(role_val) {
+        return Err("Only staff, leads or admins can update work orders".to_string());
     }
-    audit_db(&state.pool, "create_inventory_item", &role_val, &actor_val).await;
-    let query = r#"
-        INSERT INTO inventory_items (
-            id, name, category, quantity_on_hand, unit,
-            reorder_threshold, reorder_amount, notes, created_by_user_id
-        )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-    "#;
 
-    sqlx::query(query)
-        .bind(&id)
-        .bind(&input.name)
-        .bind(&input.category)
-        .bind(input.quantity_on_hand)
-        .bind(&input.unit)
-        .bind(input.reorder_threshold)
-        .bind(input.reorder_amount)
-        .bind(&input.notes)
-        .bind(&input.created_by_user_id)
-        .execute(&state.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut synced = find_stale_client_orders(&mut tx, &input.client_id).await?;
+    if let Some(ids) = &input.work_order_ids {
+        synced.retain(|o| ids.contains(&o.work_order_id));
+    }
+    if let Some(fields) = &input.fields {
+        for order in synced.iter_mut() {
+            order.fields.retain(|f| fields.contains(&f.field));
+        }
+        synced.retain(|o| !o.fields.is_empty());
+    }
+    for order in &synced {
+        for field in &order.fields {
+            // Column names come from CopiedClientFields::pairs, never from the caller.
+            sqlx::query(&format!(
+                "UPDATE work_orders SET {} = ?, updated_at = datetime('now'), version = version + 1 WHERE id = ?",
+                field.field
+            ))
+            .bind(&field.client_value)
+            .bind(&order.work_order_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            if field.field == "client_name" {
+                sqlx::query(
+                    r#"
+                    UPDATE delivery_events
+                    SET title = ?, updated_at = datetime('now')
+                    WHERE work_order_id = ? AND title = ?
+                    "#,
+                )
+                .bind(format!(
+                    "Delivery for {}",
+                    field.client_value.as_deref().unwrap_or_default()
+                ))
+                .bind(&order.work_order_id)
+                .bind(format!(
+                    "Delivery for {}",
+                    field.order_value.as_deref().unwrap_or_default()
+                ))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
 
-    Ok(id)
+    for order in &synced {
+        for field in &order.fields {
+            audit_change(
+                pool,
+                "sync_client_to_open_orders",
+                role_val,
+                actor_val,
+                "work_orders",
+                &order.work_order_id,
+                &field.field,
+                field.order_value.clone(),
+                field.client_value.clone(),
+            )
+            .await;
+        }
+    }
+    if !can_view_client_pii(role_val, hipaa) {
+        redact_stale_orders(&mut synced);
+    }
+    Ok(synced)
 }
 
-#[tauri::command]
-async fn list_inventory_items(state: State<'_, AppState>) -> Result<Vec<InventoryRow>, String> {
-    let rows = sqlx::query_as::<_, InventoryRow>(
-        r#"
-        SELECT
-            id,
-            name,
-            category,
-            quantity_on_hand,
-            unit,
-            reorder_threshold,
-            reorder_amount,
-            notes,
-            reserved_quantity
-        FROM inventory_items
-        WHERE is_deleted = 0
-        ORDER BY name ASC
-        "#,
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct ErasureRecordCount {
+    table: String,
+    rows: u64,
+}
 
-    audit_db(&state.pool, "list_inventory_items", "unknown", "unknown").await;
-    Ok(rows)
+#[derive(Debug, Serialize)]
+struct ErasureCertificate {
+    certificate_id: String,
+    client_id: String,
+    anonymized_label: String,
+    reason: Option<String>,
+    performed_by: String,
+    performed_at: String,
+    records: Vec<ErasureRecordCount>,
+    retained_fields: Vec<String>,
+    digest: String,
 }
 
-#[tauri::command]
-async fn update_inventory_item(
-    state: State<'_, AppState>,
-    input: InventoryUpdateInput,
-    role: Option<String>,
-    actor: Option<String>,
-) -> Result<(), String> {
-    let role_val = role.unwrap_or_else(|| "unknown".to_string());
-    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    audit_db(&state.pool, "update_inventory_item", &role_val, &actor_val).await;
+#[derive(Debug, FromRow)]
+struct ErasureCertificateRow {
+    id: String,
+    client_id: String,
+    anonymized_label: String,
+    reason: Option<String>,
+    performed_by: String,
+    performed_at: String,
+    records_json: String,
+    digest: String,
+}
 
-    let existing = sqlx::query!(
+#[derive(Debug, FromRow)]
+struct ClientPiiRow {
+    name: String,
+    telephone: Option<String>,
+    email: Option<String>,
+    physical_address_line1: String,
+    mailing_address_line1: Option<String>,
+    anonymized_at: Option<String>,
+}
+
+/// Audit fields whose values identify the client; their history is overwritten on erasure.
+const CLIENT_PII_FIELDS: [&str; 26] = [
+    "client_title",
+    "name",
+    "client_name",
+    "first_name",
+    "last_name",
+    "physical_address_line1",
+    "physical_address_line2",
+    "physical_address_postal_code",
+    "mailing_address_line1",
+    "mailing_address_line2",
+    "mailing_address_city",
+    "mailing_address_state",
+    "mailing_address_postal_code",
+    "telephone",
+    "email",
+    "gate_combo",
+    "notes",
+    "directions",
+    "denial_reason",
+    "site_hazards",
+    "site_drop_location",
+    "site_vehicle_access",
+    "site_preferred_contact_time",
+    "emergency_contact",
+    "accessibility_needs",
+    "import_row",
+];
+
+/// Kept so season totals, delivery counts and town-level reports still add up.
+const CLIENT_RETAINED_FIELDS: [&str; 10] = [
+    "physical_address_city",
+    "physical_address_state",
+    "approval_status",
+    "date_of_onboarding",
+    "how_did_they_hear_about_us",
+    "referring_agency",
+    "household_size",
+    "work_orders.scheduled_date / status",
+    "work_orders.delivery_size_cords / pickup_quantity_cords",
+    "work_orders.mileage / work_hours",
+];
+
+const REDACTED: &str = "[redacted]";
+
+fn erasure_digest(certificate: &ErasureCertificate) -> String {
+    let body = serde_json::json!({
+        "certificate_id": certificate.certificate_id,
+        "client_id": certificate.client_id,
+        "anonymized_label": certificate.anonymized_label,
+        "reason": certificate.reason,
+        "performed_by": certificate.performed_by,
+        "performed_at": certificate.performed_at,
+        "records": certificate.records,
+        "retained_fields": certificate.retained_fields,
+    });
+    attachments::sha256_hex(body.to_string().as_bytes())
+}
+
+/// Strips every identifying value for a former client from clients, work orders, invoices,
+/// calendar events, contacts, waitlist notes, PII attachments and audit history, leaving
+/// the counts, dates, cords and town used for reporting.
+async fn anonymize_client_with_pool(
+    pool: &SqlitePool,
+    attachments_dir: &std::path::Path,
+    client_id: &str,
+    reason: Option<String>,
+    actor: &str,
+) -> Result<ErasureCertificate, String> {
+    let client = sqlx::query_as::<_, ClientPiiRow>(
         r#"
-        SELECT name, category, quantity_on_hand, unit, reorder_threshold, reorder_amount, notes
-        FROM inventory_items
-        WHERE id = ? AND is_deleted = 0
+        SELECT name, telephone, email, physical_address_line1, mailing_address_line1, anonymized_at
+        FROM clients
+        WHERE id = ?
         "#,
-        input.id
     )
-    .fetch_optional(&state.pool)
+    .bind(client_id)
+    .fetch_optional(pool)
     .await
-    .map_err(|e| e.to_string())?;
-    sqlx::query(
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Client not found".to_string())?;
+    if client.anonymized_at.is_some() {
+        return Err("Client has already been anonymized".to_string());
+    }
+    let open_orders: i64 = sqlx::query_scalar(
         r#"
-        UPDATE inventory_items
-        SET name = ?,
-            category = ?,
-            quantity_on_hand = ?,
-            unit = ?,
-            reorder_threshold = ?,
-            reorder_amount = ?,
-            notes = ?,
-            updated_at = datetime('now')
-        WHERE id = ?
+        SELECT COUNT(*)
+        FROM work_orders
+        WHERE client_id = ?
+          AND is_deleted = 0
+          AND lower(status) NOT IN ('completed', 'picked_up', 'cancelled')
         "#,
     )
-    .bind(&input.name)
-    .bind(&input.category)
-    .bind(input.quantity_on_hand)
-    .bind(&input.unit)
-    .bind(input.reorder_threshold)
-    .bind(input.reorder_amount)
-    .bind(&input.notes)
-    .bind(&input.id)
-    .execute(&state.pool)
+    .bind(client_id)
+    .fetch_one(pool)
     .await
     .map_err(|e| e.to_string())?;
-    if let Some(prev) = existing {
-        let log_field = |field: &str, old_val: Option<String>, new_val: Option<String>| {
-            if old_val != new_val {
-                let pool = state.pool.clone();
-                let role = role_val.clone();
-                let actor = actor_val.clone();
-                let entity_id = input.id.clone();
-                let field = field.to_string();
-                tauri::async_runtime::spawn(async move {
-                    audit_change(
-                        &pool,
-                        "update_inventory_item",
-                        &role,
-                        &actor,
-                        "inventory_items",
-                        &entity_id,
-                        &field,
-                        old_val,
-                        new_val,
-                    )
-                    .await;
-                });
-            }
-        };
-        log_field("name", Some(prev.name), Some(input.name.clone()));
-        log_field("category", prev.category, input.category.clone());
-        log_field(
-            "quantity_on_hand",
-            Some(prev.quantity_on_hand.to_string()),
-            Some(input.quantity_on_hand.to_string()),
-        );
-        log_field("unit", Some(prev.unit), Some(input.unit.clone()));
-        log_field(
-            "reorder_threshold",
-            Some(prev.reorder_threshold.to_string()),
-            Some(input.reorder_threshold.to_string()),
-        );
-        log_field(
-            "reorder_amount",
-            prev.reorder_amount.map(|v| v.to_string()),
-            input.reorder_amount.map(|v| v.to_string()),
-        );
-        log_field("notes", prev.notes, input.notes.clone());
+    if open_orders > 0 {
+        return Err(format!(
+            "Client has {} open work order(s); complete or cancel them before erasure",
+            open_orders
+        ));
     }
-    Ok(())
-}
 
-#[tauri::command]
-async fn delete_inventory_item(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    audit_db(&state.pool, "delete_inventory_item", "unknown", "unknown").await;
-    sqlx::query(
+    let label = format!(
+        "Anonymized client {}",
+        client_id.get(..8).unwrap_or(client_id)
+    );
+    let mut records: Vec<ErasureRecordCount> = Vec::new();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let result = sqlx::query(
         r#"
-        UPDATE inventory_items
-        SET is_deleted = 1,
-            updated_at = datetime('now')
+        UPDATE clients
+        SET client_title = NULL, name = ?, first_name = NULL, last_name = NULL,
+            physical_address_line1 = ?, physical_address_line2 = NULL,
+            physical_address_postal_code = '',
+            mailing_address_line1 = NULL, mailing_address_line2 = NULL,
+            mailing_address_city = NULL, mailing_address_state = NULL,
+            mailing_address_postal_code = NULL,
+            telephone = NULL, email = NULL, gate_combo = NULL, notes = NULL,
+            directions = NULL, denial_reason = NULL,
+            site_hazards = NULL, site_drop_location = NULL,
+            site_vehicle_access = NULL, site_preferred_contact_time = NULL,
+            is_deleted = 1, anonymized_at = datetime('now'),
+            updated_at = datetime('now'), version = version + 1
         WHERE id = ?
         "#,
     )
-    .bind(&id)
-    .execute(&state.pool)
+    .bind(&label)
+    .bind(REDACTED)
+    .bind(client_id)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
-    Ok(())
-}
-
-#[tauri::command]
-async fn create_work_order(
-    state: State<'_, AppState>,
-    input: WorkOrderInput,
-    role: Option<String>,
-) -> Result<String, String> {
-    let id = Uuid::new_v4().to_string();
-    let status = input.status.unwrap_or_else(|| "draft".to_string());
-    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
-    if role_val != "admin" && !is_staff_like(&role_val) {
-        return Err("Only staff or admin may create work orders".to_string());
-    }
-    audit_db(&state.pool, "create_work_order", &role_val, "unknown").await;
-
-    let query = r#"
-        INSERT INTO work_orders (
-            id, client_id, client_title, client_name,
-            physical_address_line1, physical_address_line2, physical_address_city,
-            physical_address_state, physical_address_postal_code,
-            mailing_address_line1, mailing_address_line2, mailing_address_city,
-            mailing_address_state, mailing_address_postal_code,
-            telephone, email, directions, gate_combo, mileage, work_hours,
-            other_heat_source_gas, other_heat_source_electric, other_heat_source_other,
-            notes, scheduled_date, status,
-            wood_size_label, wood_size_other,
-            delivery_size_label, delivery_size_cords,
-            pickup_delivery_type,
-            pickup_quantity_cords, pickup_length, pickup_width, pickup_height, pickup_units,
-            assignees_json,
-            created_by_user_id, created_by_display,
-            paired_order_id
-        )
-        VALUES (
-            ?, ?, ?, ?,
-            ?, ?, ?,
-            ?, ?,
-            ?, ?, ?,
-            ?, ?,
-            ?, ?, ?, ?, ?, ?,
-            ?, ?, ?,
-            ?, ?, ?,
-            ?, ?,
-            ?, ?,
-            ?,
-            ?, ?, ?, ?, ?,
-            ?,
-            ?, ?,
-            ?
-        )
-    "#;
-
-    let assignees_store = input
-        .assignees_json
-        .clone()
-        .unwrap_or_else(|| "[]".to_string());
-
-    sqlx::query(query)
-        .bind(&id)
-        .bind(&input.client_id)
-        .bind(&input.client_title)
-        .bind(&input.client_name)
-        .bind(&input.physical_address_line1)
-        .bind(&input.physical_address_line2)
-        .bind(&input.physical_address_city)
-        .bind(&input.physical_address_state)
-        .bind(&input.physical_address_postal_code)
-        .bind(&input.mailing_address_line1)
-        .bind(&input.mailing_address_line2)
-        .bind(&input.mailing_address_city)
-        .bind(&input.mailing_address_state)
-        .bind(&input.mailing_address_postal_code)
-        .bind(&input.telephone)
-        .bind(&input.email)
-        .bind(&input.directions)
-        .bind(&input.gate_combo)
-        .bind(&input.mileage)
-        .bind(&input.work_hours)
-        .bind(input.other_heat_source_gas)
-        .bind(input.other_heat_source_electric)
-        .bind(&input.other_heat_source_other)
-        .bind(&input.notes)
-        .bind(&input.scheduled_date)
-        .bind(&status)
-        .bind(&input.wood_size_label)
-        .bind(&input.wood_size_other)
-        .bind(&input.delivery_size_label)
-        .bind(&input.delivery_size_cords)
-        .bind(&input.pickup_delivery_type)
-        .bind(&input.pickup_quantity_cords)
-        .bind(&input.pickup_length)
-        .bind(&input.pickup_width)
-        .bind(&input.pickup_height)
-        .bind(&input.pickup_units)
-        .bind(&assignees_store)
-        .bind(&input.created_by_user_id)
-        .bind(&input.created_by_display)
-        .bind(&input.paired_order_id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    // If this order is paired with another, update the other order's paired_order_id to point back
-    if let Some(paired_id) = &input.paired_order_id {
-        if !paired_id.is_empty() {
-            sqlx::query(
-                r#"UPDATE work_orders SET paired_order_id = ?, updated_at = datetime('now') WHERE id = ?"#
-            )
-            .bind(&id)
-            .bind(paired_id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| e.to_string())?;
-        }
-    }
-
-    let inventory_cords = if status.eq_ignore_ascii_case("picked_up") {
-        input.pickup_quantity_cords.unwrap_or(0.0)
-    } else {
-        input.delivery_size_cords.unwrap_or(0.0)
-    };
-    adjust_inventory_for_transition_tx(&mut tx, "draft", &status, inventory_cords)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    let assigned_json = assignees_store.clone();
-
-    // Auto-create a delivery event when a work order is scheduled.
-    if let Some(start_date) = &input.scheduled_date {
-        let delivery_id = Uuid::new_v4().to_string();
-        let delivery_query = r#"
-            INSERT INTO delivery_events (
-                id, title, description, event_type, work_order_id,
-                start_date, end_date, color_code, assigned_user_ids_json
-            )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-        "#;
+    records.push(ErasureRecordCount {
+        table: "clients".to_string(),
+        rows: result.rows_affected(),
+    });
 
-        sqlx::query(delivery_query)
-            .bind(&delivery_id)
-            .bind(format!("Delivery for {}", input.client_name))
-            .bind::<Option<String>>(None)
-            .bind("delivery")
-            .bind(&id)
-            .bind(start_date)
-            .bind::<Option<String>>(None)
-            .bind("#e67f1e")
-            .bind(&assigned_json)
-            .execute(&mut *tx)
+    let order_ids: Vec<String> =
+        sqlx::query_scalar("SELECT id FROM work_orders WHERE client_id = ?")
+            .bind(client_id)
+            .fetch_all(&mut *tx)
             .await
             .map_err(|e| e.to_string())?;
-    }
-
-    tx.commit().await.map_err(|e| e.to_string())?;
-
-    Ok(id)
-}
+    let order_ids_json = serde_json::to_string(&order_ids).map_err(|e| e.to_string())?;
 
-#[tauri::command]
-async fn list_work_orders(
-    state: State<'_, AppState>,
-    role: Option<String>,
-    username: Option<String>,
-    hipaa_certified: Option<bool>,
-    is_driver: Option<bool>,
-) -> Result<Vec<WorkOrderRow>, String> {
-    sqlx::query(
+    let result = sqlx::query(
         r#"
         UPDATE work_orders
-        SET status = 'scheduled', updated_at = datetime('now')
-        WHERE is_deleted = 0
-          AND scheduled_date IS NOT NULL
-          AND lower(status) IN ('received', 'pending', 'rescheduled', 'draft')
+        SET client_title = NULL, client_name = ?,
+            physical_address_line1 = ?, physical_address_line2 = NULL,
+            physical_address_postal_code = '',
+            mailing_address_line1 = NULL, mailing_address_line2 = NULL,
+            mailing_address_city = NULL, mailing_address_state = NULL,
+            mailing_address_postal_code = NULL,
+            telephone = NULL, email = NULL, directions = NULL, gate_combo = NULL,
+            notes = NULL, other_heat_source_other = NULL,
+            updated_at = datetime('now'), version = version + 1
+        WHERE client_id = ?
         "#,
     )
-    .execute(&state.pool)
+    .bind(&label)
+    .bind(REDACTED)
+    .bind(client_id)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
+    records.push(ErasureRecordCount {
+        table: "work_orders".to_string(),
+        rows: result.rows_affected(),
+    });
 
-    #[derive(sqlx::FromRow)]
-    struct MissingDeliveryRow {
-        id: String,
-        client_name: String,
-        scheduled_date: String,
-        assignees_json: Option<String>,
-    }
-
-    let missing_deliveries = sqlx::query_as::<_, MissingDeliveryRow>(
+    let invoices: Vec<(String, Option<String>)> = sqlx::query_as(
         r#"
-        SELECT
-            id,
-            client_name,
-            scheduled_date,
-            COALESCE(assignees_json, '[]') as assignees_json
-        FROM work_orders
-        WHERE is_deleted = 0
-          AND scheduled_date IS NOT NULL
-          AND NOT EXISTS (
-              SELECT 1
-              FROM delivery_events
-              WHERE work_order_id = work_orders.id
-                AND is_deleted = 0
-          )
+        SELECT id, client_snapshot_json
+        FROM invoices
+        WHERE work_order_id IN (SELECT value FROM json_each(?))
         "#,
     )
-    .fetch_all(&state.pool)
+    .bind(&order_ids_json)
+    .fetch_all(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
-
-    for missing in missing_deliveries {
-        let delivery_id = Uuid::new_v4().to_string();
+    for (invoice_id, snapshot) in &invoices {
+        let mut snapshot: serde_json::Value = snapshot
+            .as_deref()
+            .and_then(|s| serde_json::from_str(s).ok())
+            .unwrap_or_else(|| serde_json::json!({}));
+        if let Some(map) = snapshot.as_object_mut() {
+            for field in CLIENT_PII_FIELDS {
+                if map.contains_key(field) {
+                    map.insert(field.to_string(), serde_json::Value::Null);
+                }
+            }
+            map.insert("client_name".to_string(), serde_json::json!(label));
+        }
         sqlx::query(
             r#"
-            INSERT INTO delivery_events (
-                id, title, description, event_type, work_order_id,
-                start_date, end_date, color_code, assigned_user_ids_json
-            )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            UPDATE invoices
+            SET client_snapshot_json = ?, notes = NULL,
+                updated_at = datetime('now'), version = version + 1
+            WHERE id = ?
             "#,
         )
-        .bind(&delivery_id)
-        .bind(format!("Delivery for {}", missing.client_name))
-        .bind::<Option<String>>(None)
-        .bind("delivery")
-        .bind(&missing.id)
-        .bind(&missing.scheduled_date)
-        .bind::<Option<String>>(None)
-        .bind("#e67f1e")
-        .bind(&missing.assignees_json)
-        .execute(&state.pool)
+        .bind(snapshot.to_string())
+        .bind(invoice_id)
+        .execute(&mut *tx)
         .await
         .map_err(|e| e.to_string())?;
     }
+    records.push(ErasureRecordCount {
+        table: "invoices".to_string(),
+        rows: invoices.len() as u64,
+    });
 
-    let mut rows = sqlx::query_as::<_, WorkOrderRow>(
+    let result = sqlx::query(
         r#"
-        SELECT
-            id,
-            client_name,
-            status,
-            scheduled_date,
-            gate_combo,
-            notes,
-            telephone,
-            physical_address_line1,
-            physical_address_city,
-            physical_address_state,
-            physical_address_postal_code,
-            mileage,
-            work_hours,
-            wood_size_label,
-            wood_size_other,
-            delivery_size_label,
-            delivery_size_cords,
-            pickup_delivery_type,
-            pickup_quantity_cords,
-            pickup_length,
-            pickup_width,
-            pickup_height,
-            pickup_units,
-            COALESCE(assignees_json, '[]') as assignees_json,
-            created_by_display,
-            created_at,
-            paired_order_id,
-            client_id
-        FROM work_orders
-        WHERE is_deleted = 0
-        ORDER BY (scheduled_date IS NULL), datetime(scheduled_date) DESC, created_at DESC
+        UPDATE delivery_events
+        SET title = ?, description = NULL, updated_at = datetime('now'), version = version + 1
+        WHERE work_order_id IN (SELECT value FROM json_each(?))
         "#,
     )
-    .fetch_all(&state.pool)
+    .bind(format!("Delivery for {}", label))
+    .bind(&order_ids_json)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
+    records.push(ErasureRecordCount {
+        table: "delivery_events".to_string(),
+        rows: result.rows_affected(),
+    });
 
-    // PII filtering and assignment filtering
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
-    let username_val = username.unwrap_or_default();
-    let username_lower = username_val.to_lowercase();
-    let is_hipaa = hipaa_certified.unwrap_or(false);
-    let driver_capable = is_driver.unwrap_or(false);
-    audit_db(&state.pool, "list_work_orders", &role_val, &username_val).await;
-
-    if role_val == "volunteer" || driver_capable {
-        rows = rows
-            .into_iter()
-            .filter(|wo| {
-                let assignees: Vec<String> =
-                    serde_json::from_str(wo.assignees_json.as_deref().unwrap_or("[]"))
-                        .unwrap_or_default();
-                assignees
-                    .iter()
-                    .map(|a| a.to_lowercase())
-                    .any(|a| a == username_lower)
-            })
-            .map(|mut wo| {
-                if role_val == "volunteer" {
-                    // Strip PII for volunteers
-                    wo.telephone = None;
-                    wo.gate_combo = None;
-                    wo.notes = None;
-                    wo.physical_address_line1 = None;
-                    wo.physical_address_city = None;
-                    wo.physical_address_state = None;
-                    wo.physical_address_postal_code = None;
-                }
-                wo
-            })
-            .collect();
-    } else if is_staff_like(&role_val) || role_val == "lead" {
-        if role_val == "lead" && is_hipaa {
-            // full access
-        } else {
-            // mask PII for non-HIPAA staff
-            rows.iter_mut().for_each(|wo| {
-                wo.telephone = None;
-                wo.gate_combo = None;
-                wo.notes = None;
-            });
-        }
-    }
+    let result = sqlx::query(
+        r#"
+        UPDATE client_contacts
+        SET summary = NULL, outcome = NULL, updated_at = datetime('now'), version = version + 1
+        WHERE client_id = ?
+        "#,
+    )
+    .bind(client_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    records.push(ErasureRecordCount {
+        table: "client_contacts".to_string(),
+        rows: result.rows_affected(),
+    });
 
-    Ok(rows)
-}
-
-async fn adjust_inventory_for_transition_tx(
-    tx: &mut Transaction<'_, Sqlite>,
-    previous_status: &str,
-    next_status: &str,
-    delivery_size_cords: f64,
-) -> Result<(), String> {
-    if delivery_size_cords <= 0.0 {
-        return Ok(());
-    }
-
-    let reserve_states = ["scheduled", "in_progress"];
-    let prev_status_lower = previous_status.to_lowercase();
-    let next_status_lower = next_status.to_lowercase();
-    let prev_reserved = reserve_states.contains(&prev_status_lower.as_str());
-    let next_reserved = reserve_states.contains(&next_status_lower.as_str());
-
-    #[derive(sqlx::FromRow)]
-    struct InventoryRecord {
-        id: String,
-        quantity_on_hand: f64,
-        reserved_quantity: f64,
-    }
-
-    let inventory_row = sqlx::query_as::<_, InventoryRecord>(
+    let result = sqlx::query(
         r#"
-        SELECT id, quantity_on_hand, reserved_quantity
-        FROM inventory_items
-        WHERE is_deleted = 0
-          AND (
-            lower(unit) LIKE '%cord%'
-            OR lower(name) LIKE '%wood%'
-          )
-        ORDER BY created_at ASC
-        LIMIT 1
+        UPDATE waitlist_entries
+        SET notes = NULL, status = CASE WHEN status = 'waiting' THEN 'removed' ELSE status END,
+            updated_at = datetime('now'), version = version + 1
+        WHERE client_id = ?
         "#,
     )
-    .fetch_optional(&mut **tx)
+    .bind(client_id)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
+    records.push(ErasureRecordCount {
+        table: "waitlist_entries".to_string(),
+        rows: result.rows_affected(),
+    });
 
-    // If no wood inventory item found, treat as an error to avoid silent no-op transitions
-    let record = match inventory_row {
-        Some(r) => r,
-        None => {
-            return Err(
-                "No wood inventory item found to adjust (add a wood inventory item).".to_string(),
-            )
-        }
-    };
-
-    let mut reserved = record.reserved_quantity;
-    let mut on_hand = record.quantity_on_hand;
-
-    // If moving into a reserved state, ensure availability before reserving
-    if !prev_reserved && next_reserved {
-        let available = on_hand - reserved;
-        if available < delivery_size_cords {
-            return Err(format!(
-                "Insufficient inventory to reserve {} cords (available: {}).",
-                delivery_size_cords, available
-            ));
-        }
-        reserved += delivery_size_cords;
-    } else if prev_reserved && !next_reserved {
-        reserved -= delivery_size_cords;
-    }
+    let result = sqlx::query(
+        r#"
+        UPDATE client_emergency_contacts
+        SET name = ?, relationship = NULL, telephone = NULL, alt_telephone = NULL,
+            email = NULL, notes = NULL, is_deleted = 1,
+            updated_at = datetime('now'), version = version + 1
+        WHERE client_id = ?
+        "#,
+    )
+    .bind(REDACTED)
+    .bind(client_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    records.push(ErasureRecordCount {
+        table: "client_emergency_contacts".to_string(),
+        rows: result.rows_affected(),
+    });
 
-    if next_status.eq_ignore_ascii_case("completed")
-        || next_status.eq_ignore_ascii_case("picked_up")
-    {
-        on_hand -= delivery_size_cords;
-    }
+    let result = sqlx::query("DELETE FROM client_communication_preferences WHERE client_id = ?")
+        .bind(client_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    records.push(ErasureRecordCount {
+        table: "client_communication_preferences".to_string(),
+        rows: result.rows_affected(),
+    });
 
-    if reserved < 0.0 {
-        reserved = 0.0;
-    }
-    if on_hand < 0.0 {
-        on_hand = 0.0;
-    }
+    let pii_hashes: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT sha256
+        FROM attachments
+        WHERE contains_pii = 1
+          AND ((entity = 'clients' AND entity_id = ?)
+            OR (entity = 'work_orders' AND entity_id IN (SELECT value FROM json_each(?))))
+        "#,
+    )
+    .bind(client_id)
+    .bind(&order_ids_json)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let result = sqlx::query(
+        r#"
+        UPDATE attachments
+        SET is_deleted = 1, file_name = ?, updated_at = datetime('now'), version = version + 1
+        WHERE contains_pii = 1
+          AND ((entity = 'clients' AND entity_id = ?)
+            OR (entity = 'work_orders' AND entity_id IN (SELECT value FROM json_each(?))))
+        "#,
+    )
+    .bind(REDACTED)
+    .bind(client_id)
+    .bind(&order_ids_json)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    records.push(ErasureRecordCount {
+        table: "attachments".to_string(),
+        rows: result.rows_affected(),
+    });
 
-    // Ensure reserved never exceeds on-hand after adjustments (concurrency safety)
-    if reserved > on_hand {
-        reserved = on_hand;
+    // Field-level history for the client and its orders...
+    let fields_json = serde_json::to_string(&CLIENT_PII_FIELDS).map_err(|e| e.to_string())?;
+    let mut audit_rows = sqlx::query(
+        r#"
+        UPDATE audit_logs
+        SET old_value = CASE WHEN old_value IS NULL THEN NULL ELSE ?1 END,
+            new_value = CASE WHEN new_value IS NULL THEN NULL ELSE ?1 END
+        WHERE field IN (SELECT value FROM json_each(?2))
+          AND ((entity = 'clients' AND entity_id = ?3)
+            OR (entity = 'work_orders' AND entity_id IN (SELECT value FROM json_each(?4))))
+        "#,
+    )
+    .bind(REDACTED)
+    .bind(&fields_json)
+    .bind(client_id)
+    .bind(&order_ids_json)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+    // ...plus any other audit value that quotes the client's distinctive identifiers.
+    let identifiers: Vec<String> = [
+        Some(client.name),
+        client.telephone,
+        client.email,
+        Some(client.physical_address_line1),
+        client.mailing_address_line1,
+    ]
+    .into_iter()
+    .flatten()
+    .map(|v| v.trim().to_string())
+    .filter(|v| v.len() >= 5)
+    .collect();
+    for value in identifiers {
+        audit_rows += sqlx::query(
+            r#"
+            UPDATE audit_logs
+            SET old_value = replace(old_value, ?1, ?2),
+                new_value = replace(new_value, ?1, ?2)
+            WHERE instr(old_value, ?1) > 0 OR instr(new_value, ?1) > 0
+            "#,
+        )
+        .bind(&value)
+        .bind(REDACTED)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .rows_affected();
     }
+    records.push(ErasureRecordCount {
+        table: "audit_logs".to_string(),
+        rows: audit_rows,
+    });
 
+    let mut certificate = ErasureCertificate {
+        certificate_id: Uuid::new_v4().to_string(),
+        client_id: client_id.to_string(),
+        anonymized_label: label,
+        reason: reason.filter(|r| !r.trim().is_empty()),
+        performed_by: actor.to_string(),
+        performed_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        records,
+        retained_fields: CLIENT_RETAINED_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .collect(),
+        digest: String::new(),
+    };
+    certificate.digest = erasure_digest(&certificate);
     sqlx::query(
         r#"
-        UPDATE inventory_items
-        SET reserved_quantity = ?, quantity_on_hand = ?, updated_at = datetime('now')
-        WHERE id = ?
+        INSERT INTO erasure_certificates (
+            id, client_id, anonymized_label, reason, performed_by, performed_at,
+            records_json, digest
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(reserved)
-    .bind(on_hand)
-    .bind(&record.id)
-    .execute(&mut **tx)
+    .bind(&certificate.certificate_id)
+    .bind(&certificate.client_id)
+    .bind(&certificate.anonymized_label)
+    .bind(&certificate.reason)
+    .bind(&certificate.performed_by)
+    .bind(&certificate.performed_at)
+    .bind(serde_json::to_string(&certificate.records).map_err(|e| e.to_string())?)
+    .bind(&certificate.digest)
+    .execute(&mut *tx)
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(())
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    // Remove document scans now rather than waiting for orphan cleanup, unless another live
+    // attachment shares the same file.
+    for hash in pii_hashes {
+        let still_used: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM attachments WHERE sha256 = ? AND is_deleted = 0",
+        )
+        .bind(&hash)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        if still_used == 0 {
+            let _ = attachments::remove_blob_file(attachments_dir, &hash);
+        }
+    }
+
+    Ok(certificate)
 }
 
 #[tauri::command]
-async fn create_delivery_event(
+async fn anonymize_client(
     state: State<'_, AppState>,
-    input: DeliveryEventInput,
+    client_id: String,
+    reason: Option<String>,
     role: Option<String>,
     actor: Option<String>,
-) -> Result<String, String> {
-    let id = Uuid::new_v4().to_string();
+) -> Result<ErasureCertificate, String> {
     let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
     let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    if role_val != "admin" && !is_staff_like(&role_val) {
-        return Err("Only staff or admins can create schedule events.".to_string());
+    audit_db(&state.pool, "anonymize_client", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can anonymize clients".to_string());
     }
-    audit_db(&state.pool, "create_delivery_event", &role_val, &actor_val).await;
-    let query = r#"
-        INSERT INTO delivery_events (
-            id, title, description, event_type, work_order_id,
-            start_date, end_date, color_code, assigned_user_ids_json
-        )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-    "#;
-
-    sqlx::query(query)
-        .bind(&id)
-        .bind(&input.title)
-        .bind(&input.description)
-        .bind(&input.event_type)
-        .bind(&input.work_order_id)
-        .bind(&input.start_date)
-        .bind(&input.end_date)
-        .bind(&input.color_code)
-        .bind(&input.assigned_user_ids_json)
-        .execute(&state.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(id)
+    let certificate = anonymize_client_with_pool(
+        &state.pool,
+        &state.attachments_dir,
+        &client_id,
+        reason,
+        &actor_val,
+    )
+    .await?;
+    audit_change(
+        &state.pool,
+        "anonymize_client",
+        &role_val,
+        &actor_val,
+        "clients",
+        &client_id,
+        "anonymized",
+        None,
+        Some(format!("certificate {}", certificate.certificate_id)),
+    )
+    .await;
+    Ok(certificate)
 }
 
 #[tauri::command]
-async fn list_users(state: State<'_, AppState>) -> Result<Vec<UserRow>, String> {
-    let rows = sqlx::query_as::<_, UserRow>(
+async fn list_erasure_certificates(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<ErasureCertificate>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "list_erasure_certificates",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if role_val != "admin" {
+        return Err("Only admins can view erasure certificates".to_string());
+    }
+    let rows = sqlx::query_as::<_, ErasureCertificateRow>(
         r#"
-        SELECT
-            id,
-            name,
-            email,
-            telephone,
-            physical_address_line1,
-            physical_address_line2,
-            physical_address_city,
-            physical_address_state,
-            physical_address_postal_code,
-            mailing_address_line1,
-            mailing_address_line2,
-            mailing_address_city,
-            mailing_address_state,
-            mailing_address_postal_code,
-            role,
-            availability_notes,
-            availability_schedule,
-            driver_license_status,
-            driver_license_number,
-            driver_license_expires_on,
-            vehicle,
-            COALESCE(hipaa_certified, 0) as hipaa_certified,
-            COALESCE(is_driver, 0) as is_driver
-        FROM users
-        WHERE is_deleted = 0
-        ORDER BY name ASC
+        SELECT id, client_id, anonymized_label, reason, performed_by, performed_at,
+               records_json, digest
+        FROM erasure_certificates
+        ORDER BY performed_at DESC
         "#,
     )
     .fetch_all(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|row| ErasureCertificate {
+            certificate_id: row.id,
+            client_id: row.client_id,
+            anonymized_label: row.anonymized_label,
+            reason: row.reason,
+            performed_by: row.performed_by,
+            performed_at: row.performed_at,
+            records: serde_json::from_str(&row.records_json).unwrap_or_default(),
+            retained_fields: CLIENT_RETAINED_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+            digest: row.digest,
+        })
+        .collect())
+}
 
-    audit_db(&state.pool, "list_users", "unknown", "unknown").await;
-    Ok(rows)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+struct DistanceSettings {
+    yard_label: Option<String>,
+    yard_latitude: Option<f64>,
+    yard_longitude: Option<f64>,
+    road_factor: f64,
 }
 
-#[derive(Debug, Deserialize)]
-struct EnsureUserInput {
+#[derive(Debug, Serialize)]
+struct ZipCentroidImportReport {
+    imported: usize,
+    skipped: usize,
+    clients_updated: usize,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct ClientMileageComparison {
+    client_id: String,
     name: String,
-    email: Option<String>,
-    telephone: Option<String>,
-    physical_address_line1: Option<String>,
-    physical_address_line2: Option<String>,
-    physical_address_city: Option<String>,
-    physical_address_state: Option<String>,
-    physical_address_postal_code: Option<String>,
-    mailing_address_line1: Option<String>,
-    mailing_address_line2: Option<String>,
-    mailing_address_city: Option<String>,
-    mailing_address_state: Option<String>,
-    mailing_address_postal_code: Option<String>,
-    role: String,
-    is_driver: Option<bool>,
-    hipaa_certified: Option<bool>,
+    estimated_mileage: Option<f64>,
+    estimated_mileage_source: Option<String>,
+    default_mileage: Option<f64>,
+    actual_average_mileage: Option<f64>,
+    actual_delivery_count: i64,
+    // Actual average minus estimate; positive means the trip runs longer than estimated.
+    difference: Option<f64>,
 }
 
-#[tauri::command]
-async fn ensure_user_exists(state: State<'_, AppState>, input: EnsureUserInput) -> Result<String, String> {
-    let name = input.name.trim().to_string();
-    if name.is_empty() {
-        return Err("Name is required".to_string());
-    }
-    let role = input.role.trim().to_lowercase();
-    let existing = sqlx::query!(
+#[derive(Debug, FromRow)]
+struct ClientLocationRow {
+    id: String,
+    physical_address_city: String,
+    physical_address_state: String,
+    physical_address_postal_code: String,
+}
+
+async fn load_distance_settings(pool: &SqlitePool) -> Result<DistanceSettings, String> {
+    Ok(sqlx::query_as::<_, DistanceSettings>(
         r#"
-        SELECT
-            id,
-            role,
-            email,
-            telephone,
-            physical_address_line1,
-            physical_address_line2,
-            physical_address_city,
-            physical_address_state,
-            physical_address_postal_code,
-            mailing_address_line1,
-            mailing_address_line2,
-            mailing_address_city,
-            mailing_address_state,
-            mailing_address_postal_code,
-            COALESCE(is_driver, 0) as "is_driver!: i64",
-            COALESCE(hipaa_certified, 0) as "hipaa_certified!: i64"
-        FROM users
-        WHERE lower(name) = lower(?)
-          AND is_deleted = 0
-        LIMIT 1
+        SELECT yard_label, yard_latitude, yard_longitude, road_factor
+        FROM distance_settings
+        WHERE id = 'default'
         "#,
-        name
     )
-    .fetch_optional(&state.pool)
+    .fetch_optional(pool)
     .await
-    .map_err(|e| e.to_string())?;
-
-    let wants_driver = input.is_driver.unwrap_or(false);
-    let wants_hipaa = input.hipaa_certified.unwrap_or(false);
+    .map_err(|e| e.to_string())?
+    .unwrap_or(DistanceSettings {
+        yard_label: None,
+        yard_latitude: None,
+        yard_longitude: None,
+        road_factor: 1.3,
+    }))
+}
 
-    if let Some(row) = existing {
-        let mut updated = false;
-        let update_role = if role_rank(&role) > role_rank(&row.role) {
-            updated = true;
-            role.clone()
-        } else {
-            row.role.clone()
-        };
-        let update_email = if row.email.is_none() && input.email.is_some() {
-            updated = true;
-            input.email.clone()
-        } else {
-            row.email.clone()
-        };
-        let update_phone = if row.telephone.is_none() && input.telephone.is_some() {
-            updated = true;
-            input.telephone.clone()
-        } else {
-            row.telephone.clone()
-        };
-        let update_phys_line1 = if row.physical_address_line1.is_none() && input.physical_address_line1.is_some() {
-            updated = true;
-            input.physical_address_line1.clone()
-        } else {
-            row.physical_address_line1.clone()
-        };
-        let update_phys_line2 = if row.physical_address_line2.is_none() && input.physical_address_line2.is_some() {
-            updated = true;
-            input.physical_address_line2.clone()
-        } else {
-            row.physical_address_line2.clone()
-        };
-        let update_phys_city = if row.physical_address_city.is_none() && input.physical_address_city.is_some() {
-            updated = true;
-            input.physical_address_city.clone()
-        } else {
-            row.physical_address_city.clone()
-        };
-        let update_phys_state = if row.physical_address_state.is_none() && input.physical_address_state.is_some() {
-            updated = true;
-            input.physical_address_state.clone()
-        } else {
-            row.physical_address_state.clone()
-        };
-        let update_phys_postal = if row.physical_address_postal_code.is_none() && input.physical_address_postal_code.is_some() {
-            updated = true;
-            input.physical_address_postal_code.clone()
-        } else {
-            row.physical_address_postal_code.clone()
-        };
-        let update_mail_line1 = if row.mailing_address_line1.is_none() && input.mailing_address_line1.is_some() {
-            updated = true;
-            input.mailing_address_line1.clone()
-        } else {
-            row.mailing_address_line1.clone()
-        };
-        let update_mail_line2 = if row.mailing_address_line2.is_none() && input.mailing_address_line2.is_some() {
-            updated = true;
-            input.mailing_address_line2.clone()
-        } else {
-            row.mailing_address_line2.clone()
-        };
-        let update_mail_city = if row.mailing_address_city.is_none() && input.mailing_address_city.is_some() {
-            updated = true;
-            input.mailing_address_city.clone()
-        } else {
-            row.mailing_address_city.clone()
-        };
-        let update_mail_state = if row.mailing_address_state.is_none() && input.mailing_address_state.is_some() {
-            updated = true;
-            input.mailing_address_state.clone()
-        } else {
-            row.mailing_address_state.clone()
-        };
-        let update_mail_postal = if row.mailing_address_postal_code.is_none() && input.mailing_address_postal_code.is_some() {
-            updated = true;
-            input.mailing_address_postal_code.clone()
-        } else {
-            row.mailing_address_postal_code.clone()
-        };
-        let update_driver = if wants_driver && row.is_driver == 0 {
-            updated = true;
-            1
-        } else {
-            row.is_driver
-        };
-        let update_hipaa = if wants_hipaa && row.hipaa_certified == 0 {
-            updated = true;
-            1
-        } else {
-            row.hipaa_certified
-        };
-        if updated {
-            sqlx::query(
-                r#"
-                UPDATE users
-                SET role = ?,
-                    email = ?,
-                    telephone = ?,
-                    physical_address_line1 = ?,
-                    physical_address_line2 = ?,
-                    physical_address_city = ?,
-                    physical_address_state = ?,
-                    physical_address_postal_code = ?,
-                    mailing_address_line1 = ?,
-                    mailing_address_line2 = ?,
-                    mailing_address_city = ?,
-                    mailing_address_state = ?,
-                    mailing_address_postal_code = ?,
-                    is_driver = ?,
-                    hipaa_certified = ?,
-                    updated_at = datetime('now')
-                WHERE id = ?
-                "#,
-            )
-            .bind(&update_role)
-            .bind(&update_email)
-            .bind(&update_phone)
-            .bind(&update_phys_line1)
-            .bind(&update_phys_line2)
-            .bind(&update_phys_city)
-            .bind(&update_phys_state)
-            .bind(&update_phys_postal)
-            .bind(&update_mail_line1)
-            .bind(&update_mail_line2)
-            .bind(&update_mail_city)
-            .bind(&update_mail_state)
-            .bind(&update_mail_postal)
-            .bind(update_driver)
-            .bind(update_hipaa)
-            .bind(&row.id)
-            .execute(&state.pool)
+async fn upsert_zip_centroids(
+    pool: &SqlitePool,
+    centroids: &[geo::Centroid],
+    source: &str,
+    replace_existing: bool,
+) -> Result<usize, String> {
+    let verb = if replace_existing {
+        "INSERT OR REPLACE"
+    } else {
+        "INSERT OR IGNORE"
+    };
+    let query = format!(
+        "{} INTO zip_centroids (zip, city, state, latitude, longitude, source, updated_at) \
+         VALUES (?, ?, ?, ?, ?, ?, datetime('now'))",
+        verb
+    );
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut written = 0;
+    for c in centroids {
+        written += sqlx::query(&query)
+            .bind(&c.zip)
+            .bind(&c.city)
+            .bind(&c.state)
+            .bind(c.latitude)
+            .bind(c.longitude)
+            .bind(source)
+            .execute(&mut *tx)
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| e.to_string())?
+            .rows_affected() as usize;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(written)
+}
+
+/// Loads the compiled-in centroids without overwriting rows an admin imported.
+async fn seed_bundled_zip_centroids(pool: &SqlitePool) -> Result<(), String> {
+    let (centroids, _) = geo::parse_centroids(geo::BUNDLED_ZIP_CENTROIDS)?;
+    upsert_zip_centroids(pool, &centroids, "bundled", false).await?;
+    Ok(())
+}
+
+/// Looks up the centroid for a ZIP or ZIP+4 code.
+async fn zip_centroid_for(
+    pool: &SqlitePool,
+    postal_code: &str,
+) -> Result<Option<(f64, f64)>, String> {
+    let zip5 = postal_code.trim().get(..5).unwrap_or_default();
+    sqlx::query_as("SELECT latitude, longitude FROM zip_centroids WHERE zip = ?")
+        .bind(zip5)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recomputes `clients.estimated_mileage` from the yard to the client's ZIP centroid, falling
+/// back to the average centroid of the client's town. Returns the number of clients updated.
+async fn refresh_client_distance_estimates(
+    pool: &SqlitePool,
+    client_ids: Option<&[String]>,
+) -> Result<usize, String> {
+    let settings = load_distance_settings(pool).await?;
+    let mut query = String::from(
+        r#"
+        SELECT id, physical_address_city, physical_address_state, physical_address_postal_code
+        FROM clients
+        WHERE is_deleted = 0
+        "#,
+    );
+    let ids_json = match client_ids {
+        Some(ids) => {
+            query.push_str(" AND id IN (SELECT value FROM json_each(?))");
+            Some(serde_json::to_string(ids).map_err(|e| e.to_string())?)
         }
-        audit_db(&state.pool, "ensure_user_exists", "system", &name).await;
-        return Ok(row.id);
+        None => None,
+    };
+    let mut q = sqlx::query_as::<_, ClientLocationRow>(&query);
+    if let Some(ids) = &ids_json {
+        q = q.bind(ids);
+    }
+    let clients = q.fetch_all(pool).await.map_err(|e| e.to_string())?;
+
+    let mut updated = 0;
+    for client in clients {
+        let mut estimate: Option<(f64, &str)> = None;
+        if let (Some(yard_lat), Some(yard_lon)) = (settings.yard_latitude, settings.yard_longitude)
+        {
+            let by_zip = zip_centroid_for(pool, &client.physical_address_postal_code).await?;
+            let located = match by_zip {
+                Some(point) => Some((point, "zip")),
+                None => {
+                    let (lat, lon): (Option<f64>, Option<f64>) = sqlx::query_as(
+                        r#"
+                        SELECT AVG(latitude), AVG(longitude)
+                        FROM zip_centroids
+                        WHERE lower(city) = lower(?) AND state = upper(?)
+                        "#,
+                    )
+                    .bind(&client.physical_address_city)
+                    .bind(&client.physical_address_state)
+                    .fetch_one(pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                    lat.zip(lon).map(|point| (point, "city"))
+                }
+            };
+            estimate = located.map(|(point, source)| {
+                (
+                    geo::estimate_road_miles((yard_lat, yard_lon), point, settings.road_factor),
+                    source,
+                )
+            });
+        }
+        sqlx::query(
+            r#"
+            UPDATE clients
+            SET estimated_mileage = ?, estimated_mileage_source = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(estimate.map(|(miles, _)| miles))
+        .bind(estimate.map(|(_, source)| source))
+        .bind(&client.id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        updated += 1;
     }
+    Ok(updated)
+}
 
-    let id = Uuid::new_v4().to_string();
-    let is_driver = if wants_driver { 1 } else { 0 };
-    let hipaa = if wants_hipaa { 1 } else { 0 };
+#[tauri::command]
+async fn get_distance_settings(state: State<'_, AppState>) -> Result<DistanceSettings, String> {
+    load_distance_settings(&state.pool).await
+}
+
+#[tauri::command]
+async fn update_distance_settings(
+    state: State<'_, AppState>,
+    input: DistanceSettings,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<usize, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "update_distance_settings",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if role_val != "admin" {
+        return Err("Only admins can change the yard location".to_string());
+    }
+    if input.yard_latitude.is_some() != input.yard_longitude.is_some() {
+        return Err("Yard latitude and longitude must be set together".to_string());
+    }
+    if input
+        .yard_latitude
+        .is_some_and(|lat| !(-90.0..=90.0).contains(&lat))
+        || input
+            .yard_longitude
+            .is_some_and(|lon| !(-180.0..=180.0).contains(&lon))
+    {
+        return Err("Yard coordinates are out of range".to_string());
+    }
+    if !(1.0..=3.0).contains(&input.road_factor) {
+        return Err("Road factor must be between 1.0 and 3.0".to_string());
+    }
+    let previous = load_distance_settings(&state.pool).await?;
     sqlx::query(
         r#"
-        INSERT INTO users (
-            id, name, email, telephone,
-            physical_address_line1, physical_address_line2, physical_address_city, physical_address_state, physical_address_postal_code,
-            mailing_address_line1, mailing_address_line2, mailing_address_city, mailing_address_state, mailing_address_postal_code,
-            role, is_driver, hipaa_certified, created_at, updated_at, is_deleted
-        )
-        VALUES (
-            ?, ?, ?, ?,
-            ?, ?, ?, ?, ?,
-            ?, ?, ?, ?, ?,
-            ?, ?, ?, datetime('now'), datetime('now'), 0
+        INSERT INTO distance_settings (
+            id, yard_label, yard_latitude, yard_longitude, road_factor, updated_by, updated_at
         )
+        VALUES ('default', ?, ?, ?, ?, ?, datetime('now'))
+        ON CONFLICT(id) DO UPDATE SET
+            yard_label = excluded.yard_label,
+            yard_latitude = excluded.yard_latitude,
+            yard_longitude = excluded.yard_longitude,
+            road_factor = excluded.road_factor,
+            updated_by = excluded.updated_by,
+            updated_at = excluded.updated_at
         "#,
     )
-    .bind(&id)
-    .bind(&name)
-    .bind(&input.email)
-    .bind(&input.telephone)
-    .bind(&input.physical_address_line1)
-    .bind(&input.physical_address_line2)
-    .bind(&input.physical_address_city)
-    .bind(&input.physical_address_state)
-    .bind(&input.physical_address_postal_code)
-    .bind(&input.mailing_address_line1)
-    .bind(&input.mailing_address_line2)
-    .bind(&input.mailing_address_city)
-    .bind(&input.mailing_address_state)
-    .bind(&input.mailing_address_postal_code)
-    .bind(&role)
-    .bind(is_driver)
-    .bind(hipaa)
+    .bind(&input.yard_label)
+    .bind(input.yard_latitude)
+    .bind(input.yard_longitude)
+    .bind(input.road_factor)
+    .bind(&actor_val)
     .execute(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    audit_db(&state.pool, "ensure_user_exists", "system", &name).await;
-    Ok(id)
+    let describe = |s: &DistanceSettings| {
+        format!(
+            "{} ({:?}, {:?}) x{}",
+            s.yard_label.as_deref().unwrap_or("yard"),
+            s.yard_latitude,
+            s.yard_longitude,
+            s.road_factor
+        )
+    };
+    audit_change(
+        &state.pool,
+        "update_distance_settings",
+        &role_val,
+        &actor_val,
+        "distance_settings",
+        "default",
+        "yard",
+        Some(describe(&previous)),
+        Some(describe(&input)),
+    )
+    .await;
+    refresh_client_distance_estimates(&state.pool, None).await
 }
 
-#[derive(Debug, Deserialize)]
-struct WorkOrderAssignmentInput {
-    work_order_id: String,
-    assignees_json: Option<String>,
+/// Accepts the app's CSV layout or a Census Gazetteer ZCTA file; imported rows replace
+/// bundled ones for the same ZIP.
+#[tauri::command]
+async fn import_zip_centroids(
+    state: State<'_, AppState>,
+    file_text: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<ZipCentroidImportReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "import_zip_centroids", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can import ZIP centroids".to_string());
+    }
+    let (centroids, skipped) = geo::parse_centroids(&file_text)?;
+    let imported = upsert_zip_centroids(&state.pool, &centroids, "import", true).await?;
+    let clients_updated = refresh_client_distance_estimates(&state.pool, None).await?;
+    audit_change(
+        &state.pool,
+        "import_zip_centroids",
+        &role_val,
+        &actor_val,
+        "zip_centroids",
+        "*",
+        "import",
+        None,
+        Some(format!("{} imported, {} skipped", imported, skipped)),
+    )
+    .await;
+    Ok(ZipCentroidImportReport {
+        imported,
+        skipped,
+        clients_updated,
+    })
 }
 
-#[derive(Debug, Deserialize)]
-struct WorkOrderScheduleInput {
-    work_order_id: String,
-    scheduled_date: Option<String>,
+/// Estimated vs. recorded mileage. Only full-load completed deliveries count as actual
+/// trips; half loads share a run with their pair and pickups have no trip at all.
+#[tauri::command]
+async fn list_client_mileage_comparison(
+    state: State<'_, AppState>,
+    client_ids: Option<Vec<String>>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<ClientMileageComparison>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "list_client_mileage_comparison",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    let mut query = String::from(
+        r#"
+        SELECT
+            c.id AS client_id,
+            c.name,
+            c.estimated_mileage,
+            c.estimated_mileage_source,
+            c.default_mileage,
+            AVG(w.mileage) AS actual_average_mileage,
+            COUNT(w.id) AS actual_delivery_count,
+            ROUND(AVG(w.mileage) - c.estimated_mileage, 1) AS difference
+        FROM clients c
+        LEFT JOIN work_orders w
+          ON w.client_id = c.id
+         AND w.is_deleted = 0
+         AND lower(w.status) = 'completed'
+         AND w.mileage IS NOT NULL
+         AND (w.paired_order_id IS NULL OR w.paired_order_id = '')
+         AND lower(COALESCE(w.pickup_delivery_type, 'delivery')) != 'pickup'
+        WHERE c.is_deleted = 0
+        "#,
+    );
+    let ids_json = match &client_ids {
+        Some(ids) => {
+            query.push_str(" AND c.id IN (SELECT value FROM json_each(?))");
+            Some(serde_json::to_string(ids).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+    query.push_str(" GROUP BY c.id ORDER BY c.name");
+    let mut q = sqlx::query_as::<_, ClientMileageComparison>(&query);
+    if let Some(ids) = &ids_json {
+        q = q.bind(ids);
+    }
+    q.fetch_all(&state.pool).await.map_err(|e| e.to_string())
 }
 
-#[derive(Debug, Deserialize)]
-struct WorkOrderStatusInput {
-    work_order_id: String,
-    status: Option<String>,
-    mileage: Option<f64>,
-    work_hours: Option<f64>,
-    is_driver: Option<bool>,
+#[derive(Debug, Serialize)]
+struct SeasonDeliveryTotal {
+    season: String,
+    cords_delivered: f64,
+    deliveries: i64,
+    pickups: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct ClientDeliverySummary {
+    client_id: String,
+    current_season: String,
+    current_season_cords: f64,
+    total_cords: f64,
+    delivery_count: i64,
+    pickup_count: i64,
+    last_delivery_date: Option<String>,
+    average_mileage: Option<f64>,
+    open_order_count: i64,
+    open_order_ids: Vec<String>,
+    seasons: Vec<SeasonDeliveryTotal>,
 }
 
 #[derive(Debug, FromRow)]
-struct WorkOrderStatusRow {
+struct ClientOrderStatRow {
+    id: String,
+    client_id: String,
     status: String,
-    mileage: Option<f64>,
-    work_hours: Option<f64>,
+    scheduled_date: Option<String>,
+    created_at: String,
+    pickup_delivery_type: Option<String>,
     delivery_size_cords: Option<f64>,
     pickup_quantity_cords: Option<f64>,
+    mileage: Option<f64>,
 }
 
-#[derive(Debug, Serialize, FromRow)]
-struct DriverAvailabilityRow {
-    name: String,
-    availability_schedule: Option<String>,
-}
+fn summarize_client_orders(
+    client_id: &str,
+    orders: &[ClientOrderStatRow],
+    current_season: &str,
+) -> ClientDeliverySummary {
+    let mut seasons: Vec<SeasonDeliveryTotal> = Vec::new();
+    let mut summary = ClientDeliverySummary {
+        client_id: client_id.to_string(),
+        current_season: current_season.to_string(),
+        current_season_cords: 0.0,
+        total_cords: 0.0,
+        delivery_count: 0,
+        pickup_count: 0,
+        last_delivery_date: None,
+        average_mileage: None,
+        open_order_count: 0,
+        open_order_ids: Vec::new(),
+        seasons: Vec::new(),
+    };
+    let mut mileage_total = 0.0;
+    let mut mileage_count = 0;
 
-#[derive(Debug, Deserialize)]
-struct UserUpdateInput {
-    id: String,
-    email: Option<String>,
-    telephone: Option<String>,
-    physical_address_line1: Option<String>,
-    physical_address_line2: Option<String>,
-    physical_address_city: Option<String>,
-    physical_address_state: Option<String>,
-    physical_address_postal_code: Option<String>,
-    mailing_address_line1: Option<String>,
-    mailing_address_line2: Option<String>,
-    mailing_address_city: Option<String>,
-    mailing_address_state: Option<String>,
-    mailing_address_postal_code: Option<String>,
-    availability_notes: Option<String>,
-    availability_schedule: Option<String>,
-    driver_license_status: Option<String>,
-    driver_license_number: Option<String>,
-    driver_license_expires_on: Option<String>,
-    vehicle: Option<String>,
-    hipaa_certified: Option<bool>,
-    is_driver: Option<bool>,
-}
+    for order in orders {
+        let status = order.status.to_lowercase();
+        if status == "cancelled" {
+            continue;
+        }
+        if status != "completed" && status != "picked_up" {
+            summary.open_order_count += 1;
+            summary.open_order_ids.push(order.id.clone());
+            continue;
+        }
 
-#[tauri::command]
-async fn get_available_drivers(
-    state: State<'_, AppState>,
-    date: String, // ISO date string like "2025-12-30"
-) -> Result<Vec<String>, String> {
-    use chrono::Datelike;
+        let is_pickup = status == "picked_up"
+            || order
+                .pickup_delivery_type
+                .as_deref()
+                .is_some_and(|t| t.eq_ignore_ascii_case("pickup"));
+        // Same quantity the inventory adjustment uses for the order.
+        let cords = if status == "picked_up" {
+            order.pickup_quantity_cords.unwrap_or(0.0)
+        } else {
+            order.delivery_size_cords.unwrap_or(0.0)
+        };
+        let served_on = order
+            .scheduled_date
+            .clone()
+            .unwrap_or_else(|| order.created_at.clone());
+        let season = heating_season_for_date(&served_on).unwrap_or_else(|| "unknown".to_string());
+
+        let entry = match seasons.iter().position(|s| s.season == season) {
+            Some(idx) => &mut seasons[idx],
+            None => {
+                seasons.push(SeasonDeliveryTotal {
+                    season: season.clone(),
+                    cords_delivered: 0.0,
+                    deliveries: 0,
+                    pickups: 0,
+                });
+                seasons.last_mut().expect("season just pushed")
+            }
+        };
+        entry.cords_delivered += cords;
+        if is_pickup {
+            entry.pickups += 1;
+            summary.pickup_count += 1;
+        } else {
+            entry.deliveries += 1;
+            summary.delivery_count += 1;
+            if summary
+                .last_delivery_date
+                .as_deref()
+                .map_or(true, |last| served_on.as_str() > last)
+            {
+                summary.last_delivery_date = Some(served_on.clone());
+            }
+        }
+        summary.total_cords += cords;
+        if season == current_season {
+            summary.current_season_cords += cords;
+        }
+        if let Some(mileage) = order.mileage {
+            mileage_total += mileage;
+            mileage_count += 1;
+        }
+    }
 
-    let rows = sqlx::query_as::<_, DriverAvailabilityRow>(
+    if mileage_count > 0 {
+        summary.average_mileage = Some(mileage_total / mileage_count as f64);
+    }
+    seasons.sort_by(|a, b| b.season.cmp(&a.season));
+    summary.seasons = seasons;
+    summary
+}
+
+async fn load_client_delivery_summaries(
+    pool: &SqlitePool,
+    client_ids: Option<&[String]>,
+) -> Result<Vec<ClientDeliverySummary>, String> {
+    let mut query = String::from(
         r#"
-        SELECT name, availability_schedule
-        FROM users
+        SELECT
+            id, client_id, status, scheduled_date, created_at,
+            pickup_delivery_type,
+            COALESCE(delivered_cords, delivery_size_cords) AS delivery_size_cords,
+            pickup_quantity_cords, mileage
+        FROM work_orders
         WHERE is_deleted = 0
-          AND is_driver = 1
-        ORDER BY name ASC
         "#,
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    // Parse the date to get day of week
-    let parsed_date = chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
-        .map_err(|e| format!("Invalid date format: {}", e))?;
+    );
+    let ids_json = match client_ids {
+        Some(ids) => {
+            query.push_str(" AND client_id IN (SELECT value FROM json_each(?))");
+            Some(serde_json::to_string(ids).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+    query.push_str(" ORDER BY client_id");
 
-    let weekday = parsed_date.weekday();
-    let day_name = weekday.to_string().to_lowercase();
+    let mut q = sqlx::query_as::<_, ClientOrderStatRow>(&query);
+    if let Some(ids) = &ids_json {
+        q = q.bind(ids);
+    }
+    let rows = q.fetch_all(pool).await.map_err(|e| e.to_string())?;
 
-    let mut available = Vec::new();
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let current_season = heating_season_for_date(&today).unwrap_or_default();
+    let mut grouped: HashMap<String, Vec<ClientOrderStatRow>> = HashMap::new();
     for row in rows {
-        if let Some(schedule_json) = row.availability_schedule {
-            if let Ok(schedule) =
-                serde_json::from_str::<std::collections::HashMap<String, bool>>(&schedule_json)
-            {
-                if schedule.get(&day_name).copied().unwrap_or(false) {
-                    available.push(row.name);
-                }
-            } else {
-                // No schedule means always available
-                available.push(row.name);
-            }
-        } else {
-            // No schedule means always available
-            available.push(row.name);
-        }
+        grouped.entry(row.client_id.clone()).or_default().push(row);
     }
 
-    Ok(available)
+    // Requested clients without any orders still get an (empty) summary row.
+    let order: Vec<String> = match client_ids {
+        Some(ids) => ids.to_vec(),
+        None => {
+            let mut keys: Vec<String> = grouped.keys().cloned().collect();
+            keys.sort();
+            keys
+        }
+    };
+    Ok(order
+        .iter()
+        .map(|id| {
+            let orders = grouped.get(id).map(|v| v.as_slice()).unwrap_or(&[]);
+            summarize_client_orders(id, orders, &current_season)
+        })
+        .collect())
 }
 
 #[tauri::command]
-async fn update_user_flags(
+async fn get_client_delivery_summary(
     state: State<'_, AppState>,
-    input: UserUpdateInput,
+    client_id: String,
     role: Option<String>,
     actor: Option<String>,
-) -> Result<(), String> {
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+) -> Result<ClientDeliverySummary, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
     let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    if role_val != "admin" && role_val != "lead" {
-        return Err("Only admins or leads can update users".to_string());
+    audit_db(
+        &state.pool,
+        "get_client_delivery_summary",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    let exists: Option<String> =
+        sqlx::query_scalar("SELECT id FROM clients WHERE id = ? AND is_deleted = 0")
+            .bind(&client_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err("Client not found".to_string());
     }
-    audit_db(&state.pool, "update_user_flags", &role_val, &actor_val).await;
+    let ids = [client_id];
+    load_client_delivery_summaries(&state.pool, Some(&ids))
+        .await?
+        .pop()
+        .ok_or_else(|| "Client summary not available".to_string())
+}
 
-    let existing = sqlx::query!(
-        r#"
-        SELECT email, telephone,
-               physical_address_line1, physical_address_line2, physical_address_city,
-               physical_address_state, physical_address_postal_code,
-               mailing_address_line1, mailing_address_line2, mailing_address_city,
-               mailing_address_state, mailing_address_postal_code,
-               availability_notes, availability_schedule,
-               driver_license_status, driver_license_number, driver_license_expires_on,
-               vehicle, hipaa_certified, is_driver
-        FROM users
-        WHERE id = ? AND is_deleted = 0
-        "#,
-        input.id
+#[tauri::command]
+async fn list_client_delivery_summaries(
+    state: State<'_, AppState>,
+    client_ids: Option<Vec<String>>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<ClientDeliverySummary>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "list_client_delivery_summaries",
+        &role_val,
+        &actor_val,
     )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    .await;
+    load_client_delivery_summaries(&state.pool, client_ids.as_deref()).await
+}
 
-    let status_clean = input
-        .driver_license_status
-        .as_ref()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty());
-    let expiry_clean = input
-        .driver_license_expires_on
-        .as_ref()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty());
+#[derive(Debug, Deserialize)]
+struct AgencyInput {
+    id: Option<String>,
+    name: String,
+    aliases: Option<Vec<String>>,
+    contact_name: Option<String>,
+    telephone: Option<String>,
+    email: Option<String>,
+    address: Option<String>,
+    notes: Option<String>,
+    is_active: Option<bool>,
+}
 
-    let wants_driver = input.is_driver.unwrap_or(false);
-    let final_is_driver = wants_driver && status_clean.is_some() && expiry_clean.is_some();
-    let hipaa = input.hipaa_certified.unwrap_or(false);
+#[derive(Debug, Serialize, FromRow)]
+struct AgencyRow {
+    id: String,
+    name: String,
+    aliases_json: String,
+    contact_name: Option<String>,
+    telephone: Option<String>,
+    email: Option<String>,
+    address: Option<String>,
+    notes: Option<String>,
+    is_active: bool,
+    client_count: i64,
+    created_at: String,
+    updated_at: String,
+}
 
-    sqlx::query(
-        r#"
-        UPDATE users
-        SET email = ?,
-            telephone = ?,
-            physical_address_line1 = ?,
-            physical_address_line2 = ?,
-            physical_address_city = ?,
-            physical_address_state = ?,
-            physical_address_postal_code = ?,
-            mailing_address_line1 = ?,
-            mailing_address_line2 = ?,
-            mailing_address_city = ?,
-            mailing_address_state = ?,
-            mailing_address_postal_code = ?,
-            availability_notes = ?,
-            availability_schedule = ?,
-            driver_license_status = ?,
-            driver_license_number = ?,
-            driver_license_expires_on = ?,
-            vehicle = ?,
-            hipaa_certified = ?,
-            is_driver = ?,
-            updated_at = datetime('now')
-        WHERE id = ?
-        "#,
-    )
-    .bind(&input.email)
-    .bind(&input.telephone)
-    .bind(&input.physical_address_line1)
-    .bind(&input.physical_address_line2)
-    .bind(&input.physical_address_city)
-    .bind(&input.physical_address_state)
-    .bind(&input.physical_address_postal_code)
-    .bind(&input.mailing_address_line1)
-    .bind(&input.mailing_address_line2)
-    .bind(&input.mailing_address_city)
-    .bind(&input.mailing_address_state)
-    .bind(&input.mailing_address_postal_code)
-    .bind(&input.availability_notes)
-    .bind(&input.availability_schedule)
-    .bind(&status_clean)
-    .bind(&input.driver_license_number)
-    .bind(&expiry_clean)
-    .bind(&input.vehicle)
-    .bind(if hipaa { 1 } else { 0 })
-    .bind(if final_is_driver { 1 } else { 0 })
-    .bind(&input.id)
-    .execute(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    if let Some(prev) = existing {
-        let log_field = |field: &str, old_val: Option<String>, new_val: Option<String>| {
-            if old_val != new_val {
-                let pool = state.pool.clone();
-                let role = role_val.clone();
-                let actor = actor_val.clone();
-                let entity_id = input.id.clone();
-                let field = field.to_string();
-                tauri::async_runtime::spawn(async move {
-                    audit_change(
-                        &pool,
-                        "update_user_flags",
-                        &role,
-                        &actor,
-                        "users",
-                        &entity_id,
-                        &field,
-                        old_val,
-                        new_val,
-                    )
-                    .await;
-                });
-            }
-        };
-        log_field("email", prev.email, input.email.clone());
-        log_field("telephone", prev.telephone, input.telephone.clone());
-        log_field("physical_address_line1", prev.physical_address_line1, input.physical_address_line1.clone());
-        log_field("physical_address_line2", prev.physical_address_line2, input.physical_address_line2.clone());
-        log_field("physical_address_city", prev.physical_address_city, input.physical_address_city.clone());
-        log_field("physical_address_state", prev.physical_address_state, input.physical_address_state.clone());
-        log_field("physical_address_postal_code", prev.physical_address_postal_code, input.physical_address_postal_code.clone());
-        log_field("mailing_address_line1", prev.mailing_address_line1, input.mailing_address_line1.clone());
-        log_field("mailing_address_line2", prev.mailing_address_line2, input.mailing_address_line2.clone());
-        log_field("mailing_address_city", prev.mailing_address_city, input.mailing_address_city.clone());
-        log_field("mailing_address_state", prev.mailing_address_state, input.mailing_address_state.clone());
-        log_field("mailing_address_postal_code", prev.mailing_address_postal_code, input.mailing_address_postal_code.clone());
-        log_field("availability_notes", prev.availability_notes, input.availability_notes.clone());
-        log_field("availability_schedule", prev.availability_schedule, input.availability_schedule.clone());
-        log_field("driver_license_status", prev.driver_license_status, status_clean.clone());
-        log_field("driver_license_number", prev.driver_license_number, input.driver_license_number.clone());
-        log_field("driver_license_expires_on", prev.driver_license_expires_on, expiry_clean.clone());
-        log_field("vehicle", prev.vehicle, input.vehicle.clone());
-        log_field("hipaa_certified", Some(prev.hipaa_certified.to_string()), Some(hipaa.to_string()));
-        log_field("is_driver", Some(prev.is_driver.to_string()), Some(final_is_driver.to_string()));
-    }
+#[derive(Debug, FromRow)]
+struct AgencyKeyRow {
+    id: String,
+    name: String,
+    aliases_json: String,
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+struct AgencyValueRow {
+    field: String,
+    value: String,
+    client_count: i64,
+    agency_id: Option<String>,
+    agency_name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct CreateUserInput {
-    name: String,
-    email: Option<String>,
-    telephone: Option<String>,
-    physical_address_line1: Option<String>,
-    physical_address_line2: Option<String>,
-    physical_address_city: Option<String>,
-    physical_address_state: Option<String>,
-    physical_address_postal_code: Option<String>,
-    mailing_address_line1: Option<String>,
-    mailing_address_line2: Option<String>,
-    mailing_address_city: Option<String>,
-    mailing_address_state: Option<String>,
-    mailing_address_postal_code: Option<String>,
-    role: String,
-    is_driver: Option<bool>,
-    username: String,
-    password: String,
+struct AgencyMappingInput {
+    value: String,
+    // Map to an existing agency, or create one with `new_agency_name`.
+    agency_id: Option<String>,
+    new_agency_name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct CreateInvoiceInput {
-    work_order_id: String,
+#[derive(Debug, Serialize)]
+struct AgencyNormalizeReport {
+    agencies_created: usize,
+    aliases_added: usize,
+    clients_linked: usize,
+    clients_rewritten: usize,
 }
 
-#[tauri::command]
-async fn create_user(
-    state: State<'_, AppState>,
-    input: CreateUserInput,
-    role: Option<String>,
-) -> Result<String, String> {
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
-    if role_val != "admin" && role_val != "lead" {
-        return Err("Only admins or leads can create users".to_string());
-    }
-    audit_db(&state.pool, "create_user", &role_val, &input.name).await;
+#[derive(Debug, Serialize)]
+struct AgencyReferralStat {
+    agency_id: Option<String>,
+    agency_name: String,
+    season: String,
+    referrals: i64,
+    approvals: i64,
+    denials: i64,
+    clients_served: i64,
+    deliveries: i64,
+    cords_delivered: f64,
+}
 
-    let username = input.username.trim().to_lowercase();
-    if username.is_empty() {
-        return Err("Username is required".to_string());
+#[derive(Debug, FromRow)]
+struct AgencyReferralClientRow {
+    id: String,
+    referring_agency_id: Option<String>,
+    agency_name: Option<String>,
+    approval_status: String,
+    referred_on: String,
+}
+
+/// Case, punctuation and spacing differences ("St. Vincent's" vs "st vincents") do not make a
+/// different agency.
+fn agency_key(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .filter(|c| *c != '\'' && *c != '\u{2019}')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Maps every agency name and alias key to (agency id, agency name). Names win over aliases.
+async fn load_agency_keys(
+    conn: &mut sqlx::SqliteConnection,
+) -> Result<HashMap<String, (String, String)>, String> {
+    let agencies = sqlx::query_as::<_, AgencyKeyRow>(
+        "SELECT id, name, aliases_json FROM agencies WHERE is_deleted = 0",
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut keys = HashMap::new();
+    for agency in &agencies {
+        keys.insert(
+            agency_key(&agency.name),
+            (agency.id.clone(), agency.name.clone()),
+        );
     }
-    let password = input.password.trim().to_string();
-    if password.is_empty() {
-        return Err("Password is required".to_string());
+    for agency in &agencies {
+        let aliases: Vec<String> = serde_json::from_str(&agency.aliases_json).unwrap_or_default();
+        for alias in aliases {
+            keys.entry(agency_key(&alias))
+                .or_insert_with(|| (agency.id.clone(), agency.name.clone()));
+        }
     }
-    let existing_login = sqlx::query!(
+    Ok(keys)
+}
+
+/// Re-derives `clients.referring_agency_id` from the free-text fields. `referring_agency` wins;
+/// `how_did_they_hear_about_us` is used when it names a known agency. Returns the links that
+/// changed as (client id, old agency id, new agency id).
+async fn link_client_agencies(
+    conn: &mut sqlx::SqliteConnection,
+    client_ids: Option<&[String]>,
+) -> Result<Vec<(String, Option<String>, Option<String>)>, String> {
+    let keys = load_agency_keys(conn).await?;
+    let mut query = String::from(
         r#"
-        SELECT id FROM auth_users
-        WHERE lower(username) = lower(?)
-          AND is_deleted = 0
-        LIMIT 1
+        SELECT id, referring_agency, how_did_they_hear_about_us, referring_agency_id
+        FROM clients
+        WHERE is_deleted = 0
         "#,
-        username
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-    if existing_login.is_some() {
-        return Err("Username already exists".to_string());
+    );
+    let ids_json = match client_ids {
+        Some(ids) => {
+            query.push_str(" AND id IN (SELECT value FROM json_each(?))");
+            Some(serde_json::to_string(ids).map_err(|e| e.to_string())?)
+        }
+        None => None,
+    };
+    let mut q =
+        sqlx::query_as::<_, (String, Option<String>, Option<String>, Option<String>)>(&query);
+    if let Some(ids) = &ids_json {
+        q = q.bind(ids);
     }
+    let clients = q.fetch_all(&mut *conn).await.map_err(|e| e.to_string())?;
 
-    let id = Uuid::new_v4().to_string();
-    let is_driver = if input.is_driver.unwrap_or(false) {
-        1
-    } else {
-        0
+    let mut changed = Vec::new();
+    for (id, referring_agency, heard_about, current) in clients {
+        let lookup = |text: Option<&String>| {
+            text.and_then(|t| keys.get(&agency_key(t)))
+                .map(|(agency_id, _)| agency_id.clone())
+        };
+        let linked = lookup(referring_agency.as_ref()).or_else(|| lookup(heard_about.as_ref()));
+        if linked != current {
+            sqlx::query("UPDATE clients SET referring_agency_id = ? WHERE id = ?")
+                .bind(&linked)
+                .bind(&id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            changed.push((id, current, linked));
+        }
+    }
+    Ok(changed)
+}
+
+fn agency_stat_entry<'a>(
+    stats: &'a mut Vec<AgencyReferralStat>,
+    agency: &(Option<String>, String),
+    season: &str,
+) -> &'a mut AgencyReferralStat {
+    let idx = match stats
+        .iter()
+        .position(|s| s.agency_id == agency.0 && s.season == season)
+    {
+        Some(idx) => idx,
+        None => {
+            stats.push(AgencyReferralStat {
+                agency_id: agency.0.clone(),
+                agency_name: agency.1.clone(),
+                season: season.to_string(),
+                referrals: 0,
+                approvals: 0,
+                denials: 0,
+                clients_served: 0,
+                deliveries: 0,
+                cords_delivered: 0.0,
+            });
+            stats.len() - 1
+        }
     };
+    &mut stats[idx]
+}
 
-    sqlx::query(
+async fn load_agency_referral_report(
+    pool: &SqlitePool,
+    season: Option<&str>,
+) -> Result<Vec<AgencyReferralStat>, String> {
+    let season_filter = match season.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(s) => {
+            let (start, _) = heating_season_bounds(s)?;
+            heating_season_for_date(&start)
+        }
+        None => None,
+    };
+    let clients = sqlx::query_as::<_, AgencyReferralClientRow>(
         r#"
-        INSERT INTO users (
-            id, name, email, telephone,
-            physical_address_line1, physical_address_line2, physical_address_city, physical_address_state, physical_address_postal_code,
-            mailing_address_line1, mailing_address_line2, mailing_address_city, mailing_address_state, mailing_address_postal_code,
-            role, is_driver, created_at, updated_at, is_deleted
-        )
-        VALUES (
-            ?, ?, ?, ?,
-            ?, ?, ?, ?, ?,
-            ?, ?, ?, ?, ?,
-            ?, ?, datetime('now'), datetime('now'), 0
-        )
-        "#
+        SELECT
+            c.id,
+            c.referring_agency_id,
+            a.name AS agency_name,
+            c.approval_status,
+            COALESCE(c.date_of_onboarding, c.created_at) AS referred_on
+        FROM clients c
+        LEFT JOIN agencies a ON a.id = c.referring_agency_id
+        WHERE c.is_deleted = 0
+        "#,
     )
-    .bind(&id)
-    .bind(&input.name)
-    .bind(&input.email)
-    .bind(&input.telephone)
-    .bind(&input.physical_address_line1)
-    .bind(&input.physical_address_line2)
-    .bind(&input.physical_address_city)
-    .bind(&input.physical_address_state)
-    .bind(&input.physical_address_postal_code)
-    .bind(&input.mailing_address_line1)
-    .bind(&input.mailing_address_line2)
-    .bind(&input.mailing_address_city)
-    .bind(&input.mailing_address_state)
-    .bind(&input.mailing_address_postal_code)
-    .bind(&input.role)
-    .bind(is_driver)
-    .execute(&state.pool)
+    .fetch_all(pool)
     .await
     .map_err(|e| e.to_string())?;
-
-    let login_id = Uuid::new_v4().to_string();
-    let hashed = hash(&password, DEFAULT_COST).map_err(|e| e.to_string())?;
-    sqlx::query(
+    let orders = sqlx::query_as::<_, ClientOrderStatRow>(
         r#"
-        INSERT INTO auth_users (id, user_id, username, password, created_at, updated_at, is_deleted)
-        VALUES (?, ?, ?, ?, datetime('now'), datetime('now'), 0)
+        SELECT
+            w.id, w.client_id, w.status, w.scheduled_date, w.created_at,
+            w.pickup_delivery_type,
+            COALESCE(w.delivered_cords, w.delivery_size_cords) AS delivery_size_cords,
+            w.pickup_quantity_cords, w.mileage
+        FROM work_orders w
+        JOIN clients c ON c.id = w.client_id AND c.is_deleted = 0
+        WHERE w.is_deleted = 0
+          AND lower(w.status) IN ('completed', 'picked_up')
         "#,
     )
-    .bind(&login_id)
-    .bind(&id)
-    .bind(&username)
-    .bind(&hashed)
-    .execute(&state.pool)
+    .fetch_all(pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    Ok(id)
+    let agency_of: HashMap<&str, (Option<String>, String)> = clients
+        .iter()
+        .map(|c| {
+            (
+                c.id.as_str(),
+                (
+                    c.referring_agency_id.clone(),
+                    c.agency_name
+                        .clone()
+                        .unwrap_or_else(|| "Unassigned".to_string()),
+                ),
+            )
+        })
+        .collect();
+    let mut stats: Vec<AgencyReferralStat> = Vec::new();
+    let mut served: HashSet<(Option<String>, String, String)> = HashSet::new();
+    let wanted = |season: &str| season_filter.as_deref().map_or(true, |f| f == season);
+
+    // Referrals and approval outcomes count in the season the client was referred.
+    for client in &clients {
+        let season =
+            heating_season_for_date(&client.referred_on).unwrap_or_else(|| "unknown".to_string());
+        if !wanted(&season) {
+            continue;
+        }
+        let stat = agency_stat_entry(&mut stats, &agency_of[client.id.as_str()], &season);
+        stat.referrals += 1;
+        match client.approval_status.to_lowercase().as_str() {
+            "approved" => stat.approvals += 1,
+            "denied" => stat.denials += 1,
+            _ => {}
+        }
+    }
+    // Deliveries count in the season the wood went out, credited to the referring agency.
+    for order in &orders {
+        let served_on = order.scheduled_date.as_deref().unwrap_or(&order.created_at);
+        let season = heating_season_for_date(served_on).unwrap_or_else(|| "unknown".to_string());
+        if !wanted(&season) {
+            continue;
+        }
+        let Some(agency) = agency_of.get(order.client_id.as_str()) else {
+            continue;
+        };
+        let stat = agency_stat_entry(&mut stats, agency, &season);
+        let cords = if order.status.eq_ignore_ascii_case("picked_up") {
+            order.pickup_quantity_cords.unwrap_or(0.0)
+        } else {
+            order.delivery_size_cords.unwrap_or(0.0)
+        };
+        stat.deliveries += 1;
+        stat.cords_delivered += cords;
+        if served.insert((agency.0.clone(), season.clone(), order.client_id.clone())) {
+            stat.clients_served += 1;
+        }
+    }
+
+    stats.sort_by(|a, b| {
+        b.season
+            .cmp(&a.season)
+            .then(a.agency_id.is_none().cmp(&b.agency_id.is_none()))
+            .then(
+                a.agency_name
+                    .to_lowercase()
+                    .cmp(&b.agency_name.to_lowercase()),
+            )
+    });
+    Ok(stats)
 }
 
 #[tauri::command]
-async fn list_invoices(state: State<'_, AppState>) -> Result<Vec<InvoiceRow>, String> {
-    let rows = sqlx::query_as::<_, InvoiceRow>(
+async fn list_agencies(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<AgencyRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_agencies", &role_val, &actor_val).await;
+    sqlx::query_as::<_, AgencyRow>(
         r#"
         SELECT
-            id,
-            work_order_id,
-            invoice_number,
-            invoice_date,
-            subtotal,
-            tax,
-            total,
-            client_snapshot_json,
-            notes,
-            status,
-            created_at
-        FROM invoices
-        WHERE is_deleted = 0
-        ORDER BY invoice_date DESC, created_at DESC
+            a.id, a.name, a.aliases_json, a.contact_name, a.telephone, a.email, a.address,
+            a.notes, a.is_active, a.created_at, a.updated_at,
+            (
+                SELECT COUNT(*) FROM clients c
+                WHERE c.referring_agency_id = a.id AND c.is_deleted = 0
+            ) AS client_count
+        FROM agencies a
+        WHERE a.is_deleted = 0
+        ORDER BY a.is_active DESC, lower(a.name)
         "#,
     )
     .fetch_all(&state.pool)
     .await
-    .map_err(|e| e.to_string())?;
-
-    audit_db(&state.pool, "list_invoices", "unknown", "unknown").await;
-    Ok(rows)
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn list_pending_changes(state: State<'_, AppState>) -> Result<Vec<SyncRecord>, String> {
-    let service = SyncService::new(state.pool.clone());
-    service.list_pending_changes().await
-}
+async fn save_agency(
+    state: State<'_, AppState>,
+    input: AgencyInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "save_agency", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can manage agencies".to_string());
+    }
+    let name = input.name.trim().to_string();
+    if agency_key(&name).is_empty() {
+        return Err("Agency name is required".to_string());
+    }
+    let (telephone, phone_error) =
+        validation::normalize_and_validate_phone(input.telephone.as_deref().unwrap_or(""));
+    if let Some(err) = phone_error {
+        return Err(err);
+    }
+    let mut aliases: Vec<String> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::from([agency_key(&name)]);
+    for alias in input.aliases.unwrap_or_default() {
+        let alias = alias.trim().to_string();
+        let key = agency_key(&alias);
+        if !key.is_empty() && seen.insert(key) {
+            aliases.push(alias);
+        }
+    }
 
-#[tauri::command]
-fn print_invoice(window: tauri::WebviewWindow) -> Result<(), String> {
-    window.eval("window.print()").map_err(|e| e.to_string())
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    // A spelling can only point at one agency, otherwise linking would be ambiguous.
+    let keys = load_agency_keys(&mut tx).await?;
+    for key in &seen {
+        if let Some((other_id, other_name)) = keys.get(key) {
+            if input.id.as_deref() != Some(other_id.as_str()) {
+                return Err(format!(
+                    "'{}' already refers to agency '{}'",
+                    key, other_name
+                ));
+            }
+        }
+    }
+    let aliases_json = serde_json::to_string(&aliases).map_err(|e| e.to_string())?;
+    let blank_to_none = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let telephone = Some(telephone).filter(|t| !t.is_empty());
+    let is_active = input.is_active.unwrap_or(true);
+
+    let id = match &input.id {
+        Some(id) => {
+            let updated = sqlx::query(
+                r#"
+                UPDATE agencies
+                SET name = ?, aliases_json = ?, contact_name = ?, telephone = ?, email = ?,
+                    address = ?, notes = ?, is_active = ?,
+                    updated_at = datetime('now'), version = version + 1
+                WHERE id = ? AND is_deleted = 0
+                "#,
+            )
+            .bind(&name)
+            .bind(&aliases_json)
+            .bind(blank_to_none(&input.contact_name))
+            .bind(&telephone)
+            .bind(blank_to_none(&input.email))
+            .bind(blank_to_none(&input.address))
+            .bind(blank_to_none(&input.notes))
+            .bind(is_active)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            if updated.rows_affected() == 0 {
+                return Err("Agency not found".to_string());
+            }
+            id.clone()
+        }
+        None => {
+            let id = Uuid::new_v4().to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO agencies (
+                    id, name, aliases_json, contact_name, telephone, email, address, notes, is_active
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(&name)
+            .bind(&aliases_json)
+            .bind(blank_to_none(&input.contact_name))
+            .bind(&telephone)
+            .bind(blank_to_none(&input.email))
+            .bind(blank_to_none(&input.address))
+            .bind(blank_to_none(&input.notes))
+            .bind(is_active)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            id
+        }
+    };
+    // Renames and alias edits can move clients between agencies.
+    let relinked = link_client_agencies(&mut tx, None).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    audit_change(
+        &state.pool,
+        "save_agency",
+        &role_val,
+        &actor_val,
+        "agencies",
+        &id,
+        "agency",
+        None,
+        Some(format!(
+            "{} aliases={} active={}",
+            name, aliases_json, is_active
+        )),
+    )
+    .await;
+    for (client_id, old, new) in relinked {
+        audit_change(
+            &state.pool,
+            "save_agency",
+            &role_val,
+            &actor_val,
+            "clients",
+            &client_id,
+            "referring_agency_id",
+            old,
+            new,
+        )
+        .await;
+    }
+    Ok(id)
 }
 
 #[tauri::command]
-async fn create_invoice_from_work_order(
+async fn delete_agency(
     state: State<'_, AppState>,
-    input: CreateInvoiceInput,
-) -> Result<String, String> {
-    let work_order = sqlx::query!(
-        r#"
-        SELECT
-            id,
-            client_id,
-            client_name,
-            physical_address_line1,
-            physical_address_line2,
-            physical_address_city,
-            physical_address_state,
-            physical_address_postal_code,
-            mailing_address_line1,
-            mailing_address_line2,
-            mailing_address_city,
-            mailing_address_state,
-            mailing_address_postal_code,
-            telephone,
-            email,
-            scheduled_date,
-            delivery_size_label,
-            delivery_size_cords,
-            notes
-        FROM work_orders
-        WHERE id = ?
-          AND is_deleted = 0
-        LIMIT 1
-        "#,
-        input.work_order_id
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .ok_or_else(|| "Work order not found".to_string())?;
-
-    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-    let invoice_count = sqlx::query!(
-        r#"
-        SELECT COUNT(*) as "count!: i64"
-        FROM invoices
-        WHERE invoice_date = ?
-          AND is_deleted = 0
-        "#,
-        today
+    id: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "delete_agency", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can delete agencies".to_string());
+    }
+    let linked: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM clients WHERE referring_agency_id = ? AND is_deleted = 0",
     )
+    .bind(&id)
     .fetch_one(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
-    let seq = invoice_count.count + 1;
-    let invoice_number = format!("INV-{}-{:03}", today.replace("-", ""), seq);
-
-    let snapshot = serde_json::json!({
-        "client_id": work_order.client_id,
-        "client_name": work_order.client_name,
-        "physical_address_line1": work_order.physical_address_line1,
-        "physical_address_line2": work_order.physical_address_line2,
-        "physical_address_city": work_order.physical_address_city,
-        "physical_address_state": work_order.physical_address_state,
-        "physical_address_postal_code": work_order.physical_address_postal_code,
-        "mailing_address_line1": work_order.mailing_address_line1,
-        "mailing_address_line2": work_order.mailing_address_line2,
-        "mailing_address_city": work_order.mailing_address_city,
-        "mailing_address_state": work_order.mailing_address_state,
-        "mailing_address_postal_code": work_order.mailing_address_postal_code,
-        "telephone": work_order.telephone,
-        "email": work_order.email,
-        "scheduled_date": work_order.scheduled_date,
-        "delivery_size_label": work_order.delivery_size_label,
-        "delivery_size_cords": work_order.delivery_size_cords,
-        "notes": work_order.notes
-    });
-
-    let id = Uuid::new_v4().to_string();
+    if linked > 0 {
+        // Deleting would silently drop those referrals from the report; merge them instead.
+        return Err(format!(
+            "{} client(s) are linked to this agency; map their referral text to another agency first",
+            linked
+        ));
+    }
     sqlx::query(
         r#"
-        INSERT INTO invoices (
-            id, work_order_id, invoice_number, invoice_date,
-            subtotal, tax, total, client_snapshot_json, notes, status,
-            created_at, updated_at, is_deleted
-        )
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'draft', datetime('now'), datetime('now'), 0)
+        UPDATE agencies
+        SET is_deleted = 1, updated_at = datetime('now'), version = version + 1
+        WHERE id = ?
         "#,
     )
     .bind(&id)
-    .bind(&work_order.id)
-    .bind(&invoice_number)
-    .bind(&today)
-    .bind(0.0)
-    .bind(0.0)
-    .bind(0.0)
-    .bind(snapshot.to_string())
-    .bind(work_order.notes)
     .execute(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
+    audit_change(
+        &state.pool,
+        "delete_agency",
+        &role_val,
+        &actor_val,
+        "agencies",
+        &id,
+        "is_deleted",
+        Some("0".to_string()),
+        Some("1".to_string()),
+    )
+    .await;
+    Ok(())
+}
 
-    let item_id = Uuid::new_v4().to_string();
-    let description = match work_order.delivery_size_label.as_deref() {
-        Some(label) if !label.is_empty() => format!("Firewood delivery ({})", label),
-        _ => "Firewood delivery".to_string(),
-    };
-    let quantity = work_order.delivery_size_cords.unwrap_or(1.0);
-    sqlx::query(
+/// Distinct free-text referral values with the agency each currently resolves to, unmatched
+/// values first, so staff can work through the normalization backlog.
+#[tauri::command]
+async fn list_agency_values(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<AgencyValueRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_agency_values", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can manage agencies".to_string());
+    }
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    let keys = load_agency_keys(&mut conn).await?;
+    let raw = sqlx::query_as::<_, (String, String)>(
         r#"
-        INSERT INTO invoice_line_items (
-            id, invoice_id, description, quantity, unit_price, total,
-            created_at, updated_at, is_deleted
-        )
-        VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'), 0)
+        SELECT 'referring_agency', trim(referring_agency)
+        FROM clients
+        WHERE is_deleted = 0 AND trim(COALESCE(referring_agency, '')) != ''
+        UNION ALL
+        SELECT 'how_did_they_hear_about_us', trim(how_did_they_hear_about_us)
+        FROM clients
+        WHERE is_deleted = 0 AND trim(COALESCE(how_did_they_hear_about_us, '')) != ''
         "#,
     )
-    .bind(&item_id)
-    .bind(&id)
-    .bind(&description)
-    .bind(quantity)
-    .bind(0.0)
-    .bind(0.0)
-    .execute(&state.pool)
+    .fetch_all(&mut *conn)
     .await
     .map_err(|e| e.to_string())?;
 
-    audit_db(&state.pool, "create_invoice_from_work_order", "unknown", "unknown").await;
-    Ok(id)
-}
-
-#[derive(Debug, Deserialize)]
-struct LoginInput {
-    username: String,
-    password: String,
-}
-
-#[derive(Debug, Serialize, FromRow)]
-struct LoginResponse {
-    user_id: String,
-    name: String,
-    username: String,
-    role: String,
-    email: Option<String>,
-    telephone: Option<String>,
-    hipaa_certified: i64,
-    is_driver: i64,
-}
-
-#[derive(Debug, Deserialize)]
-struct ChangePasswordInput {
-    username: String,
-    current_password: String,
-    new_password: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct ResetPasswordInput {
-    user_id: String,
-    new_password: String,
+    let mut values: Vec<AgencyValueRow> = Vec::new();
+    for (field, value) in raw {
+        let key = agency_key(&value);
+        if let Some(existing) = values
+            .iter_mut()
+            .find(|v| v.field == field && agency_key(&v.value) == key)
+        {
+            existing.client_count += 1;
+            continue;
+        }
+        let agency = keys.get(&key);
+        values.push(AgencyValueRow {
+            field,
+            value,
+            client_count: 1,
+            agency_id: agency.map(|(id, _)| id.clone()),
+            agency_name: agency.map(|(_, name)| name.clone()),
+        });
+    }
+    values.sort_by(|a, b| {
+        a.agency_id
+            .is_some()
+            .cmp(&b.agency_id.is_some())
+            .then(b.client_count.cmp(&a.client_count))
+            .then(a.value.to_lowercase().cmp(&b.value.to_lowercase()))
+    });
+    Ok(values)
 }
 
+/// Records each free-text value as an alias of its agency (creating agencies on request) and
+/// relinks clients. With `rewrite_text`, `clients.referring_agency` is also replaced by the
+/// agency's canonical name.
 #[tauri::command]
-async fn login_user(state: State<'_, AppState>, input: LoginInput) -> Result<LoginResponse, String> {
-    let username = input.username.trim().to_lowercase();
-    let password = input.password.trim().to_string();
-    if username.is_empty() || password.is_empty() {
-        return Err("Username and password are required".to_string());
+async fn normalize_agency_values(
+    state: State<'_, AppState>,
+    mappings: Vec<AgencyMappingInput>,
+    rewrite_text: Option<bool>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<AgencyNormalizeReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "normalize_agency_values",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can manage agencies".to_string());
     }
+    let rewrite_text = rewrite_text.unwrap_or(false);
+    let mut report = AgencyNormalizeReport {
+        agencies_created: 0,
+        aliases_added: 0,
+        clients_linked: 0,
+        clients_rewritten: 0,
+    };
+    let mut rewrites: Vec<(String, Option<String>, String)> = Vec::new();
 
-    let row = sqlx::query!(
-        r#"
-        SELECT
-            au.user_id as user_id,
-            u.name as name,
-            au.username as username,
-            u.role as role,
-            u.email as email,
-            u.telephone as telephone,
-            COALESCE(u.hipaa_certified, 0) as hipaa_certified,
-            COALESCE(u.is_driver, 0) as is_driver,
-            au.password as password
-        FROM auth_users au
-        JOIN users u ON u.id = au.user_id
-        WHERE lower(au.username) = lower(?)
-          AND au.is_deleted = 0
-          AND u.is_deleted = 0
-        LIMIT 1
-        "#
-    , username
-    )
-    .fetch_optional(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    for mapping in &mappings {
+        let key = agency_key(&mapping.value);
+        if key.is_empty() {
+            continue;
+        }
+        let (agency_id, agency_name) = match (&mapping.agency_id, &mapping.new_agency_name) {
+            (Some(id), _) => {
+                let name: Option<String> =
+                    sqlx::query_scalar("SELECT name FROM agencies WHERE id = ? AND is_deleted = 0")
+                        .bind(id)
+                        .fetch_optional(&mut *tx)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                let name = name.ok_or_else(|| format!("Agency {} not found", id))?;
+                (id.clone(), name)
+            }
+            (None, Some(new_name)) => {
+                let new_name = new_name.trim().to_string();
+                if agency_key(&new_name).is_empty() {
+                    return Err("Agency name is required".to_string());
+                }
+                match load_agency_keys(&mut tx).await?.get(&agency_key(&new_name)) {
+                    Some(existing) => existing.clone(),
+                    None => {
+                        let id = Uuid::new_v4().to_string();
+                        sqlx::query("INSERT INTO agencies (id, name) VALUES (?, ?)")
+                            .bind(&id)
+                            .bind(&new_name)
+                            .execute(&mut *tx)
+                            .await
+                            .map_err(|e| e.to_string())?;
+                        report.agencies_created += 1;
+                        (id, new_name)
+                    }
+                }
+            }
+            (None, None) => {
+                return Err(format!(
+                    "Choose an agency (or a new agency name) for '{}'",
+                    mapping.value
+                ))
+            }
+        };
 
-    let row = row.ok_or_else(|| "Invalid username or password".to_string())?;
-    if !verify(&password, &row.password).map_err(|e| e.to_string())? {
-        return Err("Invalid username or password".to_string());
+        match load_agency_keys(&mut tx).await?.get(&key) {
+            Some((existing_id, _)) if *existing_id == agency_id => {}
+            Some((_, other_name)) => {
+                return Err(format!(
+                    "'{}' already refers to agency '{}'",
+                    mapping.value, other_name
+                ))
+            }
+            None => {
+                let aliases_json: String =
+                    sqlx::query_scalar("SELECT aliases_json FROM agencies WHERE id = ?")
+                        .bind(&agency_id)
+                        .fetch_one(&mut *tx)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                let mut aliases: Vec<String> =
+                    serde_json::from_str(&aliases_json).unwrap_or_default();
+                aliases.push(mapping.value.trim().to_string());
+                sqlx::query(
+                    r#"
+                    UPDATE agencies
+                    SET aliases_json = ?, updated_at = datetime('now'), version = version + 1
+                    WHERE id = ?
+                    "#,
+                )
+                .bind(serde_json::to_string(&aliases).map_err(|e| e.to_string())?)
+                .bind(&agency_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+                report.aliases_added += 1;
+            }
+        }
+
+        if rewrite_text {
+            let candidates = sqlx::query_as::<_, (String, Option<String>)>(
+                r#"
+                SELECT id, referring_agency
+                FROM clients
+                WHERE is_deleted = 0 AND trim(COALESCE(referring_agency, '')) != ''
+                "#,
+            )
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            for (client_id, text) in candidates {
+                let text_key = text.as_deref().map(agency_key).unwrap_or_default();
+                if text_key != key || text.as_deref() == Some(agency_name.as_str()) {
+                    continue;
+                }
+                sqlx::query(
+                    r#"
+                    UPDATE clients
+                    SET referring_agency = ?, updated_at = datetime('now')
+                    WHERE id = ?
+                    "#,
+                )
+                .bind(&agency_name)
+                .bind(&client_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| e.to_string())?;
+                rewrites.push((client_id, text, agency_name.clone()));
+            }
+        }
     }
+    let relinked = link_client_agencies(&mut tx, None).await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
 
-    audit_db(&state.pool, "login_user", &row.role, &row.username).await;
-    Ok(LoginResponse {
-        user_id: row.user_id,
-        name: row.name,
-        username: row.username,
-        role: row.role,
-        email: row.email,
-        telephone: row.telephone,
-        hipaa_certified: row.hipaa_certified,
-        is_driver: row.is_driver,
-    })
+    report.clients_linked = relinked.len();
+    report.clients_rewritten = rewrites.len();
+    for (client_id, old, new) in rewrites {
+        audit_change(
+            &state.pool,
+            "normalize_agency_values",
+            &role_val,
+            &actor_val,
+            "clients",
+            &client_id,
+            "referring_agency",
+            old,
+            Some(new),
+        )
+        .await;
+    }
+    for (client_id, old, new) in relinked {
+        audit_change(
+            &state.pool,
+            "normalize_agency_values",
+            &role_val,
+            &actor_val,
+            "clients",
+            &client_id,
+            "referring_agency_id",
+            old,
+            new,
+        )
+        .await;
+    }
+    Ok(report)
 }
 
-async fn change_password_with_pool(
+/// Referrals, approval outcomes and wood delivered per agency per heating season. Counts
+/// only, so staff can run it for partner agencies without PII access.
+#[tauri::command]
+async fn get_agency_referral_report(
+    state: State<'_, AppState>,
+    season: Option<String>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<AgencyReferralStat>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "get_agency_referral_report",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if !(role_val == "admin" || role_val == "lead" || is_staff_like(&role_val)) {
+        return Err("Only admins, leads or staff can view agency reports".to_string());
+    }
+    load_agency_referral_report(&state.pool, season.as_deref()).await
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientContactInput {
+    client_id: String,
+    contact_type: String,
+    direction: String,
+    contacted_at: Option<String>,
+    summary: Option<String>,
+    outcome: Option<String>,
+    follow_up_date: Option<String>,
+    staff_user_id: Option<String>,
+    staff_display: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct ClientContactRow {
+    id: String,
+    client_id: String,
+    contact_type: String,
+    direction: String,
+    contacted_at: String,
+    summary: Option<String>,
+    outcome: Option<String>,
+    follow_up_date: Option<String>,
+    staff_user_id: Option<String>,
+    staff_display: Option<String>,
+    created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ClientTimelineEvent {
+    occurred_at: String,
+    kind: String, // "contact", "work_order", "work_order_status" or "approval"
+    title: String,
+    detail: Option<String>,
+    reference_id: String,
+    actor: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct TimelineOrderRow {
+    id: String,
+    status: String,
+    scheduled_date: Option<String>,
+    created_at: String,
+    created_by_display: Option<String>,
+    delivery_size_cords: Option<f64>,
+    pickup_quantity_cords: Option<f64>,
+}
+
+#[derive(Debug, FromRow)]
+struct TimelineAuditRow {
+    entity_id: Option<String>,
+    actor: Option<String>,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    created_at: String,
+}
+
+const CONTACT_TYPES: [&str; 4] = ["call", "visit", "letter", "sms"];
+const CONTACT_DIRECTIONS: [&str; 2] = ["inbound", "outbound"];
+
+fn can_view_client_contacts(role: &str) -> bool {
+    role == "admin" || role == "lead" || is_staff_like(role)
+}
+
+async fn load_client_contacts(
     pool: &SqlitePool,
-    input: ChangePasswordInput,
-) -> Result<(), String> {
-    let username = input.username.trim().to_lowercase();
-    let current_password = input.current_password.trim();
-    let new_password = input.new_password.trim();
-    if username.is_empty() || current_password.is_empty() || new_password.is_empty() {
-        return Err("Username, current password, and new password are required".to_string());
+    client_id: &str,
+) -> Result<Vec<ClientContactRow>, String> {
+    sqlx::query_as::<_, ClientContactRow>(
+        r#"
+        SELECT
+            id, client_id, contact_type, direction, contacted_at, summary, outcome,
+            follow_up_date, staff_user_id, staff_display, created_at
+        FROM client_contacts
+        WHERE client_id = ? AND is_deleted = 0
+        ORDER BY contacted_at DESC
+        "#,
+    )
+    .bind(client_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Contacts, work orders, work order status changes and approval decisions, newest first.
+async fn load_client_timeline(
+    pool: &SqlitePool,
+    client_id: &str,
+) -> Result<Vec<ClientTimelineEvent>, String> {
+    let mut events: Vec<ClientTimelineEvent> = Vec::new();
+
+    for contact in load_client_contacts(pool, client_id).await? {
+        let mut detail: Vec<String> = Vec::new();
+        detail.extend(contact.summary.filter(|s| !s.trim().is_empty()));
+        if let Some(outcome) = contact.outcome.filter(|s| !s.trim().is_empty()) {
+            detail.push(format!("Outcome: {}", outcome));
+        }
+        if let Some(follow_up) = contact.follow_up_date {
+            detail.push(format!("Follow up {}", follow_up));
+        }
+        events.push(ClientTimelineEvent {
+            occurred_at: contact.contacted_at,
+            kind: "contact".to_string(),
+            title: format!("{} {}", contact.direction, contact.contact_type),
+            detail: (!detail.is_empty()).then(|| detail.join(" · ")),
+            reference_id: contact.id,
+            actor: contact.staff_display,
+        });
     }
 
-    let row = sqlx::query!(
+    let orders = sqlx::query_as::<_, TimelineOrderRow>(
         r#"
-        SELECT id, password
-        FROM auth_users
-        WHERE lower(username) = lower(?)
-          AND is_deleted = 0
-        LIMIT 1
+        SELECT
+            id, status, scheduled_date, created_at, created_by_display,
+            delivery_size_cords, pickup_quantity_cords
+        FROM work_orders
+        WHERE client_id = ? AND is_deleted = 0
         "#,
-        username
     )
-    .fetch_optional(pool)
+    .bind(client_id)
+    .fetch_all(pool)
     .await
-    .map_err(|e| e.to_string())?
-    .ok_or_else(|| "User not found".to_string())?;
+    .map_err(|e| e.to_string())?;
+    for order in &orders {
+        let cords = if order.status.eq_ignore_ascii_case("picked_up") {
+            order.pickup_quantity_cords
+        } else {
+            order.delivery_size_cords
+        };
+        let mut detail = vec![format!("Status: {}", order.status)];
+        if let Some(date) = &order.scheduled_date {
+            detail.push(format!("scheduled {}", date));
+        }
+        if let Some(cords) = cords {
+            detail.push(format!("{} cords", cords));
+        }
+        events.push(ClientTimelineEvent {
+            occurred_at: order.created_at.clone(),
+            kind: "work_order".to_string(),
+            title: "Work order created".to_string(),
+            detail: Some(detail.join(", ")),
+            reference_id: order.id.clone(),
+            actor: order.created_by_display.clone(),
+        });
+    }
 
-    if !verify(current_password, &row.password).map_err(|e| e.to_string())? {
-        return Err("Current password is incorrect".to_string());
+    let order_ids: Vec<&str> = orders.iter().map(|o| o.id.as_str()).collect();
+    let status_changes = sqlx::query_as::<_, TimelineAuditRow>(
+        r#"
+        SELECT entity_id, actor, old_value, new_value, created_at
+        FROM audit_logs
+        WHERE entity = 'work_orders'
+          AND field = 'status'
+          AND entity_id IN (SELECT value FROM json_each(?))
+        "#,
+    )
+    .bind(serde_json::to_string(&order_ids).map_err(|e| e.to_string())?)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    for change in status_changes {
+        events.push(ClientTimelineEvent {
+            occurred_at: change.created_at,
+            kind: "work_order_status".to_string(),
+            title: format!(
+                "Work order {} → {}",
+                change.old_value.as_deref().unwrap_or("new"),
+                change.new_value.as_deref().unwrap_or("")
+            ),
+            detail: None,
+            reference_id: change.entity_id.unwrap_or_default(),
+            actor: change.actor,
+        });
     }
 
-    let hashed = hash(new_password, DEFAULT_COST).map_err(|e| e.to_string())?;
-    sqlx::query(
+    let approvals = sqlx::query_as::<_, TimelineAuditRow>(
         r#"
-        UPDATE auth_users
-        SET password = ?, updated_at = datetime('now')
-        WHERE id = ?
+        SELECT entity_id, actor, old_value, new_value, created_at
+        FROM audit_logs
+        WHERE entity = 'clients'
+          AND entity_id = ?
+          AND field IN ('approval_status', 'denial_reason')
         "#,
     )
-    .bind(&hashed)
-    .bind(&row.id)
-    .execute(pool)
+    .bind(client_id)
+    .fetch_all(pool)
     .await
     .map_err(|e| e.to_string())?;
+    for change in approvals {
+        events.push(ClientTimelineEvent {
+            occurred_at: change.created_at,
+            kind: "approval".to_string(),
+            title: format!(
+                "Approval {} → {}",
+                change.old_value.as_deref().unwrap_or("none"),
+                change.new_value.as_deref().unwrap_or("none")
+            ),
+            detail: None,
+            reference_id: change.entity_id.unwrap_or_default(),
+            actor: change.actor,
+        });
+    }
 
-    audit_db(pool, "change_password", "unknown", &username).await;
-    Ok(())
+    // Timestamps are SQLite "YYYY-MM-DD HH:MM:SS" text, so string order is chronological.
+    events.sort_by(|a, b| b.occurred_at.cmp(&a.occurred_at));
+    Ok(events)
 }
 
 #[tauri::command]
-async fn change_password(state: State<'_, AppState>, input: ChangePasswordInput) -> Result<(), String> {
-    change_password_with_pool(&state.pool, input).await
-}
-
-async fn reset_password_with_pool(
-    pool: &SqlitePool,
-    input: ResetPasswordInput,
+async fn create_client_contact(
+    state: State<'_, AppState>,
+    input: ClientContactInput,
     role: Option<String>,
-) -> Result<(), String> {
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
-    if role_val != "admin" && role_val != "lead" {
-        return Err("Only admins or leads can reset passwords".to_string());
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "create_client_contact", &role_val, &actor_val).await;
+    if !can_view_client_contacts(&role_val) {
+        return Err("Only staff, leads or admins can log client contacts".to_string());
     }
-    let new_password = input.new_password.trim();
-    if new_password.is_empty() {
-        return Err("New password is required".to_string());
+    let contact_type = input.contact_type.trim().to_lowercase();
+    if !CONTACT_TYPES.contains(&contact_type.as_str()) {
+        return Err("Contact type must be call, visit, letter, or sms".to_string());
     }
-    let hashed = hash(new_password, DEFAULT_COST).map_err(|e| e.to_string())?;
+    let direction = input.direction.trim().to_lowercase();
+    if !CONTACT_DIRECTIONS.contains(&direction.as_str()) {
+        return Err("Direction must be inbound or outbound".to_string());
+    }
+    if let Some(date) = input.follow_up_date.as_deref().filter(|d| !d.is_empty()) {
+        if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return Err("Follow-up date must be YYYY-MM-DD".to_string());
+        }
+    }
+    let exists: Option<String> =
+        sqlx::query_scalar("SELECT id FROM clients WHERE id = ? AND is_deleted = 0")
+            .bind(&input.client_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err("Client not found".to_string());
+    }
+
+    let id = Uuid::new_v4().to_string();
     sqlx::query(
         r#"
-        UPDATE auth_users
-        SET password = ?, updated_at = datetime('now')
-        WHERE user_id = ?
-          AND is_deleted = 0
+        INSERT INTO client_contacts (
+            id, client_id, contact_type, direction, contacted_at, summary, outcome,
+            follow_up_date, staff_user_id, staff_display
+        )
+        VALUES (?, ?, ?, ?, COALESCE(?, datetime('now')), ?, ?, ?, ?, ?)
         "#,
     )
-    .bind(&hashed)
-    .bind(&input.user_id)
-    .execute(pool)
+    .bind(&id)
+    .bind(&input.client_id)
+    .bind(&contact_type)
+    .bind(&direction)
+    .bind(
+        input
+            .contacted_at
+            .as_deref()
+            .filter(|d| !d.trim().is_empty()),
+    )
+    .bind(&input.summary)
+    .bind(&input.outcome)
+    .bind(input.follow_up_date.as_deref().filter(|d| !d.is_empty()))
+    .bind(&input.staff_user_id)
+    .bind(input.staff_display.as_deref().unwrap_or(&actor_val))
+    .execute(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    audit_db(pool, "reset_password", &role_val, &input.user_id).await;
-    Ok(())
+    audit_change(
+        &state.pool,
+        "create_client_contact",
+        &role_val,
+        &actor_val,
+        "client_contacts",
+        &id,
+        "contact_type",
+        None,
+        Some(format!(
+            "{} {} for client {}",
+            direction, contact_type, input.client_id
+        )),
+    )
+    .await;
+    Ok(id)
 }
 
 #[tauri::command]
-async fn reset_password(
+async fn list_client_contacts(
     state: State<'_, AppState>,
-    input: ResetPasswordInput,
+    client_id: String,
     role: Option<String>,
-) -> Result<(), String> {
-    reset_password_with_pool(&state.pool, input, role).await
+    actor: Option<String>,
+) -> Result<Vec<ClientContactRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_client_contacts", &role_val, &actor_val).await;
+    if !can_view_client_contacts(&role_val) {
+        return Err("Only staff, leads or admins can view client contacts".to_string());
+    }
+    load_client_contacts(&state.pool, &client_id).await
 }
 
 #[tauri::command]
-async fn update_work_order_assignees(
+async fn get_client_timeline(
     state: State<'_, AppState>,
-    input: WorkOrderAssignmentInput,
+    client_id: String,
     role: Option<String>,
     actor: Option<String>,
-) -> Result<(), String> {
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+) -> Result<Vec<ClientTimelineEvent>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
     let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
-        return Err("Only staff or admins can assign drivers/helpers".to_string());
+    audit_db(&state.pool, "get_client_timeline", &role_val, &actor_val).await;
+    if !can_view_client_contacts(&role_val) {
+        return Err("Only staff, leads or admins can view the client timeline".to_string());
     }
-    audit_db(
-        &state.pool,
-        "update_work_order_assignees",
-        &role_val,
-        &actor_val,
-    )
-    .await;
+    load_client_timeline(&state.pool, &client_id).await
+}
 
-    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+#[derive(Debug, Deserialize)]
+struct EmergencyContactInput {
+    id: Option<String>,
+    client_id: String,
+    name: String,
+    relationship: Option<String>,
+    telephone: Option<String>,
+    alt_telephone: Option<String>,
+    email: Option<String>,
+    notes: Option<String>,
+    priority: Option<i64>,
+}
 
-    let existing = sqlx::query!(
-        r#"SELECT assignees_json FROM work_orders WHERE id = ? AND is_deleted = 0"#,
-        input.work_order_id
+#[derive(Debug, Clone, Serialize, FromRow)]
+struct EmergencyContactRow {
+    id: String,
+    client_id: String,
+    name: String,
+    relationship: Option<String>,
+    telephone: Option<String>,
+    alt_telephone: Option<String>,
+    email: Option<String>,
+    notes: Option<String>,
+    priority: i64,
+    updated_at: String,
+}
+
+/// A recurring quiet period; `days` are the days the window starts on (empty means every day)
+/// and a window may run past midnight, e.g. 20:00-08:00.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DoNotCallWindow {
+    days: Vec<String>,
+    start: String,
+    end: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommunicationPreferencesInput {
+    client_id: String,
+    preferred_language: Option<String>,
+    best_channel: Option<String>,
+    do_not_call_windows: Option<Vec<DoNotCallWindow>>,
+    accessibility_needs: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CommunicationPreferences {
+    client_id: String,
+    preferred_language: Option<String>,
+    best_channel: Option<String>,
+    do_not_call_windows: Vec<DoNotCallWindow>,
+    accessibility_needs: Option<String>,
+    updated_by: Option<String>,
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct CommunicationPreferencesRow {
+    client_id: String,
+    preferred_language: Option<String>,
+    best_channel: Option<String>,
+    do_not_call_json: String,
+    accessibility_needs: Option<String>,
+    updated_by: Option<String>,
+    updated_at: Option<String>,
+}
+
+impl From<CommunicationPreferencesRow> for CommunicationPreferences {
+    fn from(row: CommunicationPreferencesRow) -> Self {
+        CommunicationPreferences {
+            client_id: row.client_id,
+            preferred_language: row.preferred_language,
+            best_channel: row.best_channel,
+            do_not_call_windows: serde_json::from_str(&row.do_not_call_json).unwrap_or_default(),
+            accessibility_needs: row.accessibility_needs,
+            updated_by: row.updated_by,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+const COMMUNICATION_CHANNELS: [&str; 5] = ["call", "sms", "email", "letter", "visit"];
+const WEEKDAY_CODES: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+fn validate_do_not_call_windows(
+    windows: Vec<DoNotCallWindow>,
+) -> Result<Vec<DoNotCallWindow>, String> {
+    windows
+        .into_iter()
+        .map(|w| {
+            let days: Vec<String> = w.days.iter().map(|d| d.trim().to_lowercase()).collect();
+            if let Some(bad) = days.iter().find(|d| !WEEKDAY_CODES.contains(&d.as_str())) {
+                return Err(format!("Unknown day '{}' (use mon..sun)", bad));
+            }
+            let parse = |t: &str| chrono::NaiveTime::parse_from_str(t.trim(), "%H:%M");
+            let (start, end) = match (parse(&w.start), parse(&w.end)) {
+                (Ok(start), Ok(end)) if start != end => (start, end),
+                _ => {
+                    return Err(
+                        "Do-not-call windows need distinct HH:MM start and end times".to_string(),
+                    )
+                }
+            };
+            Ok(DoNotCallWindow {
+                days,
+                start: start.format("%H:%M").to_string(),
+                end: end.format("%H:%M").to_string(),
+            })
+        })
+        .collect()
+}
+
+fn in_do_not_call_window(windows: &[DoNotCallWindow], at: chrono::NaiveDateTime) -> bool {
+    use chrono::Datelike;
+    let day_code =
+        |date: chrono::NaiveDate| WEEKDAY_CODES[date.weekday().num_days_from_monday() as usize];
+    let today = day_code(at.date());
+    let yesterday = day_code(at.date() - chrono::Duration::days(1));
+    let time = at.time();
+    windows.iter().any(|w| {
+        let (Ok(start), Ok(end)) = (
+            chrono::NaiveTime::parse_from_str(&w.start, "%H:%M"),
+            chrono::NaiveTime::parse_from_str(&w.end, "%H:%M"),
+        ) else {
+            return false;
+        };
+        let starts_on = |day: &str| w.days.is_empty() || w.days.iter().any(|d| d == day);
+        if start < end {
+            starts_on(today) && time >= start && time < end
+        } else {
+            (starts_on(today) && time >= start) || (starts_on(yesterday) && time < end)
+        }
+    })
+}
+
+fn describe_emergency_contact(contact: &EmergencyContactRow) -> String {
+    format!(
+        "{}{}{}",
+        contact.name,
+        contact
+            .relationship
+            .as_deref()
+            .map(|r| format!(" ({})", r))
+            .unwrap_or_default(),
+        contact
+            .telephone
+            .as_deref()
+            .map(|t| format!(" {}", t))
+            .unwrap_or_default()
     )
-    .fetch_optional(&mut *tx)
-    .await
-    .map_err(|e| e.to_string())?;
+}
 
-    sqlx::query(
+async fn load_emergency_contacts(
+    pool: &SqlitePool,
+    client_ids: &[String],
+) -> Result<Vec<EmergencyContactRow>, String> {
+    sqlx::query_as::<_, EmergencyContactRow>(
         r#"
-        UPDATE work_orders
-        SET assignees_json = ?, updated_at = datetime('now')
-        WHERE id = ?
+        SELECT
+            id, client_id, name, relationship, telephone, alt_telephone, email, notes,
+            priority, updated_at
+        FROM client_emergency_contacts
+        WHERE is_deleted = 0
+          AND client_id IN (SELECT value FROM json_each(?))
+        ORDER BY client_id, priority, name
         "#,
     )
-    .bind(&input.assignees_json)
-    .bind(&input.work_order_id)
-    .execute(&mut *tx)
+    .bind(serde_json::to_string(client_ids).map_err(|e| e.to_string())?)
+    .fetch_all(pool)
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| e.to_string())
+}
 
-    sqlx::query(
+async fn load_communication_preferences(
+    pool: &SqlitePool,
+    client_ids: &[String],
+) -> Result<Vec<CommunicationPreferences>, String> {
+    let rows = sqlx::query_as::<_, CommunicationPreferencesRow>(
         r#"
-        UPDATE delivery_events
-        SET assigned_user_ids_json = COALESCE(?, '[]'), updated_at = datetime('now')
-        WHERE work_order_id = ?
+        SELECT
+            client_id, preferred_language, best_channel, do_not_call_json,
+            accessibility_needs, updated_by, updated_at
+        FROM client_communication_preferences
+        WHERE client_id IN (SELECT value FROM json_each(?))
         "#,
     )
-    .bind(&input.assignees_json)
-    .bind(&input.work_order_id)
-    .execute(&mut *tx)
+    .bind(serde_json::to_string(client_ids).map_err(|e| e.to_string())?)
+    .fetch_all(pool)
     .await
     .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(CommunicationPreferences::from)
+        .collect())
+}
 
-    if let Some(prev) = existing {
-        let old_val = prev.assignees_json;
-        let new_val = input.assignees_json.clone();
-        if old_val != new_val {
-            audit_change(
-                &state.pool,
-                "update_work_order_assignees",
-                &role_val,
-                &actor_val,
-                "work_orders",
-                &input.work_order_id,
-                "assignees_json",
-                old_val,
-                new_val,
-            )
-            .await;
+#[tauri::command]
+async fn list_client_emergency_contacts(
+    state: State<'_, AppState>,
+    client_id: String,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<Vec<EmergencyContactRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "list_client_emergency_contacts",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false)) {
+        return Err("Only admins or HIPAA-certified leads can view emergency contacts".to_string());
+    }
+    load_emergency_contacts(&state.pool, &[client_id]).await
+}
+
+#[tauri::command]
+async fn save_client_emergency_contact(
+    state: State<'_, AppState>,
+    input: EmergencyContactInput,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "save_client_emergency_contact",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false)) {
+        return Err("Only admins or HIPAA-certified leads can edit emergency contacts".to_string());
+    }
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Emergency contact name is required".to_string());
+    }
+    let mut phones = Vec::new();
+    for phone in [&input.telephone, &input.alt_telephone] {
+        let (normalized, error) =
+            validation::normalize_and_validate_phone(phone.as_deref().unwrap_or(""));
+        if let Some(err) = error {
+            return Err(err);
         }
+        phones.push(Some(normalized).filter(|p| !p.is_empty()));
+    }
+    if phones.iter().all(Option::is_none)
+        && input.email.as_deref().map_or(true, |e| e.trim().is_empty())
+    {
+        return Err("An emergency contact needs a phone number or email".to_string());
+    }
+    let exists: Option<String> =
+        sqlx::query_scalar("SELECT id FROM clients WHERE id = ? AND is_deleted = 0")
+            .bind(&input.client_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err("Client not found".to_string());
     }
 
-    tx.commit().await.map_err(|e| e.to_string())?;
+    let previous = match &input.id {
+        Some(id) => Some(
+            sqlx::query_as::<_, EmergencyContactRow>(
+                r#"
+                SELECT
+                    id, client_id, name, relationship, telephone, alt_telephone, email, notes,
+                    priority, updated_at
+                FROM client_emergency_contacts
+                WHERE id = ? AND client_id = ? AND is_deleted = 0
+                "#,
+            )
+            .bind(id)
+            .bind(&input.client_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Emergency contact not found".to_string())?,
+        ),
+        None => None,
+    };
+    let priority = match input.priority {
+        Some(p) if p >= 1 => p,
+        Some(_) => return Err("Priority must be 1 or higher".to_string()),
+        None => match &previous {
+            Some(p) => p.priority,
+            None => sqlx::query_scalar::<_, i64>(
+                r#"
+                SELECT COALESCE(MAX(priority), 0) + 1
+                FROM client_emergency_contacts
+                WHERE client_id = ? AND is_deleted = 0
+                "#,
+            )
+            .bind(&input.client_id)
+            .fetch_one(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?,
+        },
+    };
 
-    Ok(())
+    let id = input
+        .id
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    sqlx::query(
+        r#"
+        INSERT INTO client_emergency_contacts (
+            id, client_id, name, relationship, telephone, alt_telephone, email, notes, priority
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(id) DO UPDATE SET
+            name = excluded.name,
+            relationship = excluded.relationship,
+            telephone = excluded.telephone,
+            alt_telephone = excluded.alt_telephone,
+            email = excluded.email,
+            notes = excluded.notes,
+            priority = excluded.priority,
+            updated_at = datetime('now'),
+            version = version + 1
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.client_id)
+    .bind(&name)
+    .bind(
+        input
+            .relationship
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty()),
+    )
+    .bind(&phones[0])
+    .bind(&phones[1])
+    .bind(
+        input
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty()),
+    )
+    .bind(input.notes.as_deref().filter(|n| !n.trim().is_empty()))
+    .bind(priority)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let saved = EmergencyContactRow {
+        id: id.clone(),
+        client_id: input.client_id.clone(),
+        name,
+        relationship: input.relationship.clone(),
+        telephone: phones[0].clone(),
+        alt_telephone: phones[1].clone(),
+        email: input.email.clone(),
+        notes: None,
+        priority,
+        updated_at: String::new(),
+    };
+    audit_change(
+        &state.pool,
+        "save_client_emergency_contact",
+        &role_val,
+        &actor_val,
+        "clients",
+        &input.client_id,
+        "emergency_contact",
+        previous.as_ref().map(describe_emergency_contact),
+        Some(describe_emergency_contact(&saved)),
+    )
+    .await;
+    Ok(id)
 }
 
 #[tauri::command]
-async fn update_work_order_schedule(
+async fn delete_client_emergency_contact(
     state: State<'_, AppState>,
-    input: WorkOrderScheduleInput,
+    id: String,
     role: Option<String>,
     actor: Option<String>,
+    hipaa_certified: Option<bool>,
 ) -> Result<(), String> {
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
     let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
-        return Err("Only staff or admin may schedule work orders".to_string());
-    }
     audit_db(
         &state.pool,
-        "update_work_order_schedule",
+        "delete_client_emergency_contact",
         &role_val,
         &actor_val,
     )
     .await;
-
-    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
-
-    let existing = sqlx::query!(
+    if !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false)) {
+        return Err("Only admins or HIPAA-certified leads can edit emergency contacts".to_string());
+    }
+    let existing = sqlx::query_as::<_, EmergencyContactRow>(
         r#"
-        SELECT scheduled_date, client_name, status
-        FROM work_orders
+        SELECT
+            id, client_id, name, relationship, telephone, alt_telephone, email, notes,
+            priority, updated_at
+        FROM client_emergency_contacts
         WHERE id = ? AND is_deleted = 0
         "#,
-        input.work_order_id
     )
-    .fetch_optional(&mut *tx)
+    .bind(&id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Emergency contact not found".to_string())?;
+    sqlx::query(
+        r#"
+        UPDATE client_emergency_contacts
+        SET is_deleted = 1, updated_at = datetime('now'), version = version + 1
+        WHERE id = ?
+        "#,
+    )
+    .bind(&id)
+    .execute(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
+    audit_change(
+        &state.pool,
+        "delete_client_emergency_contact",
+        &role_val,
+        &actor_val,
+        "clients",
+        &existing.client_id,
+        "emergency_contact",
+        Some(describe_emergency_contact(&existing)),
+        None,
+    )
+    .await;
+    Ok(())
+}
 
-    let existing = match existing {
-        Some(e) => e,
-        None => return Err("Work order not found or has been deleted".to_string()),
+#[tauri::command]
+async fn get_client_communication_preferences(
+    state: State<'_, AppState>,
+    client_id: String,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<Option<CommunicationPreferences>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "get_client_communication_preferences",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false)) {
+        return Err(
+            "Only admins or HIPAA-certified leads can view communication preferences".to_string(),
+        );
+    }
+    Ok(load_communication_preferences(&state.pool, &[client_id])
+        .await?
+        .pop())
+}
+
+#[tauri::command]
+async fn save_client_communication_preferences(
+    state: State<'_, AppState>,
+    input: CommunicationPreferencesInput,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<CommunicationPreferences, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "save_client_communication_preferences",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false)) {
+        return Err(
+            "Only admins or HIPAA-certified leads can edit communication preferences".to_string(),
+        );
+    }
+    let clean = |v: &Option<String>| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
     };
+    let best_channel = clean(&input.best_channel).map(|c| c.to_lowercase());
+    if let Some(channel) = &best_channel {
+        if !COMMUNICATION_CHANNELS.contains(&channel.as_str()) {
+            return Err("Best channel must be call, sms, email, letter, or visit".to_string());
+        }
+    }
+    let windows = validate_do_not_call_windows(input.do_not_call_windows.unwrap_or_default())?;
+    let exists: Option<String> =
+        sqlx::query_scalar("SELECT id FROM clients WHERE id = ? AND is_deleted = 0")
+            .bind(&input.client_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err("Client not found".to_string());
+    }
+    let previous =
+        load_communication_preferences(&state.pool, std::slice::from_ref(&input.client_id))
+            .await?
+            .pop();
 
+    let windows_json = serde_json::to_string(&windows).map_err(|e| e.to_string())?;
     sqlx::query(
         r#"
-        UPDATE work_orders
-        SET scheduled_date = ?, updated_at = datetime('now')
-        WHERE id = ? AND is_deleted = 0
+        INSERT INTO client_communication_preferences (
+            client_id, preferred_language, best_channel, do_not_call_json,
+            accessibility_needs, updated_by
+        )
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(client_id) DO UPDATE SET
+            preferred_language = excluded.preferred_language,
+            best_channel = excluded.best_channel,
+            do_not_call_json = excluded.do_not_call_json,
+            accessibility_needs = excluded.accessibility_needs,
+            updated_by = excluded.updated_by,
+            updated_at = datetime('now'),
+            version = version + 1
         "#,
     )
-    .bind(&input.scheduled_date)
-    .bind(&input.work_order_id)
-    .execute(&mut *tx)
+    .bind(&input.client_id)
+    .bind(clean(&input.preferred_language))
+    .bind(&best_channel)
+    .bind(&windows_json)
+    .bind(clean(&input.accessibility_needs))
+    .bind(&actor_val)
+    .execute(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
 
-    let next_status = match input.scheduled_date.as_ref() {
-        Some(_) => {
-            let current = existing.status.to_lowercase();
-            if ["completed", "cancelled", "picked_up", "in_progress", "issue"].contains(&current.as_str()) {
-                None
-            } else {
-                Some("scheduled".to_string())
-            }
-        }
-        None => None,
-    };
-
-    if let Some(status) = &next_status {
-        sqlx::query(
-            r#"
-            UPDATE work_orders
-            SET status = ?, updated_at = datetime('now')
-            WHERE id = ? AND is_deleted = 0
-            "#,
-        )
-        .bind(status)
-        .bind(&input.work_order_id)
-        .execute(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-    }
-
-    if let Some(start_date) = &input.scheduled_date {
-        let existing_delivery = sqlx::query!(
-            r#"SELECT id FROM delivery_events WHERE work_order_id = ? AND is_deleted = 0 LIMIT 1"#,
-            input.work_order_id
-        )
-        .fetch_optional(&mut *tx)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        if let Some(row) = existing_delivery {
-            sqlx::query(
-                r#"
-                UPDATE delivery_events
-                SET start_date = ?, updated_at = datetime('now')
-                WHERE id = ?
-                "#,
-            )
-            .bind(start_date)
-            .bind(&row.id)
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| e.to_string())?;
-        } else {
-            let delivery_id = Uuid::new_v4().to_string();
-            sqlx::query(
-                r#"
-                INSERT INTO delivery_events (
-                    id, title, description, event_type, work_order_id,
-                    start_date, end_date, color_code, assigned_user_ids_json
-                )
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
-                "#,
-            )
-            .bind(&delivery_id)
-            .bind(format!("Delivery for {}", existing.client_name))
-            .bind::<Option<String>>(None)
-            .bind("delivery")
-            .bind(&input.work_order_id)
-            .bind(start_date)
-            .bind::<Option<String>>(None)
-            .bind("#e67f1e")
-            .bind("[]")
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| e.to_string())?;
-        }
-    }
-
-    if existing.scheduled_date != input.scheduled_date {
-        audit_change(
-            &state.pool,
-            "update_work_order_schedule",
-            &role_val,
-            &actor_val,
-            "work_orders",
-            &input.work_order_id,
-            "scheduled_date",
-            existing.scheduled_date,
-            input.scheduled_date,
-        )
-        .await;
-    }
-
-    if let Some(status) = next_status {
-        if existing.status != status {
+    let saved = load_communication_preferences(&state.pool, std::slice::from_ref(&input.client_id))
+        .await?
+        .pop()
+        .ok_or_else(|| "Communication preferences not saved".to_string())?;
+    let old = previous.as_ref();
+    let changes = [
+        (
+            "preferred_language",
+            old.and_then(|p| p.preferred_language.clone()),
+            saved.preferred_language.clone(),
+        ),
+        (
+            "best_channel",
+            old.and_then(|p| p.best_channel.clone()),
+            saved.best_channel.clone(),
+        ),
+        (
+            "do_not_call_windows",
+            old.map(|p| serde_json::to_string(&p.do_not_call_windows).unwrap_or_default()),
+            Some(windows_json),
+        ),
+        (
+            "accessibility_needs",
+            old.and_then(|p| p.accessibility_needs.clone()),
+            saved.accessibility_needs.clone(),
+        ),
+    ];
+    for (field, old_value, new_value) in changes {
+        if old_value != new_value {
             audit_change(
                 &state.pool,
-                "update_work_order_schedule",
+                "save_client_communication_preferences",
                 &role_val,
                 &actor_val,
-                "work_orders",
-                &input.work_order_id,
-                "status",
-                Some(existing.status.clone()),
-                Some(status.clone()),
+                "clients",
+                &input.client_id,
+                field,
+                old_value,
+                new_value,
             )
             .await;
         }
     }
+    Ok(saved)
+}
 
-    tx.commit().await.map_err(|e| e.to_string())?;
+#[derive(Debug, Serialize)]
+struct RunSheetStop {
+    work_order_id: String,
+    scheduled_date: Option<String>,
+    status: String,
+    client_name: String,
+    telephone: Option<String>,
+    physical_address_line1: Option<String>,
+    physical_address_line2: Option<String>,
+    physical_address_city: Option<String>,
+    physical_address_state: Option<String>,
+    physical_address_postal_code: Option<String>,
+    directions: Option<String>,
+    gate_combo: Option<String>,
+    delivery_size_cords: Option<f64>,
+    wood_size_label: Option<String>,
+    pickup_delivery_type: Option<String>,
+    assignees_json: Option<String>,
+    site_notes: SiteNotes,
+    preferred_language: Option<String>,
+    best_channel: Option<String>,
+    accessibility_needs: Option<String>,
+    do_not_call_windows: Vec<DoNotCallWindow>,
+    do_not_call_now: bool,
+    emergency_contacts: Vec<EmergencyContactRow>,
+}
 
-    Ok(())
+#[derive(Debug, Serialize)]
+struct RunSheet {
+    date: String,
+    driver: Option<String>,
+    // False when the caller may not see phone numbers; contacts are then left out entirely.
+    contact_details_included: bool,
+    stops: Vec<RunSheetStop>,
+}
+
+#[derive(Debug, FromRow)]
+struct RunSheetOrderRow {
+    id: String,
+    client_id: String,
+    scheduled_date: Option<String>,
+    status: String,
+    client_name: String,
+    telephone: Option<String>,
+    physical_address_line1: Option<String>,
+    physical_address_line2: Option<String>,
+    physical_address_city: Option<String>,
+    physical_address_state: Option<String>,
+    physical_address_postal_code: Option<String>,
+    directions: Option<String>,
+    gate_combo: Option<String>,
+    delivery_size_cords: Option<f64>,
+    wood_size_label: Option<String>,
+    pickup_delivery_type: Option<String>,
+    assignees_json: Option<String>,
+    #[sqlx(flatten)]
+    site_notes: SiteNotes,
 }
 
+/// One day's stops for a driver (or every stop, for office roles). Drivers see phone numbers,
+/// gate codes, accessibility needs and emergency contacts for the stops assigned to them, the
+/// same access `list_work_orders` gives them; volunteers get site notes and language only.
 #[tauri::command]
-async fn update_work_order_status(
+async fn get_driver_run_sheet(
     state: State<'_, AppState>,
-    input: WorkOrderStatusInput,
+    date: String,
+    username: Option<String>,
     role: Option<String>,
-    actor: Option<String>,
-) -> Result<(), String> {
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
-    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
-    let driver_capable = input.is_driver.unwrap_or(false);
+    hipaa_certified: Option<bool>,
+    is_driver: Option<bool>,
+) -> Result<RunSheet, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let username_val = username.unwrap_or_default();
     audit_db(
         &state.pool,
-        "update_work_order_status",
+        "get_driver_run_sheet",
         &role_val,
-        &actor_val,
+        &username_val,
     )
     .await;
+    let day = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|_| "Run sheet date must be YYYY-MM-DD".to_string())?
+        .format("%Y-%m-%d")
+        .to_string();
+    let driver_capable = is_driver.unwrap_or(false);
+    let office = role_val == "admin" || role_val == "lead" || is_staff_like(&role_val);
+    // Crew members only ever get their own stops.
+    let driver_filter = if office && !driver_capable {
+        Some(username_val.clone()).filter(|u| !u.is_empty())
+    } else if role_val == "volunteer" || driver_capable {
+        if username_val.is_empty() {
+            return Err("Username is required for a driver run sheet".to_string());
+        }
+        Some(username_val.clone())
+    } else {
+        return Err("Not authorized to view run sheets".to_string());
+    };
+    let contact_details = can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false))
+        || (driver_capable && role_val != "volunteer");
+    let show_address = role_val != "volunteer";
 
-    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let orders = sqlx::query_as::<_, RunSheetOrderRow>(
+        r#"
+        SELECT
+            w.id, w.client_id, w.scheduled_date, w.status, w.client_name, w.telephone,
+            w.physical_address_line1, w.physical_address_line2, w.physical_address_city,
+            w.physical_address_state, w.physical_address_postal_code,
+            w.directions, w.gate_combo, w.delivery_size_cords, w.wood_size_label,
+            w.pickup_delivery_type, COALESCE(w.assignees_json, '[]') AS assignees_json,
+            c.site_hazards, c.site_drop_location, c.site_vehicle_access,
+            c.site_preferred_contact_time
+        FROM work_orders w
+        LEFT JOIN clients c ON c.id = w.client_id
+        WHERE w.is_deleted = 0
+          AND date(w.scheduled_date) = date(?1)
+          AND lower(w.status) != 'cancelled'
+          AND (
+            ?2 IS NULL
+            OR EXISTS (
+                SELECT 1
+                FROM work_order_assignments a
+                JOIN auth_users au ON au.user_id = a.user_id AND au.is_deleted = 0
+                WHERE a.work_order_id = w.id AND lower(au.username) = lower(?2)
+            )
+          )
+        ORDER BY w.scheduled_date, w.client_name
+        "#,
+    )
+    .bind(&day)
+    .bind(&driver_filter)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
 
-    let existing = sqlx::query_as::<_, WorkOrderStatusRow>(
+    let client_ids: Vec<String> = orders.iter().map(|o| o.client_id.clone()).collect();
+    let preferences: HashMap<String, CommunicationPreferences> =
+        load_communication_preferences(&state.pool, &client_ids)
+            .await?
+            .into_iter()
+            .map(|p| (p.client_id.clone(), p))
+            .collect();
+    let mut contacts: HashMap<String, Vec<EmergencyContactRow>> = HashMap::new();
+    if contact_details {
+        for contact in load_emergency_contacts(&state.pool, &client_ids).await? {
+            contacts
+                .entry(contact.client_id.clone())
+                .or_default()
+                .push(EmergencyContactRow {
+                    // Free-text notes about the contact stay in the office.
+                    notes: None,
+                    ..contact
+                });
+        }
+    }
+    let now = chrono::Local::now().naive_local();
+
+    let stops = orders
+        .into_iter()
+        .map(|order| {
+            let prefs = preferences.get(&order.client_id);
+            let windows = prefs
+                .map(|p| p.do_not_call_windows.clone())
+                .unwrap_or_default();
+            let address = |v: Option<String>| if show_address { v } else { None };
+            RunSheetStop {
+                work_order_id: order.id,
+                scheduled_date: order.scheduled_date,
+                status: order.status,
+                client_name: order.client_name,
+                telephone: order.telephone.filter(|_| contact_details),
+                physical_address_line1: address(order.physical_address_line1),
+                physical_address_line2: address(order.physical_address_line2),
+                physical_address_city: address(order.physical_address_city),
+                physical_address_state: address(order.physical_address_state),
+                physical_address_postal_code: address(order.physical_address_postal_code),
+                directions: address(order.directions),
+                gate_combo: order.gate_combo.filter(|_| contact_details),
+                delivery_size_cords: order.delivery_size_cords,
+                wood_size_label: order.wood_size_label,
+                pickup_delivery_type: order.pickup_delivery_type,
+                assignees_json: order.assignees_json,
+                site_notes: order.site_notes,
+                preferred_language: prefs.and_then(|p| p.preferred_language.clone()),
+                best_channel: prefs.and_then(|p| p.best_channel.clone()),
+                accessibility_needs: prefs
+                    .and_then(|p| p.accessibility_needs.clone())
+                    .filter(|_| contact_details),
+                do_not_call_now: in_do_not_call_window(&windows, now),
+                do_not_call_windows: windows,
+                emergency_contacts: contacts.remove(&order.client_id).unwrap_or_default(),
+            }
+        })
+        .collect();
+
+    Ok(RunSheet {
+        date: day,
+        driver: driver_filter,
+        contact_details_included: contact_details,
+        stops,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct AllotmentRuleInput {
+    id: Option<String>,
+    name: String,
+    // NULL/blank applies to every season; a season-specific rule takes precedence.
+    season: Option<String>,
+    max_cords_per_household: f64,
+    extra_cords_per_member: Option<f64>,
+    max_cords_cap: Option<f64>,
+    enforcement: Option<String>,
+    is_active: Option<bool>,
+    created_by_user_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct AllotmentRuleRow {
+    id: String,
+    name: String,
+    season: Option<String>,
+    max_cords_per_household: f64,
+    extra_cords_per_member: f64,
+    max_cords_cap: Option<f64>,
+    enforcement: String,
+    is_active: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AllotmentCheck {
+    client_id: String,
+    season: String,
+    household_size: Option<i64>,
+    rule_id: Option<String>,
+    rule_name: Option<String>,
+    enforcement: Option<String>,
+    limit_cords: Option<f64>,
+    used_cords: f64,
+    requested_cords: f64,
+    remaining_cords: Option<f64>,
+    exceeded: bool,
+    message: Option<String>,
+}
+
+/// Drafts are not yet committed to the client and cancelled orders release their wood.
+fn counts_toward_allotment(status: &str) -> bool {
+    !matches!(status.to_lowercase().as_str(), "draft" | "cancelled")
+}
+
+fn allotment_limit(rule: &AllotmentRuleRow, household_size: Option<i64>) -> f64 {
+    let extra_members = (household_size.unwrap_or(1).max(1) - 1) as f64;
+    let limit = rule.max_cords_per_household + rule.extra_cords_per_member * extra_members;
+    match rule.max_cords_cap {
+        Some(cap) => limit.min(cap),
+        None => limit,
+    }
+}
+
+/// Compares the client's committed cords for the season of `served_on` (today when absent)
+/// plus `requested_cords` against the active allotment rule.
+async fn check_allotment(
+    conn: &mut sqlx::SqliteConnection,
+    client_id: &str,
+    exclude_order_id: Option<&str>,
+    served_on: Option<&str>,
+    requested_cords: f64,
+) -> Result<AllotmentCheck, String> {
+    let served_on = match served_on.filter(|d| !d.trim().is_empty()) {
+        Some(date) => date.to_string(),
+        None => chrono::Local::now().format("%Y-%m-%d").to_string(),
+    };
+    let season = heating_season_for_date(&served_on)
+        .ok_or_else(|| format!("Invalid scheduled date '{}'", served_on))?;
+    let (season_start, season_end) = heating_season_bounds(&season)?;
+
+    let household_size: Option<i64> =
+        sqlx::query_scalar("SELECT household_size FROM clients WHERE id = ?")
+            .bind(client_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?
+            .flatten();
+
+    let rule = sqlx::query_as::<_, AllotmentRuleRow>(
+        r#"
+        SELECT
+            id, name, season, max_cords_per_household, extra_cords_per_member,
+            max_cords_cap, enforcement, is_active, created_at, updated_at
+        FROM allotment_rules
+        WHERE is_deleted = 0
+          AND is_active = 1
+          AND (season = ? OR season IS NULL)
+        ORDER BY season IS NULL, updated_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&season)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let used_cords: f64 = sqlx::query_scalar(
         r#"
-        SELECT status, mileage, work_hours, delivery_size_cords, pickup_quantity_cords
+        SELECT COALESCE(SUM(
+            CASE WHEN lower(status) = 'picked_up'
+                THEN COALESCE(pickup_quantity_cords, 0)
+                ELSE COALESCE(delivered_cords, delivery_size_cords, 0)
+            END
+        ), 0.0)
         FROM work_orders
-        WHERE id = ? AND is_deleted = 0
+        WHERE client_id = ?
+          AND is_deleted = 0
+          AND id != ?
+          AND lower(status) NOT IN ('draft', 'cancelled')
+          AND date(COALESCE(scheduled_date, created_at)) >= date(?)
+          AND date(COALESCE(scheduled_date, created_at)) < date(?)
         "#,
     )
-    .bind(&input.work_order_id)
-    .fetch_optional(&mut *tx)
+    .bind(client_id)
+    .bind(exclude_order_id.unwrap_or(""))
+    .bind(&season_start)
+    .bind(&season_end)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| e.to_string())?;
 
-    let existing = match existing {
-        Some(e) => e,
-        None => return Err("Work order not found or has been deleted".to_string()),
+    let mut check = AllotmentCheck {
+        client_id: client_id.to_string(),
+        season,
+        household_size,
+        rule_id: None,
+        rule_name: None,
+        enforcement: None,
+        limit_cords: None,
+        used_cords,
+        requested_cords,
+        remaining_cords: None,
+        exceeded: false,
+        message: None,
     };
+    if let Some(rule) = rule {
+        let limit = allotment_limit(&rule, household_size);
+        check.exceeded = used_cords + requested_cords > limit + 1e-9;
+        if check.exceeded {
+            check.message = Some(format!(
+                "Allotment exceeded for {}: {:.2} cords already committed + {:.2} requested > {:.2} allowed ({}).",
+                check.season, used_cords, requested_cords, limit, rule.name
+            ));
+        }
+        check.limit_cords = Some(limit);
+        check.remaining_cords = Some((limit - used_cords).max(0.0));
+        check.rule_id = Some(rule.id);
+        check.rule_name = Some(rule.name);
+        check.enforcement = Some(rule.enforcement);
+    }
+    Ok(check)
+}
 
-    let current_status = existing.status;
-    let next_status = input
-        .status
-        .clone()
-        .unwrap_or_else(|| current_status.clone());
-    let delivery_size = if next_status.eq_ignore_ascii_case("picked_up")
-        || current_status.eq_ignore_ascii_case("picked_up")
-    {
-        existing.pickup_quantity_cords.unwrap_or(0.0)
-    } else {
-        existing.delivery_size_cords.unwrap_or(0.0)
-    };
+/// The message to show the user when a warn-mode rule let the order through.
+fn allotment_warning(note: &Option<(&'static str, Option<String>, String)>) -> Option<String> {
+    note.as_ref()
+        .filter(|(field, _, _)| *field == "allotment_warning")
+        .map(|(_, _, message)| message.clone())
+}
 
-    // Simple validation: mileage required if status completed
-    if next_status == "completed" && input.mileage.is_none() {
-        return Err("Mileage is required to mark completed".to_string());
-    }
-    if input.work_hours.is_some() && role_val != "admin" && role_val != "lead" {
-        return Err("Only admins or leads can set work hours".to_string());
-    }
-    if next_status == "completed"
-        && current_status != "completed"
-        && (role_val == "admin" || role_val == "lead")
-        && input.work_hours.is_none()
-    {
-        return Err("Work hours are required to close an order".to_string());
+/// Decides whether an over-allotment order may proceed. Returns the audit field and value to
+/// record (a warning or a lead/admin override), or an error when the rule rejects it.
+fn resolve_allotment(
+    check: &AllotmentCheck,
+    role: &str,
+    override_reason: Option<&str>,
+) -> Result<Option<(&'static str, String)>, String> {
+    if !check.exceeded {
+        return Ok(None);
     }
-
-    // Volunteers can only update if they are marked as drivers.
-    if role_val == "volunteer" && !driver_capable {
-        return Err("Volunteers cannot update status/mileage".to_string());
+    let message = check.message.clone().unwrap_or_default();
+    if check.enforcement.as_deref() == Some("warn") {
+        return Ok(Some(("allotment_warning", message)));
     }
-
-    if driver_capable && input.status.is_some() {
-        let allowed = ["in_progress", "delivered", "issue"];
-        if !allowed.contains(&next_status.as_str()) {
-            return Err("Drivers can only mark in_progress, delivered, or issue".to_string());
+    match override_reason.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(reason) if role == "admin" || role == "lead" => {
+            Ok(Some(("allotment_override", reason.to_string())))
         }
+        Some(_) => Err("Only leads or admins can override allotment limits".to_string()),
+        None => Err(format!("{} A lead can override with a reason.", message)),
     }
+}
 
-    sqlx::query(
+#[tauri::command]
+async fn list_allotment_rules(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<AllotmentRuleRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_allotment_rules", &role_val, &actor_val).await;
+    sqlx::query_as::<_, AllotmentRuleRow>(
         r#"
-        UPDATE work_orders
-        SET status = ?,
-            mileage = COALESCE(?, mileage),
-            work_hours = COALESCE(?, work_hours),
-            updated_at = datetime('now')
-        WHERE id = ? AND is_deleted = 0
+        SELECT
+            id, name, season, max_cords_per_household, extra_cords_per_member,
+            max_cords_cap, enforcement, is_active, created_at, updated_at
+        FROM allotment_rules
+        WHERE is_deleted = 0
+        ORDER BY season IS NULL, season DESC, name
         "#,
     )
-    .bind(&next_status)
-    .bind(&input.mileage)
-    .bind(&input.work_hours)
-    .bind(&input.work_order_id)
-    .execute(&mut *tx)
+    .fetch_all(&state.pool)
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| e.to_string())
+}
 
-    if current_status != next_status {
-        adjust_inventory_for_transition_tx(&mut tx, &current_status, &next_status, delivery_size)
-            .await
-            .map_err(|e| e.to_string())?;
+#[tauri::command]
+async fn save_allotment_rule(
+    state: State<'_, AppState>,
+    input: AllotmentRuleInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "save_allotment_rule", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can configure allotment rules".to_string());
     }
 
-    if current_status != next_status {
-        audit_change(
-            &state.pool,
-            "update_work_order_status",
-            &role_val,
-            &actor_val,
-            "work_orders",
-            &input.work_order_id,
-            "status",
-            Some(current_status),
-            Some(next_status.clone()),
-        )
-        .await;
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Rule name is required".to_string());
     }
-    let prev_mileage = existing.mileage;
-    if prev_mileage != input.mileage {
-        audit_change(
-            &state.pool,
-            "update_work_order_status",
-            &role_val,
-            &actor_val,
-            "work_orders",
-            &input.work_order_id,
-            "mileage",
-            prev_mileage.map(|v| v.to_string()),
-            input.mileage.map(|v| v.to_string()),
-        )
-        .await;
+    let season = match input
+        .season
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(s) => {
+            // Store the canonical "YYYY-YYYY" label so lookups by season match.
+            let (start, _) = heating_season_bounds(s)?;
+            heating_season_for_date(&start)
+        }
+        None => None,
+    };
+    let extra = input.extra_cords_per_member.unwrap_or(0.0);
+    if input.max_cords_per_household < 0.0
+        || extra < 0.0
+        || input.max_cords_cap.is_some_and(|c| c < 0.0)
+    {
+        return Err("Allotment amounts cannot be negative".to_string());
     }
-    let prev_hours = existing.work_hours;
-    if prev_hours != input.work_hours {
-        audit_change(
-            &state.pool,
-            "update_work_order_status",
-            &role_val,
-            &actor_val,
-            "work_orders",
-            &input.work_order_id,
-            "work_hours",
-            prev_hours.map(|v| v.to_string()),
-            input.work_hours.map(|v| v.to_string()),
-        )
-        .await;
+    let enforcement = input
+        .enforcement
+        .as_deref()
+        .map(|e| e.trim().to_lowercase())
+        .unwrap_or_else(|| "reject".to_string());
+    if enforcement != "reject" && enforcement != "warn" {
+        return Err("Enforcement must be 'reject' or 'warn'".to_string());
     }
+    let is_active = input.is_active.unwrap_or(true);
 
-    // Save mileage to client on first completed order (if not a paired half order)
-    if next_status.eq_ignore_ascii_case("completed") || next_status.eq_ignore_ascii_case("picked_up") {
-        if let Some(mileage) = input.mileage {
-            // Get work order details to check if it's a half order
-            let wo_info = sqlx::query!(
-                r#"SELECT client_id, paired_order_id, delivery_size_label FROM work_orders WHERE id = ?"#,
-                input.work_order_id
+    let id = match &input.id {
+        Some(id) => {
+            let updated = sqlx::query(
+                r#"
+                UPDATE allotment_rules
+                SET name = ?, season = ?, max_cords_per_household = ?, extra_cords_per_member = ?,
+                    max_cords_cap = ?, enforcement = ?, is_active = ?,
+                    updated_at = datetime('now'), version = version + 1
+                WHERE id = ? AND is_deleted = 0
+                "#,
             )
-            .fetch_optional(&mut *tx)
+            .bind(&name)
+            .bind(&season)
+            .bind(input.max_cords_per_household)
+            .bind(extra)
+            .bind(input.max_cords_cap)
+            .bind(&enforcement)
+            .bind(is_active)
+            .bind(id)
+            .execute(&state.pool)
             .await
             .map_err(|e| e.to_string())?;
-
-            if let Some(wo) = wo_info {
-                let is_half_order = wo.delivery_size_label.as_deref() == Some("F-250 1/2") 
-                    || wo.paired_order_id.is_some();
-                
-                // Only save mileage to client if NOT a half order
-                if !is_half_order {
-                    let client_id = &wo.client_id;
-                    if !client_id.is_empty() {
-                        // Check if client already has default mileage
-                        let client_mileage = sqlx::query!(
-                            r#"SELECT default_mileage FROM clients WHERE id = ?"#,
-                            client_id
-                        )
-                        .fetch_optional(&mut *tx)
-                        .await
-                        .map_err(|e| e.to_string())?;
-
-                        // Only save if client doesn't already have a default mileage
-                        if let Some(cm) = client_mileage {
-                            if cm.default_mileage.is_none() {
-                                sqlx::query(
-                                    r#"UPDATE clients SET default_mileage = ?, updated_at = datetime('now') WHERE id = ?"#
-                                )
-                                .bind(mileage)
-                                .bind(client_id)
-                                .execute(&mut *tx)
-                                .await
-                                .map_err(|e| e.to_string())?;
-                            }
-                        }
-                    }
-                }
+            if updated.rows_affected() == 0 {
+                return Err("Allotment rule not found".to_string());
             }
+            id.clone()
         }
-    }
-
-    tx.commit().await.map_err(|e| e.to_string())?;
-
-    Ok(())
-}
-
-#[tauri::command]
-async fn list_delivery_events(
-    state: State<'_, AppState>,
-    role: Option<String>,
-    username: Option<String>,
-    hipaa_certified: Option<bool>,
-    is_driver: Option<bool>,
-) -> Result<Vec<DeliveryEventRow>, String> {
-    let mut rows = sqlx::query_as::<_, DeliveryEventRow>(
-        r#"
-        SELECT
-            id,
-            title,
-            event_type,
-            start_date,
-            end_date,
-            work_order_id,
-            color_code,
-            COALESCE(
-              assigned_user_ids_json,
-              (SELECT assignees_json FROM work_orders WHERE work_orders.id = delivery_events.work_order_id)
-            ) AS assigned_user_ids_json
-        FROM delivery_events
-        WHERE is_deleted = 0
-        ORDER BY datetime(start_date) ASC
-        "#
-    )
-    .fetch_all(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+        None => {
+            let id = Uuid::new_v4().to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO allotment_rules (
+                    id, name, season, max_cords_per_household, extra_cords_per_member,
+                    max_cords_cap, enforcement, is_active, created_by_user_id
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(&name)
+            .bind(&season)
+            .bind(input.max_cords_per_household)
+            .bind(extra)
+            .bind(input.max_cords_cap)
+            .bind(&enforcement)
+            .bind(is_active)
+            .bind(&input.created_by_user_id)
+            .execute(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            id
+        }
+    };
 
-    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
-    let username_val = username.unwrap_or_default();
-    let is_hipaa = hipaa_certified.unwrap_or(false);
-    let driver_capable = is_driver.unwrap_or(false);
-    audit_db(
+    audit_change(
         &state.pool,
-        "list_delivery_events",
+        "save_allotment_rule",
         &role_val,
-        &username_val,
+        &actor_val,
+        "allotment_rules",
+        &id,
+        "rule",
+        None,
+        Some(format!(
+            "{} season={} base={} per_member={} cap={} enforcement={} active={}",
+            name,
+            season.as_deref().unwrap_or("all"),
+            input.max_cords_per_household,
+            extra,
+            input
+                .max_cords_cap
+                .map(|c| c.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+            enforcement,
+            is_active
+        )),
     )
     .await;
-
-    if driver_capable || role_val == "volunteer" {
-        let uname = username_val.to_lowercase();
-        rows = rows
-            .into_iter()
-            .filter(|ev| {
-                let assignees: Vec<String> =
-                    serde_json::from_str(ev.assigned_user_ids_json.as_deref().unwrap_or("[]"))
-                        .unwrap_or_default();
-                assignees
-                    .iter()
-                    .map(|a| a.to_lowercase())
-                    .any(|a| a == uname)
-            })
-            .collect();
-    } else if !(role_val == "admin" || (role_val == "lead" && is_hipaa)) {
-        rows.iter_mut().for_each(|ev| {
-            if ev.work_order_id.is_some() {
-                ev.title = "Hidden".to_string();
-            }
-        });
-    }
-
-    Ok(rows)
+    Ok(id)
 }
 
 #[tauri::command]
-async fn list_motd(
+async fn delete_allotment_rule(
     state: State<'_, AppState>,
-    active_only: Option<bool>,
-) -> Result<Vec<MotdRow>, String> {
-    let only_active = active_only.unwrap_or(true);
-    let mut query = String::from(
-        r#"
-        SELECT id, message, active_from, active_to, created_at
-        FROM motd
-        WHERE is_deleted = 0
-        "#,
-    );
-    if only_active {
-        query.push_str(
-            r#"
-              AND (active_from IS NULL OR datetime(active_from) <= datetime('now'))
-              AND (active_to IS NULL OR datetime(active_to) >= datetime('now'))
-            "#,
-        );
+    id: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "delete_allotment_rule", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can configure allotment rules".to_string());
     }
-    query.push_str(" ORDER BY datetime(created_at) DESC");
-
-    let rows = sqlx::query_as::<_, MotdRow>(&query)
-        .fetch_all(&state.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    audit_db(&state.pool, "list_motd", "unknown", "unknown").await;
-    Ok(rows)
-}
-
-#[tauri::command]
-async fn create_motd(state: State<'_, AppState>, input: MotdInput) -> Result<String, String> {
-    let id = Uuid::new_v4().to_string();
-    sqlx::query(
-        r#"
-        INSERT INTO motd (id, message, active_from, active_to, created_by_user_id)
-        VALUES (?, ?, ?, ?, ?)
-        "#,
-    )
-    .bind(&id)
-    .bind(&input.message)
-    .bind(&input.active_from)
-    .bind(&input.active_to)
-    .bind(&input.created_by_user_id)
-    .execute(&state.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    audit_db(&state.pool, "create_motd", "unknown", "unknown").await;
-    Ok(id)
-}
-
-#[tauri::command]
-async fn delete_motd(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    audit_db(&state.pool, "delete_motd", "unknown", "unknown").await;
     sqlx::query(
         r#"
-        UPDATE motd
-        SET is_deleted = 1, updated_at = datetime('now')
+        UPDATE allotment_rules
+        SET is_deleted = 1, updated_at = datetime('now'), version = version + 1
         WHERE id = ?
         "#,
     )
@@ -3343,297 +5199,9830 @@ async fn delete_motd(state: State<'_, AppState>, id: String) -> Result<(), Strin
     Ok(())
 }
 
+/// Lets the order form show remaining allotment (and any warning) before submitting.
 #[tauri::command]
-async fn create_change_request(
+async fn check_client_allotment(
     state: State<'_, AppState>,
-    input: ChangeRequestInput,
-) -> Result<String, String> {
-    let id = Uuid::new_v4().to_string();
-    audit_db(&state.pool, "create_change_request", "unknown", "unknown").await;
-    sqlx::query(
-        r#"
-        INSERT INTO change_requests (id, title, description, requested_by_user_id, status)
-        VALUES (?, ?, ?, ?, 'open')
-        "#,
+    client_id: String,
+    cords: f64,
+    scheduled_date: Option<String>,
+    exclude_order_id: Option<String>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<AllotmentCheck, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "check_client_allotment", &role_val, &actor_val).await;
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    check_allotment(
+        &mut conn,
+        &client_id,
+        exclude_order_id.as_deref(),
+        scheduled_date.as_deref(),
+        cords,
     )
-    .bind(&id)
-    .bind(&input.title)
-    .bind(&input.description)
-    .bind(&input.requested_by_user_id)
-    .execute(&state.pool)
     .await
-    .map_err(|e| e.to_string())?;
-    Ok(id)
 }
 
-#[tauri::command]
-async fn list_change_requests(
-    state: State<'_, AppState>,
-    status: Option<String>,
-) -> Result<Vec<ChangeRequestRow>, String> {
-    let status_filter = status.unwrap_or_else(|| "open".to_string());
-    // If "all", fetch all, else filter
-    let query_str = if status_filter == "all" {
-        r#"
-        SELECT id, title, description, requested_by_user_id, status, resolution_notes, resolved_by_user_id, created_at
-        FROM change_requests
-        WHERE is_deleted = 0
-        ORDER BY created_at DESC
-        "#
-    } else {
-        r#"
-        SELECT id, title, description, requested_by_user_id, status, resolution_notes, resolved_by_user_id, created_at
-        FROM change_requests
-        WHERE is_deleted = 0 AND status = ?
-        ORDER BY created_at DESC
-        "#
+#[derive(Debug, Serialize, FromRow)]
+struct StandingOrderRow {
+    id: String,
+    client_id: String,
+    client_name: Option<String>,
+    cadence_weeks: i64,
+    preferred_weekday: String,
+    delivery_size_label: Option<String>,
+    delivery_size_cords: f64,
+    window_start: String,
+    window_end: String,
+    notes: Option<String>,
+    is_active: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StandingOrderInput {
+    id: Option<String>,
+    client_id: String,
+    cadence_weeks: i64,
+    preferred_weekday: String,
+    delivery_size_label: Option<String>,
+    delivery_size_cords: f64,
+    window_start: String,
+    window_end: String,
+    notes: Option<String>,
+    is_active: Option<bool>,
+    created_by_user_id: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct StandingOrderRunReport {
+    created: Vec<GeneratedStandingOrder>,
+    skipped: Vec<SkippedStandingOrder>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeneratedStandingOrder {
+    standing_order_id: String,
+    work_order_id: String,
+    scheduled_date: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SkippedStandingOrder {
+    standing_order_id: String,
+    scheduled_date: String,
+    reason: String,
+}
+
+/// How far ahead the generator creates orders.
+const STANDING_ORDER_HORIZON_DAYS: i64 = 28;
+
+/// Delivery dates for a standing order that fall between `from` and `through`: the first
+/// preferred weekday on or after the window opens, then every `cadence_weeks` weeks until it
+/// closes.
+fn standing_order_dates(
+    window_start: chrono::NaiveDate,
+    window_end: chrono::NaiveDate,
+    preferred_weekday: usize,
+    cadence_weeks: i64,
+    from: chrono::NaiveDate,
+    through: chrono::NaiveDate,
+) -> Vec<chrono::NaiveDate> {
+    use chrono::Datelike;
+    let offset =
+        (preferred_weekday as i64 + 7 - window_start.weekday().num_days_from_monday() as i64) % 7;
+    let last = window_end.min(through);
+    let mut day = window_start + chrono::Duration::days(offset);
+    let mut dates = Vec::new();
+    while day <= last {
+        if day >= from {
+            dates.push(day);
+        }
+        day += chrono::Duration::weeks(cadence_weeks.max(1));
+    }
+    dates
+}
+
+/// Creates the work orders standing orders call for between `today` and `horizon_days` out.
+/// A date is skipped (and reported) when the season allotment would be exceeded, no driver is
+/// available, the day has no room left or there is not enough wood to reserve; it is tried again
+/// on the next run.
+async fn generate_standing_orders_through(
+    pool: &SqlitePool,
+    today: chrono::NaiveDate,
+    horizon_days: i64,
+    role_val: &str,
+    actor_val: &str,
+) -> Result<StandingOrderRunReport, String> {
+    let definitions = sqlx::query_as::<_, StandingOrderRow>(
+        r#"
+        SELECT s.id, s.client_id, c.name AS client_name, s.cadence_weeks, s.preferred_weekday,
+               s.delivery_size_label, s.delivery_size_cords, s.window_start, s.window_end,
+               s.notes, s.is_active, s.created_at, s.updated_at
+        FROM standing_orders s
+        LEFT JOIN clients c ON c.id = s.client_id
+        WHERE s.is_deleted = 0 AND s.is_active = 1
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let through = today + chrono::Duration::days(horizon_days);
+    let parse = |d: &str| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d");
+    let mut due: Vec<(chrono::NaiveDate, &StandingOrderRow)> = Vec::new();
+    for definition in &definitions {
+        let (Ok(start), Ok(end)) = (
+            parse(&definition.window_start),
+            parse(&definition.window_end),
+        ) else {
+            continue;
+        };
+        let Some(weekday) = WEEKDAY_CODES
+            .iter()
+            .position(|d| *d == definition.preferred_weekday)
+        else {
+            continue;
+        };
+        for date in standing_order_dates(
+            start,
+            end,
+            weekday,
+            definition.cadence_weeks,
+            today,
+            through,
+        ) {
+            due.push((date, definition));
+        }
+    }
+    // Earlier loads get first call on drivers' days and on the woodpile.
+    due.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.id.cmp(&b.1.id)));
+
+    let mut report = StandingOrderRunReport::default();
+    for (date, definition) in due {
+        let scheduled_date = date.format("%Y-%m-%d").to_string();
+        let exists: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM work_orders
+            WHERE standing_order_id = ? AND scheduled_date = ? AND is_deleted = 0
+            "#,
+        )
+        .bind(&definition.id)
+        .bind(&scheduled_date)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        if exists > 0 {
+            continue;
+        }
+        let mut skip = |reason: String| {
+            report.skipped.push(SkippedStandingOrder {
+                standing_order_id: definition.id.clone(),
+                scheduled_date: scheduled_date.clone(),
+                reason,
+            })
+        };
+
+        let drivers = available_driver_names(pool, &scheduled_date).await?.len();
+        if drivers == 0 {
+            skip(format!("No driver is available on {}", scheduled_date));
+            continue;
+        }
+        let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+        let capacity = day_capacity(&mut conn, &scheduled_date, drivers).await?;
+        if !capacity.fits(definition.delivery_size_cords) {
+            skip(format!(
+                "{} is full: {} trip(s) and {:.2} cords already booked",
+                scheduled_date, capacity.trips_booked, capacity.cords_booked
+            ));
+            continue;
+        }
+        let check = check_allotment(
+            &mut conn,
+            &definition.client_id,
+            None,
+            Some(&scheduled_date),
+            definition.delivery_size_cords,
+        )
+        .await?;
+        drop(conn);
+        if check.exceeded {
+            skip(check.message.clone().unwrap_or_default());
+            continue;
+        }
+
+        let order = build_client_work_order(
+            pool,
+            ClientWorkOrderInput {
+                client_id: definition.client_id.clone(),
+                scheduled_date: Some(scheduled_date.clone()),
+                status: Some(WorkOrderStatus::Scheduled.as_str().to_string()),
+                work_hours: None,
+                other_heat_source_gas: None,
+                other_heat_source_electric: None,
+                other_heat_source_other: None,
+                notes: definition.notes.clone(),
+                wood_size_label: None,
+                wood_size_other: None,
+                delivery_size_label: definition.delivery_size_label.clone(),
+                delivery_size_cords: Some(definition.delivery_size_cords),
+                pickup_delivery_type: None,
+                pickup_quantity_cords: None,
+                pickup_length: None,
+                pickup_width: None,
+                pickup_height: None,
+                pickup_units: None,
+                assignees_json: None,
+                created_by_user_id: None,
+                created_by_display: Some(actor_val.to_string()),
+                paired_order_id: None,
+                allotment_override_reason: None,
+            },
+        )
+        .await;
+        let created = match order {
+            Ok(mut order) => {
+                order.standing_order_id = Some(definition.id.clone());
+                store_work_order(pool, order, role_val, actor_val, "generate_standing_orders")
+                    .await
+                    .map(|saved| saved.id)
+            }
+            Err(e) => Err(e),
+        };
+        match created {
+            Ok(work_order_id) => report.created.push(GeneratedStandingOrder {
+                standing_order_id: definition.id.clone(),
+                work_order_id,
+                scheduled_date,
+            }),
+            Err(reason) => skip(reason),
+        }
+    }
+    Ok(report)
+}
+
+#[tauri::command]
+async fn list_standing_orders(
+    state: State<'_, AppState>,
+    client_id: Option<String>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<StandingOrderRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_standing_orders", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads or admins can view standing orders".to_string());
+    }
+    sqlx::query_as::<_, StandingOrderRow>(
+        r#"
+        SELECT s.id, s.client_id, c.name AS client_name, s.cadence_weeks, s.preferred_weekday,
+               s.delivery_size_label, s.delivery_size_cords, s.window_start, s.window_end,
+               s.notes, s.is_active, s.created_at, s.updated_at
+        FROM standing_orders s
+        LEFT JOIN clients c ON c.id = s.client_id
+        WHERE s.is_deleted = 0
+          AND (?1 IS NULL OR s.client_id = ?1)
+        ORDER BY c.name, s.window_start
+        "#,
+    )
+    .bind(&client_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_standing_order(
+    state: State<'_, AppState>,
+    input: StandingOrderInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "save_standing_order", &role_val, &actor_val).await;
+    if role_val != "admin" && !is_staff_like(&role_val) {
+        return Err("Only staff or admin may set up standing orders".to_string());
+    }
+
+    let weekday = input.preferred_weekday.trim().to_lowercase();
+    if !WEEKDAY_CODES.contains(&weekday.as_str()) {
+        return Err(format!(
+            "Unknown day '{}' (use mon..sun)",
+            input.preferred_weekday
+        ));
+    }
+    if !(1..=12).contains(&input.cadence_weeks) {
+        return Err("Cadence must be between 1 and 12 weeks".to_string());
+    }
+    if !input.delivery_size_cords.is_finite() || input.delivery_size_cords <= 0.0 {
+        return Err("Delivery size must be more than zero cords".to_string());
+    }
+    let parse = |label: &str, d: &str| {
+        chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("{} must be a YYYY-MM-DD date", label))
     };
+    let window_start = parse("Window start", &input.window_start)?;
+    let window_end = parse("Window end", &input.window_end)?;
+    if window_end < window_start {
+        return Err("The season window ends before it starts".to_string());
+    }
+    let is_active = input.is_active.unwrap_or(true);
 
-    let rows = if status_filter == "all" {
-        sqlx::query_as::<_, ChangeRequestRow>(query_str)
-            .fetch_all(&state.pool)
+    let id = match &input.id {
+        Some(id) => {
+            let updated = sqlx::query(
+                r#"
+                UPDATE standing_orders
+                SET client_id = ?, cadence_weeks = ?, preferred_weekday = ?,
+                    delivery_size_label = ?, delivery_size_cords = ?,
+                    window_start = ?, window_end = ?, notes = ?, is_active = ?,
+                    updated_at = datetime('now'), version = version + 1
+                WHERE id = ? AND is_deleted = 0
+                "#,
+            )
+            .bind(&input.client_id)
+            .bind(input.cadence_weeks)
+            .bind(&weekday)
+            .bind(&input.delivery_size_label)
+            .bind(input.delivery_size_cords)
+            .bind(window_start.to_string())
+            .bind(window_end.to_string())
+            .bind(&input.notes)
+            .bind(is_active)
+            .bind(id)
+            .execute(&state.pool)
             .await
-            .map_err(|e| e.to_string())?
-    } else {
-        sqlx::query_as::<_, ChangeRequestRow>(query_str)
-            .bind(status_filter)
-            .fetch_all(&state.pool)
+            .map_err(|e| e.to_string())?;
+            if updated.rows_affected() == 0 {
+                return Err("Standing order not found".to_string());
+            }
+            id.clone()
+        }
+        None => {
+            let id = Uuid::new_v4().to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO standing_orders (
+                    id, client_id, cadence_weeks, preferred_weekday,
+                    delivery_size_label, delivery_size_cords,
+                    window_start, window_end, notes, is_active, created_by_user_id
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(&input.client_id)
+            .bind(input.cadence_weeks)
+            .bind(&weekday)
+            .bind(&input.delivery_size_label)
+            .bind(input.delivery_size_cords)
+            .bind(window_start.to_string())
+            .bind(window_end.to_string())
+            .bind(&input.notes)
+            .bind(is_active)
+            .bind(&input.created_by_user_id)
+            .execute(&state.pool)
             .await
-            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+            id
+        }
     };
 
-    audit_db(&state.pool, "list_change_requests", "unknown", "unknown").await;
-    Ok(rows)
+    audit_change(
+        &state.pool,
+        "save_standing_order",
+        &role_val,
+        &actor_val,
+        "standing_orders",
+        &id,
+        "standing_order",
+        None,
+        Some(format!(
+            "client={} every {} week(s) on {} {} cords {}..{} active={}",
+            input.client_id,
+            input.cadence_weeks,
+            weekday,
+            input.delivery_size_cords,
+            window_start,
+            window_end,
+            is_active
+        )),
+    )
+    .await;
+    Ok(id)
 }
 
+/// Orders already generated stay on the calendar; cancel them individually if needed.
 #[tauri::command]
-async fn resolve_change_request(
+async fn delete_standing_order(
     state: State<'_, AppState>,
     id: String,
-    status: String,
-    resolution_notes: Option<String>,
-    resolved_by_user_id: String,
+    role: Option<String>,
+    actor: Option<String>,
 ) -> Result<(), String> {
-    audit_db(&state.pool, "resolve_change_request", "unknown", "unknown").await;
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "delete_standing_order", &role_val, &actor_val).await;
+    if role_val != "admin" && !is_staff_like(&role_val) {
+        return Err("Only staff or admin may set up standing orders".to_string());
+    }
     sqlx::query(
         r#"
-        UPDATE change_requests
-        SET status = ?, resolution_notes = ?, resolved_by_user_id = ?, updated_at = datetime('now')
+        UPDATE standing_orders
+        SET is_deleted = 1, updated_at = datetime('now'), version = version + 1
         WHERE id = ?
         "#,
     )
-    .bind(&status)
-    .bind(&resolution_notes)
-    .bind(&resolved_by_user_id)
     .bind(&id)
     .execute(&state.pool)
     .await
     .map_err(|e| e.to_string())?;
+    audit_change(
+        &state.pool,
+        "delete_standing_order",
+        &role_val,
+        &actor_val,
+        "standing_orders",
+        &id,
+        "is_deleted",
+        Some("0".to_string()),
+        Some("1".to_string()),
+    )
+    .await;
     Ok(())
 }
 
-#[derive(Debug, Serialize, FromRow)]
-struct AuditLogRow {
-    id: String,
-    event: String,
+#[tauri::command]
+async fn generate_standing_orders(
+    state: State<'_, AppState>,
+    horizon_days: Option<i64>,
     role: Option<String>,
     actor: Option<String>,
-    entity: Option<String>,
-    entity_id: Option<String>,
-    field: Option<String>,
-    old_value: Option<String>,
-    new_value: Option<String>,
-    created_at: String,
+) -> Result<StandingOrderRunReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "generate_standing_orders",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if !can_create_work_orders(&role_val) {
+        return Err("Only staff, leads or admins may create work orders".to_string());
+    }
+    generate_standing_orders_through(
+        &state.pool,
+        chrono::Local::now().date_naive(),
+        horizon_days
+            .unwrap_or(STANDING_ORDER_HORIZON_DAYS)
+            .clamp(1, 180),
+        &role_val,
+        &actor_val,
+    )
+    .await
 }
 
-#[tauri::command]
-async fn list_audit_logs(
-    state: State<'_, AppState>,
-    filter: Option<String>, // "day", "7days", "month", "year", "all"
-) -> Result<Vec<AuditLogRow>, String> {
-    let filter_val = filter.unwrap_or_else(|| "all".to_string());
-    let query = match filter_val.as_str() {
-        "day" => {
-            r#"
-            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
-            FROM audit_logs
-            WHERE date(created_at) = date('now')
-            ORDER BY created_at DESC
-            "#
-        }
-        "7days" => {
-            r#"
-            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
-            FROM audit_logs
-            WHERE created_at >= datetime('now', '-7 days')
-            ORDER BY created_at DESC
-            "#
-        }
-        "month" => {
-            r#"
-            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
-            FROM audit_logs
-            WHERE date(created_at) >= date('now', 'start of month')
-            ORDER BY created_at DESC
-            "#
-        }
-        "year" => {
-            r#"
-            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
-            FROM audit_logs
-            WHERE date(created_at) >= date('now', 'start of year')
-            ORDER BY created_at DESC
-            "#
-        }
-        _ => {
-            // "all" or default
-            r#"
-            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
-            FROM audit_logs
-            ORDER BY created_at DESC
-            "#
-        }
-    };
+#[derive(Debug, Deserialize)]
+struct WaitlistEntryInput {
+    client_id: String,
+    requested_cords: f64,
+    priority_flags: Option<Vec<String>>,
+    distance_miles: Option<f64>,
+    notes: Option<String>,
+    created_by_user_id: Option<String>,
+}
 
-    let rows = sqlx::query_as::<_, AuditLogRow>(query)
-        .fetch_all(&state.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WaitlistSettings {
+    weight_days_waiting: f64,
+    weight_priority_flag: f64,
+    // Per-flag overrides of `weight_priority_flag`, keyed by lowercase flag name.
+    flag_weights: HashMap<String, f64>,
+    weight_per_mile: f64,
+}
 
-    Ok(rows)
+#[derive(Debug, FromRow)]
+struct WaitlistSettingsRow {
+    weight_days_waiting: f64,
+    weight_priority_flag: f64,
+    flag_weights_json: String,
+    weight_per_mile: f64,
+}
+
+#[derive(Debug, FromRow)]
+struct WaitlistRow {
+    id: String,
+    client_id: String,
+    client_name: String,
+    requested_cords: f64,
+    priority_flags_json: String,
+    distance_miles: Option<f64>,
+    notes: Option<String>,
+    requested_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RankedWaitlistEntry {
+    rank: usize,
+    id: String,
+    client_id: String,
+    client_name: String,
+    requested_cords: f64,
+    priority_flags: Vec<String>,
+    distance_miles: Option<f64>,
+    notes: Option<String>,
+    requested_at: String,
+    days_waiting: i64,
+    score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct WaitlistPromoteInput {
+    count: usize,
+    scheduled_date: String,
+    assignees_json: Option<String>,
+    created_by_user_id: Option<String>,
+    created_by_display: Option<String>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[derive(Debug, Serialize)]
+struct WaitlistPromotion {
+    entry_id: String,
+    client_id: String,
+    client_name: String,
+    work_order_id: String,
+    cords: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct WaitlistSkip {
+    entry_id: String,
+    client_id: String,
+    reason: String,
+}
+
+#[derive(Debug, Serialize)]
+struct WaitlistPromoteReport {
+    promoted: Vec<WaitlistPromotion>,
+    skipped: Vec<WaitlistSkip>,
+    // Set when promotion ended early, e.g. the next household needs more wood than is available.
+    stopped_reason: Option<String>,
+}
+
+fn waitlist_score(
+    settings: &WaitlistSettings,
+    days_waiting: i64,
+    flags: &[String],
+    distance_miles: Option<f64>,
+) -> f64 {
+    let flag_total: f64 = flags
+        .iter()
+        .map(|flag| {
+            settings
+                .flag_weights
+                .get(&flag.to_lowercase())
+                .copied()
+                .unwrap_or(settings.weight_priority_flag)
+        })
+        .sum();
+    days_waiting as f64 * settings.weight_days_waiting
+        + flag_total
+        + distance_miles.unwrap_or(0.0) * settings.weight_per_mile
+}
+
+/// Highest score first; ties go to whoever asked first.
+fn rank_waitlist(
+    rows: Vec<WaitlistRow>,
+    settings: &WaitlistSettings,
+    today: chrono::NaiveDate,
+) -> Vec<RankedWaitlistEntry> {
+    let mut ranked: Vec<RankedWaitlistEntry> = rows
+        .into_iter()
+        .map(|row| {
+            let priority_flags: Vec<String> =
+                serde_json::from_str(&row.priority_flags_json).unwrap_or_default();
+            let days_waiting = row
+                .requested_at
+                .get(..10)
+                .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .map(|d| (today - d).num_days().max(0))
+                .unwrap_or(0);
+            let score = waitlist_score(settings, days_waiting, &priority_flags, row.distance_miles);
+            RankedWaitlistEntry {
+                rank: 0,
+                id: row.id,
+                client_id: row.client_id,
+                client_name: row.client_name,
+                requested_cords: row.requested_cords,
+                priority_flags,
+                distance_miles: row.distance_miles,
+                notes: row.notes,
+                requested_at: row.requested_at,
+                days_waiting,
+                score,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.requested_at.cmp(&b.requested_at))
+    });
+    for (i, entry) in ranked.iter_mut().enumerate() {
+        entry.rank = i + 1;
+    }
+    ranked
+}
+
+async fn load_waitlist_settings(
+    conn: &mut sqlx::SqliteConnection,
+) -> Result<WaitlistSettings, String> {
+    let row = sqlx::query_as::<_, WaitlistSettingsRow>(
+        r#"
+        SELECT weight_days_waiting, weight_priority_flag, flag_weights_json, weight_per_mile
+        FROM waitlist_settings
+        WHERE id = 'default'
+        "#,
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(match row {
+        Some(row) => WaitlistSettings {
+            weight_days_waiting: row.weight_days_waiting,
+            weight_priority_flag: row.weight_priority_flag,
+            flag_weights: serde_json::from_str(&row.flag_weights_json).unwrap_or_default(),
+            weight_per_mile: row.weight_per_mile,
+        },
+        None => WaitlistSettings {
+            weight_days_waiting: 1.0,
+            weight_priority_flag: 30.0,
+            flag_weights: HashMap::new(),
+            weight_per_mile: -0.5,
+        },
+    })
+}
+
+/// Waiting entries for approved, active clients in queue order.
+async fn load_ranked_waitlist(
+    conn: &mut sqlx::SqliteConnection,
+) -> Result<Vec<RankedWaitlistEntry>, String> {
+    let settings = load_waitlist_settings(&mut *conn).await?;
+    let rows = sqlx::query_as::<_, WaitlistRow>(
+        r#"
+        SELECT
+            w.id,
+            w.client_id,
+            c.name AS client_name,
+            w.requested_cords,
+            w.priority_flags_json,
+            COALESCE(w.distance_miles, c.default_mileage) AS distance_miles,
+            w.notes,
+            w.requested_at
+        FROM waitlist_entries w
+        JOIN clients c ON c.id = w.client_id
+        WHERE w.is_deleted = 0
+          AND w.status = 'waiting'
+          AND c.is_deleted = 0
+          AND lower(c.approval_status) = 'approved'
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rank_waitlist(
+        rows,
+        &settings,
+        chrono::Local::now().date_naive(),
+    ))
+}
+
+#[tauri::command]
+async fn add_waitlist_entry(
+    state: State<'_, AppState>,
+    input: WaitlistEntryInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "add_waitlist_entry", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads or admins can manage the waitlist".to_string());
+    }
+    if input.requested_cords <= 0.0 {
+        return Err("Requested cords must be greater than zero".to_string());
+    }
+    let approval: Option<String> =
+        sqlx::query_scalar("SELECT approval_status FROM clients WHERE id = ? AND is_deleted = 0")
+            .bind(&input.client_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    match approval {
+        None => return Err("Client not found".to_string()),
+        Some(status) if !status.eq_ignore_ascii_case("approved") => {
+            return Err("Only approved clients can join the waitlist".to_string())
+        }
+        Some(_) => {}
+    }
+
+    let flags: Vec<String> = input
+        .priority_flags
+        .unwrap_or_default()
+        .iter()
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO waitlist_entries (
+            id, client_id, requested_cords, priority_flags_json, distance_miles, notes,
+            created_by_user_id
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.client_id)
+    .bind(input.requested_cords)
+    .bind(serde_json::to_string(&flags).map_err(|e| e.to_string())?)
+    .bind(input.distance_miles)
+    .bind(&input.notes)
+    .bind(&input.created_by_user_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE") {
+            "Client is already on the waitlist".to_string()
+        } else {
+            e.to_string()
+        }
+    })?;
+
+    audit_change(
+        &state.pool,
+        "add_waitlist_entry",
+        &role_val,
+        &actor_val,
+        "waitlist_entries",
+        &id,
+        "status",
+        None,
+        Some("waiting".to_string()),
+    )
+    .await;
+    Ok(id)
+}
+
+#[tauri::command]
+async fn remove_waitlist_entry(
+    state: State<'_, AppState>,
+    id: String,
+    reason: Option<String>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "remove_waitlist_entry", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads or admins can manage the waitlist".to_string());
+    }
+    let updated = sqlx::query(
+        r#"
+        UPDATE waitlist_entries
+        SET status = 'removed', removed_reason = ?, updated_at = datetime('now'), version = version + 1
+        WHERE id = ? AND status = 'waiting' AND is_deleted = 0
+        "#,
+    )
+    .bind(&reason)
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if updated.rows_affected() == 0 {
+        return Err("Waitlist entry not found or no longer waiting".to_string());
+    }
+    audit_change(
+        &state.pool,
+        "remove_waitlist_entry",
+        &role_val,
+        &actor_val,
+        "waitlist_entries",
+        &id,
+        "status",
+        Some("waiting".to_string()),
+        Some(match reason {
+            Some(r) if !r.trim().is_empty() => format!("removed: {}", r.trim()),
+            _ => "removed".to_string(),
+        }),
+    )
+    .await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_waitlist(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<RankedWaitlistEntry>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_waitlist", &role_val, &actor_val).await;
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    load_ranked_waitlist(&mut conn).await
+}
+
+#[tauri::command]
+async fn get_waitlist_settings(state: State<'_, AppState>) -> Result<WaitlistSettings, String> {
+    let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+    load_waitlist_settings(&mut conn).await
+}
+
+#[tauri::command]
+async fn update_waitlist_settings(
+    state: State<'_, AppState>,
+    input: WaitlistSettings,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "update_waitlist_settings",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can change the waitlist formula".to_string());
+    }
+    let previous = {
+        let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+        load_waitlist_settings(&mut conn).await?
+    };
+    let flag_weights: HashMap<String, f64> = input
+        .flag_weights
+        .iter()
+        .map(|(flag, weight)| (flag.trim().to_lowercase(), *weight))
+        .filter(|(flag, _)| !flag.is_empty())
+        .collect();
+    let flag_weights_json = serde_json::to_string(&flag_weights).map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        INSERT INTO waitlist_settings (
+            id, weight_days_waiting, weight_priority_flag, flag_weights_json, weight_per_mile,
+            updated_by, updated_at
+        )
+        VALUES ('default', ?, ?, ?, ?, ?, datetime('now'))
+        ON CONFLICT(id) DO UPDATE SET
+            weight_days_waiting = excluded.weight_days_waiting,
+            weight_priority_flag = excluded.weight_priority_flag,
+            flag_weights_json = excluded.flag_weights_json,
+            weight_per_mile = excluded.weight_per_mile,
+            updated_by = excluded.updated_by,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(input.weight_days_waiting)
+    .bind(input.weight_priority_flag)
+    .bind(&flag_weights_json)
+    .bind(input.weight_per_mile)
+    .bind(&actor_val)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let describe = |s: &WaitlistSettings, flags: String| {
+        format!(
+            "days={} flag={} flags={} mile={}",
+            s.weight_days_waiting, s.weight_priority_flag, flags, s.weight_per_mile
+        )
+    };
+    let old_flags = serde_json::to_string(&previous.flag_weights).unwrap_or_default();
+    audit_change(
+        &state.pool,
+        "update_waitlist_settings",
+        &role_val,
+        &actor_val,
+        "waitlist_settings",
+        "default",
+        "formula",
+        Some(describe(&previous, old_flags)),
+        Some(describe(&input, flag_weights_json)),
+    )
+    .await;
+    Ok(())
+}
+
+/// Turns the top `count` waitlist entries into scheduled delivery orders, reserving wood for each.
+/// Stops at the first household the remaining stock cannot cover rather than skipping ahead,
+/// so the queue order is respected. Entries blocked by an allotment rule are skipped.
+#[tauri::command]
+async fn promote_waitlist(
+    state: State<'_, AppState>,
+    input: WaitlistPromoteInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<WaitlistPromoteReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "promote_waitlist", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads or admins can manage the waitlist".to_string());
+    }
+    if chrono::NaiveDate::parse_from_str(&input.scheduled_date, "%Y-%m-%d").is_err() {
+        return Err("Scheduled date must be YYYY-MM-DD".to_string());
+    }
+    let assignees = input
+        .assignees_json
+        .clone()
+        .unwrap_or_else(|| "[]".to_string());
+    let assignee_names = parse_assignee_names(Some(&assignees))?;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let ranked = load_ranked_waitlist(&mut tx).await?;
+    let mut report = WaitlistPromoteReport {
+        promoted: Vec::new(),
+        skipped: Vec::new(),
+        stopped_reason: None,
+    };
+    let mut warnings: Vec<(String, String)> = Vec::new();
+
+    for entry in ranked {
+        if report.promoted.len() >= input.count {
+            break;
+        }
+        let check = check_allotment(
+            &mut tx,
+            &entry.client_id,
+            None,
+            Some(&input.scheduled_date),
+            entry.requested_cords,
+        )
+        .await?;
+        match resolve_allotment(&check, &role_val, None) {
+            Err(reason) => {
+                report.skipped.push(WaitlistSkip {
+                    entry_id: entry.id,
+                    client_id: entry.client_id,
+                    reason,
+                });
+                continue;
+            }
+            Ok(Some((_, message))) => warnings.push((entry.id.clone(), message)),
+            Ok(None) => {}
+        }
+
+        // Reserve first: this fails (without writing) when stock cannot cover the entry.
+        if let Err(reason) =
+            adjust_inventory_for_transition_tx(&mut tx, "draft", "scheduled", entry.requested_cords)
+                .await
+        {
+            report.stopped_reason = Some(format!("{}: {}", entry.client_name, reason));
+            break;
+        }
+
+        let work_order_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO work_orders (
+                id, client_id, client_title, client_name,
+                physical_address_line1, physical_address_line2, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                mailing_address_line1, mailing_address_line2, mailing_address_city,
+                mailing_address_state, mailing_address_postal_code,
+                telephone, email, directions, gate_combo,
+                wood_size_label, wood_size_other,
+                notes, scheduled_date, status,
+                delivery_size_cords, pickup_delivery_type,
+                assignees_json, created_by_user_id, created_by_display
+            )
+            SELECT
+                ?, id, client_title, name,
+                physical_address_line1, physical_address_line2, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                mailing_address_line1, mailing_address_line2, mailing_address_city,
+                mailing_address_state, mailing_address_postal_code,
+                telephone, email, directions, gate_combo,
+                wood_size_label, wood_size_other,
+                ?, ?, 'scheduled',
+                ?, 'delivery',
+                ?, ?, ?
+            FROM clients
+            WHERE id = ?
+            "#,
+        )
+        .bind(&work_order_id)
+        .bind(&entry.notes)
+        .bind(&input.scheduled_date)
+        .bind(entry.requested_cords)
+        .bind(&assignees)
+        .bind(&input.created_by_user_id)
+        .bind(&input.created_by_display)
+        .bind(&entry.client_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO delivery_events (
+                id, title, description, event_type, work_order_id,
+                start_date, end_date, color_code, assigned_user_ids_json
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(format!("Delivery for {}", entry.client_name))
+        .bind::<Option<String>>(None)
+        .bind("delivery")
+        .bind(&work_order_id)
+        .bind(&input.scheduled_date)
+        .bind::<Option<String>>(None)
+        .bind("#e67f1e")
+        .bind(&assignees)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        record_status_change(
+            &mut tx,
+            &work_order_id,
+            None,
+            WorkOrderStatus::Scheduled.as_str(),
+            &role_val,
+            &actor_val,
+            Some("Promoted from the waitlist"),
+        )
+        .await?;
+        sync_assignments_from_names(&mut tx, &work_order_id, &assignee_names, &actor_val).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE waitlist_entries
+            SET status = 'promoted', work_order_id = ?, promoted_at = datetime('now'),
+                updated_at = datetime('now'), version = version + 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(&work_order_id)
+        .bind(&entry.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        report.promoted.push(WaitlistPromotion {
+            entry_id: entry.id,
+            client_id: entry.client_id,
+            client_name: entry.client_name,
+            work_order_id,
+            cords: entry.requested_cords,
+        });
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    for promotion in &report.promoted {
+        audit_change(
+            &state.pool,
+            "promote_waitlist",
+            &role_val,
+            &actor_val,
+            "waitlist_entries",
+            &promotion.entry_id,
+            "status",
+            Some("waiting".to_string()),
+            Some(format!("promoted: {}", promotion.work_order_id)),
+        )
+        .await;
+    }
+    for (entry_id, message) in warnings {
+        audit_change(
+            &state.pool,
+            "promote_waitlist",
+            &role_val,
+            &actor_val,
+            "waitlist_entries",
+            &entry_id,
+            "allotment_warning",
+            None,
+            Some(message),
+        )
+        .await;
+    }
+    Ok(report)
+}
+
+#[derive(Debug, Deserialize)]
+struct AttachmentInput {
+    entity: String,
+    entity_id: String,
+    attachment_type: String,
+    file_name: Option<String>,
+    mime_type: Option<String>,
+    // The frontend reads the picked file and sends its bytes; the backend never opens paths.
+    content: Vec<u8>,
+    uploaded_by_user_id: Option<String>,
+    uploaded_by_display: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct AttachmentRow {
+    id: String,
+    entity: String,
+    entity_id: String,
+    attachment_type: String,
+    file_name: String,
+    mime_type: Option<String>,
+    size_bytes: i64,
+    sha256: String,
+    contains_pii: bool,
+    uploaded_by_user_id: Option<String>,
+    uploaded_by_display: Option<String>,
+    created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct AttachmentContent {
+    attachment: AttachmentRow,
+    content: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+struct AttachmentIntegrityReport {
+    checked: usize,
+    ok: usize,
+    missing_ids: Vec<String>,
+    corrupted_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AttachmentCleanupReport {
+    dry_run: bool,
+    // Files on disk that no live attachment row points at.
+    orphan_files: Vec<String>,
+    orphan_bytes: u64,
+    // Live rows whose client or work order has been deleted.
+    orphan_attachment_ids: Vec<String>,
+}
+
+const ATTACHMENT_ENTITIES: [&str; 2] = ["clients", "work_orders"];
+const ATTACHMENT_TYPES: [&str; 4] = [
+    "proof_of_residence",
+    "onboarding_form",
+    "delivery_photo",
+    "other",
+];
+const MAX_ATTACHMENT_BYTES: usize = 25 * 1024 * 1024;
+
+/// Delivery photos show the woodpile, not the household; every other type is client paperwork.
+fn attachment_type_contains_pii(attachment_type: &str) -> bool {
+    attachment_type != "delivery_photo"
+}
+
+const ATTACHMENT_COLUMNS: &str = r#"
+    id, entity, entity_id, attachment_type, file_name, mime_type, size_bytes, sha256,
+    contains_pii, uploaded_by_user_id, uploaded_by_display, created_at
+"#;
+
+async fn fetch_attachment(pool: &SqlitePool, id: &str) -> Result<AttachmentRow, String> {
+    sqlx::query_as::<_, AttachmentRow>(&format!(
+        "SELECT {} FROM attachments WHERE id = ? AND is_deleted = 0",
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Attachment not found".to_string())
+}
+
+#[tauri::command]
+async fn add_attachment(
+    state: State<'_, AppState>,
+    input: AttachmentInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<AttachmentRow, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "add_attachment", &role_val, &actor_val).await;
+
+    let entity = input.entity.trim().to_lowercase();
+    if !ATTACHMENT_ENTITIES.contains(&entity.as_str()) {
+        return Err("Attachments can only be added to clients or work orders".to_string());
+    }
+    let attachment_type = input.attachment_type.trim().to_lowercase();
+    if !ATTACHMENT_TYPES.contains(&attachment_type.as_str()) {
+        return Err(format!(
+            "Attachment type must be one of: {}",
+            ATTACHMENT_TYPES.join(", ")
+        ));
+    }
+    // Drivers may add delivery photos; everything else is office paperwork.
+    let is_delivery_photo = entity == "work_orders" && attachment_type == "delivery_photo";
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) && !is_delivery_photo
+    {
+        return Err("Volunteers can only attach delivery photos to work orders".to_string());
+    }
+    let exists: Option<String> = sqlx::query_scalar(&format!(
+        "SELECT id FROM {} WHERE id = ? AND is_deleted = 0",
+        entity
+    ))
+    .bind(&input.entity_id)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if exists.is_none() {
+        return Err("Attachment target not found".to_string());
+    }
+
+    let bytes = input.content;
+    if bytes.is_empty() {
+        return Err("Attachment is empty".to_string());
+    }
+    if bytes.len() > MAX_ATTACHMENT_BYTES {
+        return Err("Attachments are limited to 25 MB".to_string());
+    }
+    let file_name = input
+        .file_name
+        .clone()
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| "attachment".to_string());
+    let contains_pii = attachment_type_contains_pii(&attachment_type);
+    let size_bytes = bytes.len() as i64;
+    let sha256 = attachments::sha256_hex(&bytes);
+
+    // The row is written before the blob and both commit together: the insert holds the write
+    // lock, so cleanup_orphan_attachments cannot list live hashes until this row is visible.
+    let id = Uuid::new_v4().to_string();
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        INSERT INTO attachments (
+            id, entity, entity_id, attachment_type, file_name, mime_type, size_bytes, sha256,
+            contains_pii, uploaded_by_user_id, uploaded_by_display
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&entity)
+    .bind(&input.entity_id)
+    .bind(&attachment_type)
+    .bind(&file_name)
+    .bind(&input.mime_type)
+    .bind(size_bytes)
+    .bind(&sha256)
+    .bind(contains_pii)
+    .bind(&input.uploaded_by_user_id)
+    .bind(input.uploaded_by_display.as_deref().unwrap_or(&actor_val))
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let root = state.attachments_dir.clone();
+    tauri::async_runtime::spawn_blocking(move || attachments::store_blob(&root, &bytes))
+        .await
+        .map_err(|e| e.to_string())??;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    audit_change(
+        &state.pool,
+        "add_attachment",
+        &role_val,
+        &actor_val,
+        "attachments",
+        &id,
+        "sha256",
+        None,
+        Some(format!("{} {} {}", entity, input.entity_id, sha256)),
+    )
+    .await;
+    fetch_attachment(&state.pool, &id).await
+}
+
+/// PII attachments (ID scans, signed forms) are left out for users who cannot see client PII.
+#[tauri::command]
+async fn list_attachments(
+    state: State<'_, AppState>,
+    entity: String,
+    entity_id: String,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<Vec<AttachmentRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_attachments", &role_val, &actor_val).await;
+    let can_view_pii = can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false));
+    let rows = sqlx::query_as::<_, AttachmentRow>(&format!(
+        r#"
+        SELECT {}
+        FROM attachments
+        WHERE entity = ? AND entity_id = ? AND is_deleted = 0
+        ORDER BY created_at DESC
+        "#,
+        ATTACHMENT_COLUMNS
+    ))
+    .bind(entity.to_lowercase())
+    .bind(&entity_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter(|a| can_view_pii || !a.contains_pii)
+        .collect())
+}
+
+#[tauri::command]
+async fn get_attachment_content(
+    state: State<'_, AppState>,
+    id: String,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<AttachmentContent, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "get_attachment_content", &role_val, &actor_val).await;
+    let attachment = fetch_attachment(&state.pool, &id).await?;
+    if attachment.contains_pii && !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false))
+    {
+        return Err("Only admins or HIPAA-certified leads can open this attachment".to_string());
+    }
+    let content = attachments::read_blob(&state.attachments_dir, &attachment.sha256)?;
+    if attachments::sha256_hex(&content) != attachment.sha256 {
+        return Err("Attachment file failed its integrity check".to_string());
+    }
+    // Record who opened which document, not just that the command ran.
+    audit_change(
+        &state.pool,
+        "get_attachment_content",
+        &role_val,
+        &actor_val,
+        "attachments",
+        &id,
+        "viewed",
+        None,
+        Some(attachment.file_name.clone()),
+    )
+    .await;
+    Ok(AttachmentContent {
+        attachment,
+        content,
+    })
+}
+
+/// Soft-deletes the metadata row; the file is removed by orphan cleanup once unreferenced.
+#[tauri::command]
+async fn delete_attachment(
+    state: State<'_, AppState>,
+    id: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "delete_attachment", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can delete attachments".to_string());
+    }
+    let attachment = fetch_attachment(&state.pool, &id).await?;
+    sqlx::query(
+        r#"
+        UPDATE attachments
+        SET is_deleted = 1, updated_at = datetime('now'), version = version + 1
+        WHERE id = ?
+        "#,
+    )
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    audit_change(
+        &state.pool,
+        "delete_attachment",
+        &role_val,
+        &actor_val,
+        "attachments",
+        &id,
+        "is_deleted",
+        Some(attachment.file_name),
+        Some("deleted".to_string()),
+    )
+    .await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn verify_attachments(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<AttachmentIntegrityReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "verify_attachments", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can verify attachments".to_string());
+    }
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, sha256 FROM attachments WHERE is_deleted = 0")
+            .fetch_all(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    let root = state.attachments_dir.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut report = AttachmentIntegrityReport {
+            checked: rows.len(),
+            ok: 0,
+            missing_ids: Vec::new(),
+            corrupted_ids: Vec::new(),
+        };
+        // Rows sharing a blob get the same verdict, so hash each file once.
+        let mut verdicts: HashMap<String, u8> = HashMap::new();
+        for (id, sha256) in rows {
+            let verdict = match verdicts.get(&sha256) {
+                Some(v) => *v,
+                None => {
+                    let v = match attachments::verify_blob(&root, &sha256) {
+                        Ok(attachments::BlobState::Ok) => 0,
+                        Ok(attachments::BlobState::Missing) => 1,
+                        Ok(attachments::BlobState::Corrupted) | Err(_) => 2,
+                    };
+                    verdicts.insert(sha256, v);
+                    v
+                }
+            };
+            match verdict {
+                0 => report.ok += 1,
+                1 => report.missing_ids.push(id),
+                _ => report.corrupted_ids.push(id),
+            }
+        }
+        report
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn cleanup_orphan_attachments(
+    state: State<'_, AppState>,
+    dry_run: Option<bool>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<AttachmentCleanupReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "cleanup_orphan_attachments",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if role_val != "admin" {
+        return Err("Only admins can clean up attachments".to_string());
+    }
+    let dry_run = dry_run.unwrap_or(true);
+
+    const ORPHAN_ROWS: &str = r#"
+        attachments.is_deleted = 0
+        AND (
+          (attachments.entity = 'clients'
+            AND NOT EXISTS (
+              SELECT 1 FROM clients c WHERE c.id = attachments.entity_id AND c.is_deleted = 0
+            ))
+          OR (attachments.entity = 'work_orders'
+            AND NOT EXISTS (
+              SELECT 1 FROM work_orders w WHERE w.id = attachments.entity_id AND w.is_deleted = 0
+            ))
+        )
+    "#;
+    // A real cleanup opens with a write so it holds the lock add_attachment takes for its insert;
+    // an upload is then either fully visible in the live set below or not started.
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+    let orphan_attachment_ids: Vec<String> = if dry_run {
+        sqlx::query_scalar(&format!("SELECT id FROM attachments WHERE {}", ORPHAN_ROWS))
+            .fetch_all(&mut *tx)
+            .await
+    } else {
+        sqlx::query_scalar(&format!(
+            r#"
+            UPDATE attachments
+            SET is_deleted = 1, updated_at = datetime('now'), version = version + 1
+            WHERE {}
+            RETURNING id
+            "#,
+            ORPHAN_ROWS
+        ))
+        .fetch_all(&mut *tx)
+        .await
+    }
+    .map_err(|e| e.to_string())?;
+
+    // In a dry run the orphaned rows above still count as live, matching what would be kept.
+    let live: HashSet<String> = sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT sha256 FROM attachments WHERE is_deleted = 0",
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .collect();
+    let root = state.attachments_dir.clone();
+    let (orphan_files, orphan_bytes) = tauri::async_runtime::spawn_blocking(move || {
+        let mut files = Vec::new();
+        let mut bytes = 0;
+        for (name, size) in attachments::list_blobs(&root)? {
+            if live.contains(&name) {
+                continue;
+            }
+            if !dry_run {
+                attachments::remove_blob_file(&root, &name)?;
+            }
+            bytes += size;
+            files.push(name);
+        }
+        Ok::<_, String>((files, bytes))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if !dry_run {
+        audit_change(
+            &state.pool,
+            "cleanup_orphan_attachments",
+            &role_val,
+            &actor_val,
+            "attachments",
+            "*",
+            "cleanup",
+            None,
+            Some(format!(
+                "{} rows, {} files, {} bytes",
+                orphan_attachment_ids.len(),
+                orphan_files.len(),
+                orphan_bytes
+            )),
+        )
+        .await;
+    }
+    Ok(AttachmentCleanupReport {
+        dry_run,
+        orphan_files,
+        orphan_bytes,
+        orphan_attachment_ids,
+    })
+}
+
+#[tauri::command]
+async fn delete_user(
+    state: State<'_, AppState>,
+    id: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can delete users".to_string());
+    }
+    audit_db(&state.pool, "delete_user", &role_val, &actor_val).await;
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET is_deleted = 1,
+            updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        r#"
+        UPDATE auth_users
+        SET is_deleted = 1,
+            updated_at = datetime('now')
+        WHERE user_id = ?
+        "#,
+    )
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn check_client_conflict(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Vec<ClientConflictRow>, String> {
+    find_client_conflicts(&state.pool, &name).await
+}
+
+async fn find_client_conflicts(
+    pool: &SqlitePool,
+    name: &str,
+) -> Result<Vec<ClientConflictRow>, String> {
+    let rows = sqlx::query_as::<_, ClientConflictRow>(
+        r#"
+        SELECT
+            id,
+            name,
+            physical_address_line1,
+            physical_address_city,
+            physical_address_state
+        FROM clients
+        WHERE is_deleted = 0
+          AND (
+            lower(name) = lower(?1)
+            OR lower(name) LIKE '%' || lower(?1) || '%'
+            OR lower(?1) LIKE '%' || lower(name) || '%'
+          )
+        "#,
+    )
+    .bind(name)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+fn import_header_key(value: &str) -> String {
+    value.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+#[tauri::command]
+async fn import_clients_csv(
+    state: State<'_, AppState>,
+    input: ClientImportInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<ClientImportReport, String> {
+    import_clients_csv_with_pool(&state.pool, input, role, actor).await
+}
+
+async fn import_clients_csv_with_pool(
+    pool: &SqlitePool,
+    input: ClientImportInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<ClientImportReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads, or admins can import clients.".to_string());
+    }
+    let dry_run = input.dry_run.unwrap_or(true);
+    audit_db(pool, "import_clients_csv", &role_val, &actor_val).await;
+
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(input.csv_text.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Could not read CSV header: {}", e))?
+        .iter()
+        .map(import_header_key)
+        .collect();
+
+    let column_map = input.column_map.unwrap_or_default();
+    if let Some(unknown) = column_map
+        .keys()
+        .find(|field| !CLIENT_IMPORT_FIELDS.contains(&field.as_str()))
+    {
+        return Err(format!("Unknown client field in column map: {}", unknown));
+    }
+    let mut columns: HashMap<&str, usize> = HashMap::new();
+    for field in CLIENT_IMPORT_FIELDS {
+        let header = column_map
+            .get(field)
+            .map(|h| import_header_key(h))
+            .unwrap_or_else(|| field.to_string());
+        match headers.iter().position(|h| *h == header) {
+            Some(idx) => {
+                columns.insert(field, idx);
+            }
+            None if column_map.contains_key(field) => {
+                return Err(format!(
+                    "Mapped column '{}' for {} is not in the CSV header.",
+                    column_map[field], field
+                ));
+            }
+            None => {}
+        }
+    }
+    if !["name", "first_name", "last_name"]
+        .iter()
+        .any(|f| columns.contains_key(f))
+    {
+        return Err("CSV needs a name column (name, or first_name/last_name).".to_string());
+    }
+    for required in [
+        "physical_address_line1",
+        "physical_address_city",
+        "physical_address_state",
+        "physical_address_postal_code",
+    ] {
+        if !columns.contains_key(required) {
+            return Err(format!("CSV is missing required column: {}", required));
+        }
+    }
+
+    struct PreparedClient {
+        input: ClientInput,
+        name: String,
+        first_name: String,
+        last_name: String,
+        approval_status: String,
+    }
+
+    let mut rows: Vec<ClientImportRowResult> = Vec::new();
+    let mut prepared: Vec<Option<PreparedClient>> = Vec::new();
+    // lower(name) -> (address key, row number) for rows already seen in this file
+    let mut seen: HashMap<String, (String, u64)> = HashMap::new();
+
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Could not read CSV row: {}", e))?;
+        if record.iter().all(|v| v.is_empty()) {
+            continue;
+        }
+        let row_number = record.position().map(|p| p.line()).unwrap_or(0);
+        let get = |field: &str| -> Option<String> {
+            columns
+                .get(field)
+                .and_then(|idx| record.get(*idx))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let mut messages: Vec<String> = Vec::new();
+
+        let mut first_name = get("first_name").unwrap_or_default();
+        let mut last_name = get("last_name").unwrap_or_default();
+        if first_name.is_empty() && last_name.is_empty() {
+            if let Some(full) = get("name") {
+                match full.rsplit_once(' ') {
+                    Some((first, last)) => {
+                        first_name = first.trim().to_string();
+                        last_name = last.to_string();
+                    }
+                    None => last_name = full,
+                }
+            }
+        }
+        let name = format!("{} {}", first_name, last_name).trim().to_string();
+        if name.is_empty() {
+            messages.push("Name is required.".to_string());
+        }
+
+        let line1 = get("physical_address_line1").unwrap_or_default();
+        if line1.is_empty() {
+            messages.push("Physical address line 1 is required.".to_string());
+        }
+        let city = validation::init_cap_city(&get("physical_address_city").unwrap_or_default());
+        if city.is_empty() {
+            messages.push("Physical city is required.".to_string());
+        }
+        let (physical_state, err) = validation::normalize_and_validate_state(
+            &get("physical_address_state").unwrap_or_default(),
+            "Physical state",
+        );
+        messages.extend(err);
+        let (physical_postal, err) = validation::normalize_and_validate_postal(
+            &get("physical_address_postal_code").unwrap_or_default(),
+            "Physical ZIP",
+            false,
+        );
+        messages.extend(err);
+        let mailing_state = get("mailing_address_state").map(|v| {
+            let (normalized, err) = validation::normalize_and_validate_state(&v, "Mailing state");
+            messages.extend(err);
+            normalized
+        });
+        let mailing_postal = get("mailing_address_postal_code").map(|v| {
+            let (normalized, err) =
+                validation::normalize_and_validate_postal(&v, "Mailing ZIP", true);
+            messages.extend(err);
+            normalized
+        });
+        let telephone = get("telephone").map(|v| {
+            let (normalized, err) = validation::normalize_and_validate_phone(&v);
+            messages.extend(err);
+            normalized
+        });
+        let approval_status = get("approval_status")
+            .map(|v| v.to_lowercase())
+            .unwrap_or_else(|| "pending".to_string());
+        if !["pending", "approved", "denied"].contains(&approval_status.as_str()) {
+            messages.push(format!(
+                "Approval status '{}' must be pending, approved, or denied.",
+                approval_status
+            ));
+        }
+        let household_size = get("household_size").and_then(|v| match v.parse::<i64>() {
+            Ok(size) if size > 0 => Some(size),
+            _ => {
+                messages.push(format!(
+                    "Household size '{}' must be a positive whole number.",
+                    v
+                ));
+                None
+            }
+        });
+        let date_of_onboarding = get("date_of_onboarding").map(|v| {
+            match chrono::NaiveDate::parse_from_str(&v, "%Y-%m-%d")
+                .or_else(|_| chrono::NaiveDate::parse_from_str(&v, "%m/%d/%Y"))
+            {
+                Ok(date) => date.format("%Y-%m-%d").to_string(),
+                Err(_) => {
+                    messages.push(format!(
+                        "Date of onboarding '{}' must be YYYY-MM-DD or MM/DD/YYYY.",
+                        v
+                    ));
+                    v
+                }
+            }
+        });
+
+        let mut status = if messages.is_empty() {
+            "ready"
+        } else {
+            "error"
+        };
+        if status == "ready" {
+            let address_key = format!(
+                "{}|{}|{}",
+                line1.to_lowercase(),
+                city.to_lowercase(),
+                physical_state.to_lowercase()
+            );
+            if let Some((prev_address, prev_row)) = seen.get(&name.to_lowercase()) {
+                if *prev_address == address_key {
+                    status = "duplicate";
+                    messages.push(format!("Duplicate of row {} in this file.", prev_row));
+                } else {
+                    status = "error";
+                    messages.push(format!(
+                        "Name '{}' appears on row {} with a different address.",
+                        name, prev_row
+                    ));
+                }
+            } else {
+                // Same rule as create_client: a name can only have one address.
+                let conflicts = find_client_conflicts(pool, &name).await?;
+                let same_address = |c: &ClientConflictRow| {
+                    c.physical_address_line1.eq_ignore_ascii_case(&line1)
+                        && c.physical_address_city.eq_ignore_ascii_case(&city)
+                        && c.physical_address_state
+                            .eq_ignore_ascii_case(&physical_state)
+                };
+                if let Some(existing) = conflicts.iter().find(|c| !same_address(c)) {
+                    status = "error";
+                    messages.push(format!(
+                        "Name '{}' already exists at a different address (id {}, {} {})",
+                        existing.name,
+                        existing.id,
+                        existing.physical_address_line1,
+                        existing.physical_address_city
+                    ));
+                } else if let Some(existing) = conflicts.first() {
+                    status = "duplicate";
+                    messages.push(format!("Already on file as client {}.", existing.id));
+                }
+                seen.insert(name.to_lowercase(), (address_key, row_number));
+            }
+        }
+
+        let client = (status == "ready").then(|| PreparedClient {
+            input: ClientInput {
+                client_title: get("client_title"),
+                first_name: Some(first_name.clone()),
+                last_name: Some(last_name.clone()),
+                physical_address_line1: line1,
+                physical_address_line2: get("physical_address_line2"),
+                physical_address_city: city,
+                physical_address_state: physical_state,
+                physical_address_postal_code: physical_postal,
+                mailing_address_line1: get("mailing_address_line1"),
+                mailing_address_line2: get("mailing_address_line2"),
+                mailing_address_city: get("mailing_address_city")
+                    .map(|c| validation::init_cap_city(&c)),
+                mailing_address_state: mailing_state,
+                mailing_address_postal_code: mailing_postal,
+                telephone,
+                email: get("email"),
+                date_of_onboarding,
+                how_did_they_hear_about_us: get("how_did_they_hear_about_us"),
+                referring_agency: get("referring_agency"),
+                approval_status: Some(approval_status.clone()),
+                denial_reason: get("denial_reason"),
+                gate_combo: get("gate_combo"),
+                notes: get("notes"),
+                wood_size_label: get("wood_size_label"),
+                wood_size_other: get("wood_size_other"),
+                directions: get("directions"),
+                household_size,
+                site_notes: None,
+                created_by_user_id: input.created_by_user_id.clone(),
+            },
+            name: name.clone(),
+            first_name,
+            last_name,
+            approval_status,
+        });
+
+        rows.push(ClientImportRowResult {
+            row_number,
+            name,
+            status: status.to_string(),
+            errors: messages,
+            client_id: None,
+        });
+        prepared.push(client);
+    }
+
+    let count = |status: &str| rows.iter().filter(|r| r.status == status).count();
+    let ready_count = count("ready");
+    let duplicate_count = count("duplicate");
+    let error_count = count("error");
+
+    // All-or-nothing: a single invalid row blocks the whole import.
+    let mut committed = false;
+    if !dry_run && error_count == 0 && ready_count > 0 {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        for (row, client) in rows.iter_mut().zip(prepared.iter()) {
+            if let Some(client) = client {
+                let id = Uuid::new_v4().to_string();
+                insert_client(
+                    &mut *tx,
+                    &id,
+                    &client.input,
+                    &client.name,
+                    &client.first_name,
+                    &client.last_name,
+                    &client.approval_status,
+                )
+                .await
+                .map_err(|e| format!("Row {}: {}", row.row_number, e))?;
+                row.status = "imported".to_string();
+                row.client_id = Some(id);
+            }
+        }
+        let imported_ids: Vec<String> = rows.iter().filter_map(|r| r.client_id.clone()).collect();
+        link_client_agencies(&mut tx, Some(&imported_ids)).await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        committed = true;
+        let _ = refresh_client_distance_estimates(pool, Some(&imported_ids)).await;
+
+        for row in rows.iter().filter(|r| r.status == "imported") {
+            audit_change(
+                pool,
+                "import_clients_csv",
+                &role_val,
+                &actor_val,
+                "clients",
+                row.client_id.as_deref().unwrap_or_default(),
+                "import_row",
+                None,
+                Some(row.row_number.to_string()),
+            )
+            .await;
+        }
+    }
+
+    Ok(ClientImportReport {
+        dry_run,
+        committed,
+        total_rows: rows.len(),
+        ready_count,
+        duplicate_count,
+        error_count,
+        rows,
+    })
+}
+
+#[tauri::command]
+async fn export_mailing_list(
+    state: State<'_, AppState>,
+    input: MailingExportInput,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<MailingExportResult, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if !can_view_client_pii(&role_val, hipaa_certified.unwrap_or(false)) {
+        return Err(
+            "Only admins or HIPAA-certified leads can export client addresses.".to_string(),
+        );
+    }
+    audit_db(&state.pool, "export_mailing_list", &role_val, &actor_val).await;
+
+    let format = input.format.trim().to_lowercase();
+    let template = match format.as_str() {
+        "csv" | "vcard" => None,
+        "labels_pdf" => {
+            let name = input.label_template.as_deref().unwrap_or("5160");
+            Some(
+                mailing::label_template(name)
+                    .ok_or_else(|| format!("Unsupported label template: {}", name))?,
+            )
+        }
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+
+    let mut query = String::from(
+        r#"
+        SELECT
+            id, name, first_name, last_name,
+            physical_address_line1, physical_address_line2, physical_address_city,
+            physical_address_state, physical_address_postal_code,
+            mailing_address_line1, mailing_address_line2, mailing_address_city,
+            mailing_address_state, mailing_address_postal_code
+        FROM clients
+        WHERE is_deleted = 0
+        "#,
+    );
+    if input.approval_status.is_some() {
+        query.push_str(" AND lower(approval_status) = lower(?)");
+    }
+    let season_bounds = match input.season.as_deref() {
+        Some(season) => Some(heating_season_bounds(season)?),
+        None => None,
+    };
+    if season_bounds.is_some() {
+        // Clients served (or with an order placed) during the season.
+        query.push_str(
+            r#"
+              AND EXISTS (
+                SELECT 1 FROM work_orders wo
+                WHERE wo.client_id = clients.id
+                  AND wo.is_deleted = 0
+                  AND date(COALESCE(wo.scheduled_date, wo.created_at)) >= date(?)
+                  AND date(COALESCE(wo.scheduled_date, wo.created_at)) < date(?)
+              )
+            "#,
+        );
+    }
+    query.push_str(" ORDER BY lower(COALESCE(last_name, name)), lower(name)");
+
+    let mut q = sqlx::query_as::<_, MailingClientRow>(&query);
+    if let Some(status) = &input.approval_status {
+        q = q.bind(status);
+    }
+    if let Some((start, end)) = &season_bounds {
+        q = q.bind(start).bind(end);
+    }
+    let rows = q.fetch_all(&state.pool).await.map_err(|e| e.to_string())?;
+
+    let selected: Option<HashSet<&String>> =
+        input.client_ids.as_ref().map(|ids| ids.iter().collect());
+    if selected.as_ref().is_some_and(|ids| ids.is_empty()) {
+        return Err("No clients selected for export.".to_string());
+    }
+
+    let addresses: Vec<mailing::MailingAddress> = rows
+        .into_iter()
+        .filter(|c| selected.as_ref().map_or(true, |ids| ids.contains(&c.id)))
+        .map(|c| {
+            let has_mailing = c
+                .mailing_address_line1
+                .as_deref()
+                .is_some_and(|l| !l.trim().is_empty());
+            if has_mailing {
+                mailing::MailingAddress {
+                    client_id: c.id,
+                    name: c.name,
+                    first_name: c.first_name,
+                    last_name: c.last_name,
+                    line1: c.mailing_address_line1.unwrap_or_default(),
+                    line2: c.mailing_address_line2,
+                    city: c.mailing_address_city.unwrap_or(c.physical_address_city),
+                    state: c.mailing_address_state.unwrap_or(c.physical_address_state),
+                    postal_code: c
+                        .mailing_address_postal_code
+                        .unwrap_or(c.physical_address_postal_code),
+                }
+            } else {
+                mailing::MailingAddress {
+                    client_id: c.id,
+                    name: c.name,
+                    first_name: c.first_name,
+                    last_name: c.last_name,
+                    line1: c.physical_address_line1,
+                    line2: c.physical_address_line2,
+                    city: c.physical_address_city,
+                    state: c.physical_address_state,
+                    postal_code: c.physical_address_postal_code,
+                }
+            }
+        })
+        .collect();
+
+    let stamp = chrono::Local::now().format("%Y%m%d").to_string();
+    let (content, extension, mime_type) = match &template {
+        Some(template) => (
+            mailing::to_label_pdf(&addresses, template),
+            "pdf",
+            "application/pdf",
+        ),
+        None if format == "vcard" => (mailing::to_vcard(&addresses), "vcf", "text/vcard"),
+        None => (mailing::to_csv(&addresses)?, "csv", "text/csv"),
+    };
+
+    let export_id = Uuid::new_v4().to_string();
+    let details = serde_json::json!({
+        "format": format,
+        "approval_status": input.approval_status,
+        "season": input.season,
+        "record_count": addresses.len(),
+        "client_ids": addresses.iter().map(|a| a.client_id.as_str()).collect::<Vec<_>>(),
+    });
+    audit_change(
+        &state.pool,
+        "export_mailing_list",
+        &role_val,
+        &actor_val,
+        "mailing_exports",
+        &export_id,
+        "export",
+        None,
+        Some(details.to_string()),
+    )
+    .await;
+
+    Ok(MailingExportResult {
+        file_name: format!("mailing-list-{}.{}", stamp, extension),
+        mime_type: mime_type.to_string(),
+        record_count: addresses.len(),
+        content,
+    })
+}
+
+#[tauri::command]
+async fn create_inventory_item(
+    state: State<'_, AppState>,
+    input: InventoryInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let id = Uuid::new_v4().to_string();
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only leads or admins can add inventory.".to_string());
+    }
+    audit_db(&state.pool, "create_inventory_item", &role_val, &actor_val).await;
+    let query = r#"
+        INSERT INTO inventory_items (
+            id, name, category, quantity_on_hand, unit,
+            reorder_threshold, reorder_amount, notes, created_by_user_id
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    "#;
+
+    sqlx::query(query)
+        .bind(&id)
+        .bind(&input.name)
+        .bind(&input.category)
+        .bind(input.quantity_on_hand)
+        .bind(&input.unit)
+        .bind(input.reorder_threshold)
+        .bind(input.reorder_amount)
+        .bind(&input.notes)
+        .bind(&input.created_by_user_id)
+        .execute(&state.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(id)
+}
+
+#[tauri::command]
+async fn list_inventory_items(state: State<'_, AppState>) -> Result<Vec<InventoryRow>, String> {
+    let rows = sqlx::query_as::<_, InventoryRow>(
+        r#"
+        SELECT
+            id,
+            name,
+            category,
+            quantity_on_hand,
+            unit,
+            reorder_threshold,
+            reorder_amount,
+            notes,
+            reserved_quantity
+        FROM inventory_items
+        WHERE is_deleted = 0
+        ORDER BY name ASC
+        "#,
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(&state.pool, "list_inventory_items", "unknown", "unknown").await;
+    Ok(rows)
+}
+
+#[tauri::command]
+async fn update_inventory_item(
+    state: State<'_, AppState>,
+    input: InventoryUpdateInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string());
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "update_inventory_item", &role_val, &actor_val).await;
+
+    let existing = sqlx::query!(
+        r#"
+        SELECT name, category, quantity_on_hand, unit, reorder_threshold, reorder_amount, notes
+        FROM inventory_items
+        WHERE id = ? AND is_deleted = 0
+        "#,
+        input.id
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        UPDATE inventory_items
+        SET name = ?,
+            category = ?,
+            quantity_on_hand = ?,
+            unit = ?,
+            reorder_threshold = ?,
+            reorder_amount = ?,
+            notes = ?,
+            updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&input.name)
+    .bind(&input.category)
+    .bind(input.quantity_on_hand)
+    .bind(&input.unit)
+    .bind(input.reorder_threshold)
+    .bind(input.reorder_amount)
+    .bind(&input.notes)
+    .bind(&input.id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Some(prev) = existing {
+        let log_field = |field: &str, old_val: Option<String>, new_val: Option<String>| {
+            if old_val != new_val {
+                let pool = state.pool.clone();
+                let role = role_val.clone();
+                let actor = actor_val.clone();
+                let entity_id = input.id.clone();
+                let field = field.to_string();
+                tauri::async_runtime::spawn(async move {
+                    audit_change(
+                        &pool,
+                        "update_inventory_item",
+                        &role,
+                        &actor,
+                        "inventory_items",
+                        &entity_id,
+                        &field,
+                        old_val,
+                        new_val,
+                    )
+                    .await;
+                });
+            }
+        };
+        log_field("name", Some(prev.name), Some(input.name.clone()));
+        log_field("category", prev.category, input.category.clone());
+        log_field(
+            "quantity_on_hand",
+            Some(prev.quantity_on_hand.to_string()),
+            Some(input.quantity_on_hand.to_string()),
+        );
+        log_field("unit", Some(prev.unit), Some(input.unit.clone()));
+        log_field(
+            "reorder_threshold",
+            Some(prev.reorder_threshold.to_string()),
+            Some(input.reorder_threshold.to_string()),
+        );
+        log_field(
+            "reorder_amount",
+            prev.reorder_amount.map(|v| v.to_string()),
+            input.reorder_amount.map(|v| v.to_string()),
+        );
+        log_field("notes", prev.notes, input.notes.clone());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn delete_inventory_item(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    audit_db(&state.pool, "delete_inventory_item", "unknown", "unknown").await;
+    sqlx::query(
+        r#"
+        UPDATE inventory_items
+        SET is_deleted = 1,
+            updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn create_work_order(
+    state: State<'_, AppState>,
+    input: WorkOrderInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<WorkOrderSaveResult, String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    insert_work_order(
+        &state.pool,
+        input,
+        &role_val,
+        &actor_val,
+        "create_work_order",
+    )
+    .await
+}
+
+#[derive(Debug, Serialize)]
+struct WorkOrderSaveResult {
+    id: String,
+    // Set when a warn-mode allotment rule was exceeded; the order is saved regardless.
+    allotment_warning: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WorkOrderStatusResult {
+    allotment_warning: Option<String>,
+}
+
+async fn insert_work_order(
+    pool: &SqlitePool,
+    input: WorkOrderInput,
+    role_val: &str,
+    actor_val: &str,
+    event: &str,
+) -> Result<WorkOrderSaveResult, String> {
+    if !can_create_work_orders(role_val) {
+        return Err("Only staff, leads or admins may create work orders".to_string());
+    }
+    store_work_order(pool, input, role_val, actor_val, event).await
+}
+
+/// `insert_work_order` without the role check, for orders the system creates on its own.
+async fn store_work_order(
+    pool: &SqlitePool,
+    input: WorkOrderInput,
+    role_val: &str,
+    actor_val: &str,
+    event: &str,
+) -> Result<WorkOrderSaveResult, String> {
+    let id = Uuid::new_v4().to_string();
+    let status = WorkOrderStatus::parse(input.status.as_deref().unwrap_or("draft"))?
+        .as_str()
+        .to_string();
+    audit_db(pool, event, role_val, actor_val).await;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let inventory_cords = if status.eq_ignore_ascii_case("picked_up") {
+        input.pickup_quantity_cords.unwrap_or(0.0)
+    } else {
+        input.delivery_size_cords.unwrap_or(0.0)
+    };
+    let mut allotment_note = None;
+    if counts_toward_allotment(&status) {
+        let check = check_allotment(
+            &mut tx,
+            &input.client_id,
+            None,
+            input.scheduled_date.as_deref(),
+            inventory_cords,
+        )
+        .await?;
+        allotment_note =
+            resolve_allotment(&check, role_val, input.allotment_override_reason.as_deref())?
+                .map(|(field, value)| (field, check.message.clone(), value));
+    }
+
+    let query = r#"
+        INSERT INTO work_orders (
+            id, client_id, client_title, client_name,
+            physical_address_line1, physical_address_line2, physical_address_city,
+            physical_address_state, physical_address_postal_code,
+            mailing_address_line1, mailing_address_line2, mailing_address_city,
+            mailing_address_state, mailing_address_postal_code,
+            telephone, email, directions, gate_combo, mileage, work_hours,
+            other_heat_source_gas, other_heat_source_electric, other_heat_source_other,
+            notes, scheduled_date, status,
+            wood_size_label, wood_size_other,
+            delivery_size_label, delivery_size_cords,
+            pickup_delivery_type,
+            pickup_quantity_cords, pickup_length, pickup_width, pickup_height, pickup_units,
+            assignees_json,
+            created_by_user_id, created_by_display,
+            paired_order_id, backorder_of_id, standing_order_id
+        )
+        VALUES (
+            ?, ?, ?, ?,
+            ?, ?, ?,
+            ?, ?,
+            ?, ?, ?,
+            ?, ?,
+            ?, ?, ?, ?, ?, ?,
+            ?, ?, ?,
+            ?, ?, ?,
+            ?, ?,
+            ?, ?,
+            ?,
+            ?, ?, ?, ?, ?,
+            ?,
+            ?, ?,
+            ?, ?, ?
+        )
+    "#;
+
+    let assignees_store = input
+        .assignees_json
+        .clone()
+        .unwrap_or_else(|| "[]".to_string());
+
+    sqlx::query(query)
+        .bind(&id)
+        .bind(&input.client_id)
+        .bind(&input.client_title)
+        .bind(&input.client_name)
+        .bind(&input.physical_address_line1)
+        .bind(&input.physical_address_line2)
+        .bind(&input.physical_address_city)
+        .bind(&input.physical_address_state)
+        .bind(&input.physical_address_postal_code)
+        .bind(&input.mailing_address_line1)
+        .bind(&input.mailing_address_line2)
+        .bind(&input.mailing_address_city)
+        .bind(&input.mailing_address_state)
+        .bind(&input.mailing_address_postal_code)
+        .bind(&input.telephone)
+        .bind(&input.email)
+        .bind(&input.directions)
+        .bind(&input.gate_combo)
+        .bind(&input.mileage)
+        .bind(&input.work_hours)
+        .bind(input.other_heat_source_gas)
+        .bind(input.other_heat_source_electric)
+        .bind(&input.other_heat_source_other)
+        .bind(&input.notes)
+        .bind(&input.scheduled_date)
+        .bind(&status)
+        .bind(&input.wood_size_label)
+        .bind(&input.wood_size_other)
+        .bind(&input.delivery_size_label)
+        .bind(&input.delivery_size_cords)
+        .bind(&input.pickup_delivery_type)
+        .bind(&input.pickup_quantity_cords)
+        .bind(&input.pickup_length)
+        .bind(&input.pickup_width)
+        .bind(&input.pickup_height)
+        .bind(&input.pickup_units)
+        .bind(&assignees_store)
+        .bind(&input.created_by_user_id)
+        .bind(&input.created_by_display)
+        .bind(&input.paired_order_id)
+        .bind(&input.backorder_of_id)
+        .bind(&input.standing_order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    let assignee_names = parse_assignee_names(Some(&assignees_store))?;
+    sync_assignments_from_names(&mut tx, &id, &assignee_names, actor_val).await?;
+
+    adjust_inventory_for_transition_tx(&mut tx, "draft", &status, inventory_cords)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let assigned_json = assignees_store.clone();
+
+    // Auto-create a delivery event when a work order is scheduled.
+    if let Some(start_date) = &input.scheduled_date {
+        let delivery_id = Uuid::new_v4().to_string();
+        let delivery_query = r#"
+            INSERT INTO delivery_events (
+                id, title, description, event_type, work_order_id,
+                start_date, end_date, color_code, assigned_user_ids_json
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#;
+
+        sqlx::query(delivery_query)
+            .bind(&delivery_id)
+            .bind(format!("Delivery for {}", input.client_name))
+            .bind::<Option<String>>(None)
+            .bind("delivery")
+            .bind(&id)
+            .bind(start_date)
+            .bind::<Option<String>>(None)
+            .bind("#e67f1e")
+            .bind(&assigned_json)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    record_status_change(&mut tx, &id, None, &status, role_val, actor_val, None).await?;
+
+    let mut pair_audits = PendingAudits::new();
+    if let Some(paired_id) = input.paired_order_id.as_deref().filter(|p| !p.is_empty()) {
+        link_pair_tx(
+            &mut tx,
+            &id,
+            paired_id,
+            role_val,
+            actor_val,
+            &mut pair_audits,
+        )
+        .await?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    flush_audits(pool, event, role_val, actor_val, pair_audits).await;
+
+    let allotment_warning = allotment_warning(&allotment_note);
+    if let Some((field, message, value)) = allotment_note {
+        audit_change(
+            pool,
+            event,
+            role_val,
+            actor_val,
+            "work_orders",
+            &id,
+            field,
+            message,
+            Some(value),
+        )
+        .await;
+    }
+
+    Ok(WorkOrderSaveResult {
+        id,
+        allotment_warning,
+    })
+}
+
+/// Order-specific fields only; everything copied from the client is read from `clients` on the
+/// server so the form cannot send stale or altered details.
+#[derive(Debug, Deserialize)]
+struct ClientWorkOrderInput {
+    client_id: String,
+    scheduled_date: Option<String>,
+    status: Option<String>,
+    work_hours: Option<f64>,
+    other_heat_source_gas: Option<bool>,
+    other_heat_source_electric: Option<bool>,
+    other_heat_source_other: Option<String>,
+    notes: Option<String>,
+    // Overrides the client's usual wood size for this order only.
+    wood_size_label: Option<String>,
+    wood_size_other: Option<String>,
+    delivery_size_label: Option<String>,
+    delivery_size_cords: Option<f64>,
+    pickup_delivery_type: Option<String>,
+    pickup_quantity_cords: Option<f64>,
+    pickup_length: Option<f64>,
+    pickup_width: Option<f64>,
+    pickup_height: Option<f64>,
+    pickup_units: Option<String>,
+    assignees_json: Option<String>,
+    created_by_user_id: Option<String>,
+    created_by_display: Option<String>,
+    paired_order_id: Option<String>,
+    allotment_override_reason: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct ClientOrderSourceRow {
+    approval_status: String,
+    anonymized_at: Option<String>,
+    wood_size_label: Option<String>,
+    wood_size_other: Option<String>,
+    default_mileage: Option<f64>,
+    #[sqlx(flatten)]
+    copied: CopiedClientFields,
+}
+
+/// Rejects deleted, anonymized and unapproved clients.
+async fn build_client_work_order(
+    pool: &SqlitePool,
+    input: ClientWorkOrderInput,
+) -> Result<WorkOrderInput, String> {
+    let client = sqlx::query_as::<_, ClientOrderSourceRow>(
+        r#"
+        SELECT
+            approval_status, anonymized_at, wood_size_label, wood_size_other, default_mileage,
+            client_title, name AS client_name,
+            physical_address_line1, physical_address_line2, physical_address_city,
+            physical_address_state, physical_address_postal_code,
+            mailing_address_line1, mailing_address_line2, mailing_address_city,
+            mailing_address_state, mailing_address_postal_code,
+            telephone, email, gate_combo, directions
+        FROM clients
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(&input.client_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Client not found".to_string())?;
+    if client.anonymized_at.is_some() {
+        return Err("Client has been anonymized".to_string());
+    }
+    if !client.approval_status.eq_ignore_ascii_case("approved") {
+        return Err(format!(
+            "Client is {}; only approved clients can receive work orders",
+            client.approval_status
+        ));
+    }
+
+    let copied = client.copied;
+    let (wood_size_label, wood_size_other) = if input.wood_size_label.is_some() {
+        (input.wood_size_label, input.wood_size_other)
+    } else {
+        (client.wood_size_label, client.wood_size_other)
+    };
+    Ok(WorkOrderInput {
+        client_id: input.client_id,
+        client_title: copied.client_title,
+        client_name: copied.client_name.unwrap_or_default(),
+        physical_address_line1: copied.physical_address_line1.unwrap_or_default(),
+        physical_address_line2: copied.physical_address_line2,
+        physical_address_city: copied.physical_address_city.unwrap_or_default(),
+        physical_address_state: copied.physical_address_state.unwrap_or_default(),
+        physical_address_postal_code: copied.physical_address_postal_code.unwrap_or_default(),
+        mailing_address_line1: copied.mailing_address_line1,
+        mailing_address_line2: copied.mailing_address_line2,
+        mailing_address_city: copied.mailing_address_city,
+        mailing_address_state: copied.mailing_address_state,
+        mailing_address_postal_code: copied.mailing_address_postal_code,
+        telephone: copied.telephone,
+        email: copied.email,
+        directions: copied.directions,
+        gate_combo: copied.gate_combo,
+        mileage: client.default_mileage,
+        work_hours: input.work_hours,
+        other_heat_source_gas: input.other_heat_source_gas.unwrap_or(false),
+        other_heat_source_electric: input.other_heat_source_electric.unwrap_or(false),
+        other_heat_source_other: input.other_heat_source_other,
+        notes: input.notes,
+        scheduled_date: input.scheduled_date,
+        status: input.status,
+        wood_size_label,
+        wood_size_other,
+        delivery_size_label: input.delivery_size_label,
+        delivery_size_cords: input.delivery_size_cords,
+        pickup_delivery_type: input.pickup_delivery_type,
+        pickup_quantity_cords: input.pickup_quantity_cords,
+        pickup_length: input.pickup_length,
+        pickup_width: input.pickup_width,
+        pickup_height: input.pickup_height,
+        pickup_units: input.pickup_units,
+        assignees_json: input.assignees_json,
+        created_by_user_id: input.created_by_user_id,
+        created_by_display: input.created_by_display,
+        paired_order_id: input.paired_order_id,
+        allotment_override_reason: input.allotment_override_reason,
+        backorder_of_id: None,
+        standing_order_id: None,
+    })
+}
+
+#[tauri::command]
+async fn create_work_order_for_client(
+    state: State<'_, AppState>,
+    input: ClientWorkOrderInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<WorkOrderSaveResult, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    // Check the role before looking the client up, so callers cannot probe client records.
+    if !can_create_work_orders(&role_val) {
+        return Err("Only staff, leads or admins may create work orders".to_string());
+    }
+    let order = build_client_work_order(&state.pool, input).await?;
+    insert_work_order(
+        &state.pool,
+        order,
+        &role_val,
+        &actor_val,
+        "create_work_order_for_client",
+    )
+    .await
+}
+
+async fn load_work_order_rows(
+    pool: &SqlitePool,
+    scope: &ListScope,
+) -> Result<Vec<WorkOrderRow>, String> {
+    sqlx::query_as::<_, WorkOrderRow>(
+        r#"
+        SELECT
+            w.id,
+            w.client_name,
+            w.status,
+            w.scheduled_date,
+            CASE WHEN ?2 THEN NULL ELSE w.gate_combo END AS gate_combo,
+            CASE WHEN ?3 THEN NULL ELSE w.notes END AS notes,
+            CASE WHEN ?1 THEN NULL ELSE w.telephone END AS telephone,
+            CASE WHEN ?4 THEN NULL ELSE w.physical_address_line1 END AS physical_address_line1,
+            CASE WHEN ?4 THEN NULL ELSE w.physical_address_city END AS physical_address_city,
+            CASE WHEN ?4 THEN NULL ELSE w.physical_address_state END AS physical_address_state,
+            CASE WHEN ?4 THEN NULL ELSE w.physical_address_postal_code END
+                AS physical_address_postal_code,
+            w.mileage,
+            w.work_hours,
+            w.wood_size_label,
+            w.wood_size_other,
+            w.delivery_size_label,
+            w.delivery_size_cords,
+            w.pickup_delivery_type,
+            w.pickup_quantity_cords,
+            w.pickup_length,
+            w.pickup_width,
+            w.pickup_height,
+            w.pickup_units,
+            COALESCE(w.assignees_json, '[]') as assignees_json,
+            w.created_by_display,
+            w.created_at,
+            w.paired_order_id,
+            w.client_id,
+            w.delivered_cords,
+            w.backorder_of_id,
+            CASE WHEN ?5 THEN NULL ELSE c.site_hazards END AS site_hazards,
+            CASE WHEN ?5 THEN NULL ELSE c.site_drop_location END AS site_drop_location,
+            CASE WHEN ?5 THEN NULL ELSE c.site_vehicle_access END AS site_vehicle_access,
+            CASE WHEN ?5 THEN NULL ELSE c.site_preferred_contact_time END
+                AS site_preferred_contact_time
+        FROM work_orders w
+        LEFT JOIN clients c ON c.id = w.client_id
+        WHERE w.is_deleted = 0
+          AND (
+            ?6 IS NULL
+            OR w.id IN (
+                SELECT a.work_order_id
+                FROM auth_users au
+                JOIN work_order_assignments a ON a.user_id = au.user_id
+                WHERE au.is_deleted = 0 AND lower(au.username) = lower(?6)
+            )
+          )
+        ORDER BY (w.scheduled_date IS NULL), datetime(w.scheduled_date) DESC, w.created_at DESC
+        "#,
+    )
+    .bind(scope.hide_contact)
+    .bind(scope.hide_gate)
+    .bind(scope.hide_notes)
+    .bind(scope.hide_address)
+    .bind(scope.hide_site_notes)
+    .bind(&scope.assigned_to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_work_orders(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    username: Option<String>,
+    hipaa_certified: Option<bool>,
+    is_driver: Option<bool>,
+) -> Result<Vec<WorkOrderRow>, String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let username_val = username.unwrap_or_default();
+    let scope = ListScope::for_work_orders(
+        &role_val,
+        &username_val,
+        hipaa_certified.unwrap_or(false),
+        is_driver.unwrap_or(false),
+    );
+    audit_db(&state.pool, "list_work_orders", &role_val, &username_val).await;
+    load_work_order_rows(&state.pool, &scope).await
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ScheduleReconcileReport {
+    scheduled: Vec<String>,
+    events_created: Vec<String>,
+    skipped: Vec<ReconcileSkip>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReconcileSkip {
+    work_order_id: String,
+    reason: String,
+}
+
+/// Brings dated orders up to `scheduled` and gives every dated order a delivery event.
+/// Each order is repaired in its own transaction so one bad row (e.g. no wood on hand to
+/// reserve) is reported and skipped instead of blocking the rest.
+async fn run_schedule_reconciliation(
+    pool: &SqlitePool,
+    role_val: &str,
+    actor_val: &str,
+) -> Result<ScheduleReconcileReport, String> {
+    #[derive(sqlx::FromRow)]
+    struct UnscheduledRow {
+        id: String,
+        status: String,
+        delivery_size_cords: Option<f64>,
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct MissingDeliveryRow {
+        id: String,
+        client_name: String,
+        scheduled_date: String,
+        assignees_json: Option<String>,
+    }
+
+    let mut report = ScheduleReconcileReport::default();
+    let scheduled = WorkOrderStatus::Scheduled.as_str();
+
+    let unscheduled = sqlx::query_as::<_, UnscheduledRow>(
+        r#"
+        SELECT id, status, delivery_size_cords
+        FROM work_orders
+        WHERE is_deleted = 0
+          AND scheduled_date IS NOT NULL
+          AND lower(status) IN ('received', 'pending', 'rescheduled', 'draft')
+        ORDER BY scheduled_date, id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for order in unscheduled {
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+        sqlx::query(
+            r#"
+            UPDATE work_orders
+            SET status = ?, updated_at = datetime('now'), version = version + 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(scheduled)
+        .bind(&order.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        let cords = order.delivery_size_cords.unwrap_or(0.0);
+        if let Err(reason) =
+            adjust_inventory_for_transition_tx(&mut tx, &order.status, scheduled, cords).await
+        {
+            tx.rollback().await.map_err(|e| e.to_string())?;
+            report.skipped.push(ReconcileSkip {
+                work_order_id: order.id,
+                reason,
+            });
+            continue;
+        }
+        record_status_change(
+            &mut tx,
+            &order.id,
+            Some(&order.status),
+            scheduled,
+            role_val,
+            actor_val,
+            Some("Scheduled date set"),
+        )
+        .await?;
+        tx.commit().await.map_err(|e| e.to_string())?;
+        audit_change(
+            pool,
+            "reconcile_schedule",
+            role_val,
+            actor_val,
+            "work_orders",
+            &order.id,
+            "status",
+            Some(order.status),
+            Some(scheduled.to_string()),
+        )
+        .await;
+        report.scheduled.push(order.id);
+    }
+
+    // Cancelled orders had their events removed on purpose; leave them alone.
+    let missing_deliveries = sqlx::query_as::<_, MissingDeliveryRow>(
+        r#"
+        SELECT
+            id,
+            client_name,
+            scheduled_date,
+            COALESCE(assignees_json, '[]') as assignees_json
+        FROM work_orders
+        WHERE is_deleted = 0
+          AND scheduled_date IS NOT NULL
+          AND lower(status) <> 'cancelled'
+          AND NOT EXISTS (
+              SELECT 1
+              FROM delivery_events
+              WHERE work_order_id = work_orders.id
+                AND is_deleted = 0
+          )
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for missing in missing_deliveries {
+        let delivery_id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO delivery_events (
+                id, title, description, event_type, work_order_id,
+                start_date, end_date, color_code, assigned_user_ids_json
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&delivery_id)
+        .bind(format!("Delivery for {}", missing.client_name))
+        .bind::<Option<String>>(None)
+        .bind("delivery")
+        .bind(&missing.id)
+        .bind(&missing.scheduled_date)
+        .bind::<Option<String>>(None)
+        .bind("#e67f1e")
+        .bind(&missing.assignees_json)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        audit_change(
+            pool,
+            "reconcile_schedule",
+            role_val,
+            actor_val,
+            "delivery_events",
+            &delivery_id,
+            "work_order_id",
+            None,
+            Some(missing.id.clone()),
+        )
+        .await;
+        report.events_created.push(delivery_id);
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+async fn reconcile_schedule(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<ScheduleReconcileReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "reconcile_schedule", &role_val, &actor_val).await;
+    if !(role_val == "admin" || role_val == "lead" || is_staff_like(&role_val)) {
+        return Err("Only staff, leads or admins can reconcile the schedule".to_string());
+    }
+    run_schedule_reconciliation(&state.pool, &role_val, &actor_val).await
+}
+
+#[tauri::command]
+async fn list_scheduled_jobs(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<jobs::ScheduledJob>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_scheduled_jobs", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can view scheduled jobs".to_string());
+    }
+    jobs::list_jobs(&state.pool).await
+}
+
+#[tauri::command]
+async fn list_job_runs(
+    state: State<'_, AppState>,
+    job_key: Option<String>,
+    limit: Option<i64>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<jobs::JobRun>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_job_runs", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can view job history".to_string());
+    }
+    let limit = limit.unwrap_or(100).clamp(1, 1000);
+    jobs::list_runs(&state.pool, job_key.as_deref(), limit).await
+}
+
+#[tauri::command]
+async fn run_scheduled_job(
+    state: State<'_, AppState>,
+    job_key: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<jobs::JobRun, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "run_scheduled_job", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can run scheduled jobs".to_string());
+    }
+    jobs::run_job(
+        &state.pool,
+        &state.data_dir,
+        &job_key,
+        &role_val,
+        &actor_val,
+    )
+    .await
+}
+
+#[tauri::command]
+async fn update_scheduled_job(
+    state: State<'_, AppState>,
+    input: jobs::ScheduledJobInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "update_scheduled_job", &role_val, &actor_val).await;
+    if role_val != "admin" {
+        return Err("Only admins can configure scheduled jobs".to_string());
+    }
+    let Some(previous) = jobs::update_job(&state.pool, &input, &actor_val).await? else {
+        audit_change(
+            &state.pool,
+            "update_scheduled_job",
+            &role_val,
+            &actor_val,
+            "scheduled_jobs",
+            &input.job_key,
+            "handler",
+            None,
+            input.handler.clone(),
+        )
+        .await;
+        return Ok(());
+    };
+    let changes = [
+        (
+            "enabled",
+            previous.enabled.to_string(),
+            input.enabled.to_string(),
+        ),
+        (
+            "interval_minutes",
+            previous.interval_minutes.to_string(),
+            input.interval_minutes.to_string(),
+        ),
+        (
+            "run_at_startup",
+            previous.run_at_startup.to_string(),
+            input
+                .run_at_startup
+                .unwrap_or(previous.run_at_startup)
+                .to_string(),
+        ),
+        (
+            "settings_json",
+            previous.settings_json.clone(),
+            input
+                .settings
+                .as_ref()
+                .map(|settings| settings.to_string())
+                .unwrap_or(previous.settings_json),
+        ),
+    ];
+    for (field, old, new) in changes {
+        if old != new {
+            audit_change(
+                &state.pool,
+                "update_scheduled_job",
+                &role_val,
+                &actor_val,
+                "scheduled_jobs",
+                &input.job_key,
+                field,
+                Some(old),
+                Some(new),
+            )
+            .await;
+        }
+    }
+    Ok(())
+}
+
+async fn record_status_change(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+    from_status: Option<&str>,
+    to_status: &str,
+    role_val: &str,
+    actor_val: &str,
+    reason: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        INSERT INTO work_order_status_history (
+            id, work_order_id, from_status, to_status, changed_at, changed_by, role, reason
+        )
+        VALUES (?, ?, ?, ?, datetime('now'), ?, ?, ?)
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(work_order_id)
+    .bind(from_status)
+    .bind(to_status)
+    .bind(actor_val)
+    .bind(role_val)
+    .bind(reason.map(str::trim).filter(|r| !r.is_empty()))
+    .execute(conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn adjust_inventory_for_transition_tx(
+    tx: &mut Transaction<'_, Sqlite>,
+    previous_status: &str,
+    next_status: &str,
+    delivery_size_cords: f64,
+) -> Result<(), String> {
+    adjust_inventory_tx(
+        tx,
+        previous_status,
+        next_status,
+        delivery_size_cords,
+        delivery_size_cords,
+    )
+    .await
+}
+
+/// Like `adjust_inventory_for_transition_tx`, but a completion takes `consumed_cords` off hand
+/// while the reservation released is still the ordered `reserved_cords` (short deliveries).
+async fn adjust_inventory_tx(
+    tx: &mut Transaction<'_, Sqlite>,
+    previous_status: &str,
+    next_status: &str,
+    reserved_cords: f64,
+    consumed_cords: f64,
+) -> Result<(), String> {
+    if reserved_cords <= 0.0 && consumed_cords <= 0.0 {
+        return Ok(());
+    }
+
+    // Delivered orders keep their reservation until completion takes the wood off hand.
+    let reserve_states = ["scheduled", "in_progress", "delivered"];
+    let prev_status_lower = previous_status.to_lowercase();
+    let next_status_lower = next_status.to_lowercase();
+    let prev_reserved = reserve_states.contains(&prev_status_lower.as_str());
+    let next_reserved = reserve_states.contains(&next_status_lower.as_str());
+
+    #[derive(sqlx::FromRow)]
+    struct InventoryRecord {
+        id: String,
+        quantity_on_hand: f64,
+        reserved_quantity: f64,
+    }
+
+    let inventory_row = sqlx::query_as::<_, InventoryRecord>(
+        r#"
+        SELECT id, quantity_on_hand, reserved_quantity
+        FROM inventory_items
+        WHERE is_deleted = 0
+          AND (
+            lower(unit) LIKE '%cord%'
+            OR lower(name) LIKE '%wood%'
+          )
+        ORDER BY created_at ASC
+        LIMIT 1
+        "#,
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // If no wood inventory item found, treat as an error to avoid silent no-op transitions
+    let record = match inventory_row {
+        Some(r) => r,
+        None => {
+            return Err(
+                "No wood inventory item found to adjust (add a wood inventory item).".to_string(),
+            )
+        }
+    };
+
+    let mut reserved = record.reserved_quantity;
+    let mut on_hand = record.quantity_on_hand;
+
+    // If moving into a reserved state, ensure availability before reserving
+    if !prev_reserved && next_reserved {
+        let available = on_hand - reserved;
+        if available < reserved_cords {
+            return Err(format!(
+                "Insufficient inventory to reserve {} cords (available: {}).",
+                reserved_cords, available
+            ));
+        }
+        reserved += reserved_cords;
+    } else if prev_reserved && !next_reserved {
+        reserved -= reserved_cords;
+    }
+
+    if next_status.eq_ignore_ascii_case("completed")
+        || next_status.eq_ignore_ascii_case("picked_up")
+    {
+        on_hand -= consumed_cords;
+    }
+
+    if reserved < 0.0 {
+        reserved = 0.0;
+    }
+    if on_hand < 0.0 {
+        on_hand = 0.0;
+    }
+
+    // Ensure reserved never exceeds on-hand after adjustments (concurrency safety)
+    if reserved > on_hand {
+        reserved = on_hand;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE inventory_items
+        SET reserved_quantity = ?, quantity_on_hand = ?, updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(reserved)
+    .bind(on_hand)
+    .bind(&record.id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn create_delivery_event(
+    state: State<'_, AppState>,
+    input: DeliveryEventInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let id = Uuid::new_v4().to_string();
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && !is_staff_like(&role_val) {
+        return Err("Only staff or admins can create schedule events.".to_string());
+    }
+    audit_db(&state.pool, "create_delivery_event", &role_val, &actor_val).await;
+    insert_delivery_event(&state.pool, &id, &input, &actor_val).await?;
+    Ok(id)
+}
+
+async fn insert_delivery_event(
+    pool: &SqlitePool,
+    id: &str,
+    input: &DeliveryEventInput,
+    actor_val: &str,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let query = r#"
+        INSERT INTO delivery_events (
+            id, title, description, event_type, work_order_id,
+            start_date, end_date, color_code, assigned_user_ids_json
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    "#;
+
+    sqlx::query(query)
+        .bind(id)
+        .bind(&input.title)
+        .bind(&input.description)
+        .bind(&input.event_type)
+        .bind(&input.work_order_id)
+        .bind(&input.start_date)
+        .bind(&input.end_date)
+        .bind(&input.color_code)
+        .bind(&input.assigned_user_ids_json)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // Events for an order take their crew from the order's assignments.
+    if input.work_order_id.is_none() {
+        let names = parse_assignee_names(input.assigned_user_ids_json.as_deref())?;
+        let user_ids = resolve_assignee_ids(&mut tx, &names).await?;
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO delivery_event_assignments (
+                id, delivery_event_id, user_id, created_by
+            )
+            SELECT lower(hex(randomblob(16))), ?1, value, ?3
+            FROM json_each(?2)
+            "#,
+        )
+        .bind(id)
+        .bind(serde_json::to_string(&user_ids).map_err(|e| e.to_string())?)
+        .bind(actor_val)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_users(state: State<'_, AppState>) -> Result<Vec<UserRow>, String> {
+    let rows = sqlx::query_as::<_, UserRow>(
+        r#"
+        SELECT
+            id,
+            name,
+            email,
+            telephone,
+            physical_address_line1,
+            physical_address_line2,
+            physical_address_city,
+            physical_address_state,
+            physical_address_postal_code,
+            mailing_address_line1,
+            mailing_address_line2,
+            mailing_address_city,
+            mailing_address_state,
+            mailing_address_postal_code,
+            role,
+            availability_notes,
+            availability_schedule,
+            driver_license_status,
+            driver_license_number,
+            driver_license_expires_on,
+            vehicle,
+            COALESCE(hipaa_certified, 0) as hipaa_certified,
+            COALESCE(is_driver, 0) as is_driver
+        FROM users
+        WHERE is_deleted = 0
+        ORDER BY name ASC
+        "#,
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(&state.pool, "list_users", "unknown", "unknown").await;
+    Ok(rows)
+}
+
+#[derive(Debug, Deserialize)]
+struct EnsureUserInput {
+    name: String,
+    email: Option<String>,
+    telephone: Option<String>,
+    physical_address_line1: Option<String>,
+    physical_address_line2: Option<String>,
+    physical_address_city: Option<String>,
+    physical_address_state: Option<String>,
+    physical_address_postal_code: Option<String>,
+    mailing_address_line1: Option<String>,
+    mailing_address_line2: Option<String>,
+    mailing_address_city: Option<String>,
+    mailing_address_state: Option<String>,
+    mailing_address_postal_code: Option<String>,
+    role: String,
+    is_driver: Option<bool>,
+    hipaa_certified: Option<bool>,
+}
+
+#[tauri::command]
+async fn ensure_user_exists(state: State<'_, AppState>, input: EnsureUserInput) -> Result<String, String> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Name is required".to_string());
+    }
+    let role = input.role.trim().to_lowercase();
+    let existing = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            role,
+            email,
+            telephone,
+            physical_address_line1,
+            physical_address_line2,
+            physical_address_city,
+            physical_address_state,
+            physical_address_postal_code,
+            mailing_address_line1,
+            mailing_address_line2,
+            mailing_address_city,
+            mailing_address_state,
+            mailing_address_postal_code,
+            COALESCE(is_driver, 0) as "is_driver!: i64",
+            COALESCE(hipaa_certified, 0) as "hipaa_certified!: i64"
+        FROM users
+        WHERE lower(name) = lower(?)
+          AND is_deleted = 0
+        LIMIT 1
+        "#,
+        name
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let wants_driver = input.is_driver.unwrap_or(false);
+    let wants_hipaa = input.hipaa_certified.unwrap_or(false);
+
+    if let Some(row) = existing {
+        let mut updated = false;
+        let update_role = if role_rank(&role) > role_rank(&row.role) {
+            updated = true;
+            role.clone()
+        } else {
+            row.role.clone()
+        };
+        let update_email = if row.email.is_none() && input.email.is_some() {
+            updated = true;
+            input.email.clone()
+        } else {
+            row.email.clone()
+        };
+        let update_phone = if row.telephone.is_none() && input.telephone.is_some() {
+            updated = true;
+            input.telephone.clone()
+        } else {
+            row.telephone.clone()
+        };
+        let update_phys_line1 = if row.physical_address_line1.is_none() && input.physical_address_line1.is_some() {
+            updated = true;
+            input.physical_address_line1.clone()
+        } else {
+            row.physical_address_line1.clone()
+        };
+        let update_phys_line2 = if row.physical_address_line2.is_none() && input.physical_address_line2.is_some() {
+            updated = true;
+            input.physical_address_line2.clone()
+        } else {
+            row.physical_address_line2.clone()
+        };
+        let update_phys_city = if row.physical_address_city.is_none() && input.physical_address_city.is_some() {
+            updated = true;
+            input.physical_address_city.clone()
+        } else {
+            row.physical_address_city.clone()
+        };
+        let update_phys_state = if row.physical_address_state.is_none() && input.physical_address_state.is_some() {
+            updated = true;
+            input.physical_address_state.clone()
+        } else {
+            row.physical_address_state.clone()
+        };
+        let update_phys_postal = if row.physical_address_postal_code.is_none() && input.physical_address_postal_code.is_some() {
+            updated = true;
+            input.physical_address_postal_code.clone()
+        } else {
+            row.physical_address_postal_code.clone()
+        };
+        let update_mail_line1 = if row.mailing_address_line1.is_none() && input.mailing_address_line1.is_some() {
+            updated = true;
+            input.mailing_address_line1.clone()
+        } else {
+            row.mailing_address_line1.clone()
+        };
+        let update_mail_line2 = if row.mailing_address_line2.is_none() && input.mailing_address_line2.is_some() {
+            updated = true;
+            input.mailing_address_line2.clone()
+        } else {
+            row.mailing_address_line2.clone()
+        };
+        let update_mail_city = if row.mailing_address_city.is_none() && input.mailing_address_city.is_some() {
+            updated = true;
+            input.mailing_address_city.clone()
+        } else {
+            row.mailing_address_city.clone()
+        };
+        let update_mail_state = if row.mailing_address_state.is_none() && input.mailing_address_state.is_some() {
+            updated = true;
+            input.mailing_address_state.clone()
+        } else {
+            row.mailing_address_state.clone()
+        };
+        let update_mail_postal = if row.mailing_address_postal_code.is_none() && input.mailing_address_postal_code.is_some() {
+            updated = true;
+            input.mailing_address_postal_code.clone()
+        } else {
+            row.mailing_address_postal_code.clone()
+        };
+        let update_driver = if wants_driver && row.is_driver == 0 {
+            updated = true;
+            1
+        } else {
+            row.is_driver
+        };
+        let update_hipaa = if wants_hipaa && row.hipaa_certified == 0 {
+            updated = true;
+            1
+        } else {
+            row.hipaa_certified
+        };
+        if updated {
+            sqlx::query(
+                r#"
+                UPDATE users
+                SET role = ?,
+                    email = ?,
+                    telephone = ?,
+                    physical_address_line1 = ?,
+                    physical_address_line2 = ?,
+                    physical_address_city = ?,
+                    physical_address_state = ?,
+                    physical_address_postal_code = ?,
+                    mailing_address_line1 = ?,
+                    mailing_address_line2 = ?,
+                    mailing_address_city = ?,
+                    mailing_address_state = ?,
+                    mailing_address_postal_code = ?,
+                    is_driver = ?,
+                    hipaa_certified = ?,
+                    updated_at = datetime('now')
+                WHERE id = ?
+                "#,
+            )
+            .bind(&update_role)
+            .bind(&update_email)
+            .bind(&update_phone)
+            .bind(&update_phys_line1)
+            .bind(&update_phys_line2)
+            .bind(&update_phys_city)
+            .bind(&update_phys_state)
+            .bind(&update_phys_postal)
+            .bind(&update_mail_line1)
+            .bind(&update_mail_line2)
+            .bind(&update_mail_city)
+            .bind(&update_mail_state)
+            .bind(&update_mail_postal)
+            .bind(update_driver)
+            .bind(update_hipaa)
+            .bind(&row.id)
+            .execute(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+        audit_db(&state.pool, "ensure_user_exists", "system", &name).await;
+        return Ok(row.id);
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let is_driver = if wants_driver { 1 } else { 0 };
+    let hipaa = if wants_hipaa { 1 } else { 0 };
+    sqlx::query(
+        r#"
+        INSERT INTO users (
+            id, name, email, telephone,
+            physical_address_line1, physical_address_line2, physical_address_city, physical_address_state, physical_address_postal_code,
+            mailing_address_line1, mailing_address_line2, mailing_address_city, mailing_address_state, mailing_address_postal_code,
+            role, is_driver, hipaa_certified, created_at, updated_at, is_deleted
+        )
+        VALUES (
+            ?, ?, ?, ?,
+            ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?,
+            ?, ?, ?, datetime('now'), datetime('now'), 0
+        )
+        "#,
+    )
+    .bind(&id)
+    .bind(&name)
+    .bind(&input.email)
+    .bind(&input.telephone)
+    .bind(&input.physical_address_line1)
+    .bind(&input.physical_address_line2)
+    .bind(&input.physical_address_city)
+    .bind(&input.physical_address_state)
+    .bind(&input.physical_address_postal_code)
+    .bind(&input.mailing_address_line1)
+    .bind(&input.mailing_address_line2)
+    .bind(&input.mailing_address_city)
+    .bind(&input.mailing_address_state)
+    .bind(&input.mailing_address_postal_code)
+    .bind(&role)
+    .bind(is_driver)
+    .bind(hipaa)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(&state.pool, "ensure_user_exists", "system", &name).await;
+    Ok(id)
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkOrderAssignmentInput {
+    work_order_id: String,
+    assignees_json: Option<String>,
+    // Explicit user ids and roles; when absent, users are matched from the assignees_json names.
+    assignments: Option<Vec<AssignmentInput>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AssignmentInput {
+    user_id: String,
+    assignment_role: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct MyAssignmentRow {
+    work_order_id: String,
+    assignment_role: String,
+    scheduled_date: Option<String>,
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkOrderScheduleInput {
+    work_order_id: String,
+    scheduled_date: Option<String>,
+    capacity_override_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkScheduleInput {
+    work_order_ids: Vec<String>,
+    scheduled_date: Option<String>,
+    capacity_override_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkOrderStatusInput {
+    work_order_id: String,
+    status: Option<String>,
+    mileage: Option<f64>,
+    work_hours: Option<f64>,
+    is_driver: Option<bool>,
+    allotment_override_reason: Option<String>,
+    // Recorded in the status history, e.g. why an order was cancelled or flagged as an issue.
+    reason: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct WorkOrderStatusRow {
+    client_id: String,
+    scheduled_date: Option<String>,
+    status: String,
+    mileage: Option<f64>,
+    work_hours: Option<f64>,
+    delivery_size_cords: Option<f64>,
+    pickup_quantity_cords: Option<f64>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct DriverAvailabilityRow {
+    name: String,
+    availability_schedule: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserUpdateInput {
+    id: String,
+    email: Option<String>,
+    telephone: Option<String>,
+    physical_address_line1: Option<String>,
+    physical_address_line2: Option<String>,
+    physical_address_city: Option<String>,
+    physical_address_state: Option<String>,
+    physical_address_postal_code: Option<String>,
+    mailing_address_line1: Option<String>,
+    mailing_address_line2: Option<String>,
+    mailing_address_city: Option<String>,
+    mailing_address_state: Option<String>,
+    mailing_address_postal_code: Option<String>,
+    availability_notes: Option<String>,
+    availability_schedule: Option<String>,
+    driver_license_status: Option<String>,
+    driver_license_number: Option<String>,
+    driver_license_expires_on: Option<String>,
+    vehicle: Option<String>,
+    hipaa_certified: Option<bool>,
+    is_driver: Option<bool>,
+}
+
+#[tauri::command]
+async fn get_available_drivers(
+    state: State<'_, AppState>,
+    date: String, // ISO date string like "2025-12-30"
+) -> Result<Vec<String>, String> {
+    available_driver_names(&state.pool, &date).await
+}
+
+async fn available_driver_names(pool: &SqlitePool, date: &str) -> Result<Vec<String>, String> {
+    use chrono::Datelike;
+
+    let rows = sqlx::query_as::<_, DriverAvailabilityRow>(
+        r#"
+        SELECT name, availability_schedule
+        FROM users
+        WHERE is_deleted = 0
+          AND is_driver = 1
+        ORDER BY name ASC
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Parse the date to get day of week; schedule inputs may carry a time ("YYYY-MM-DDTHH:MM").
+    let parsed_date = chrono::NaiveDate::parse_from_str(date.get(..10).unwrap_or(date), "%Y-%m-%d")
+        .map_err(|e| format!("Invalid date format: {}", e))?;
+
+    let weekday = parsed_date.weekday();
+    let day_name = weekday.to_string().to_lowercase();
+
+    let mut available = Vec::new();
+    for row in rows {
+        if let Some(schedule_json) = row.availability_schedule {
+            if let Ok(schedule) =
+                serde_json::from_str::<std::collections::HashMap<String, bool>>(&schedule_json)
+            {
+                if schedule.get(&day_name).copied().unwrap_or(false) {
+                    available.push(row.name);
+                }
+            } else {
+                // No schedule means always available
+                available.push(row.name);
+            }
+        } else {
+            // No schedule means always available
+            available.push(row.name);
+        }
+    }
+
+    Ok(available)
+}
+
+#[tauri::command]
+async fn update_user_flags(
+    state: State<'_, AppState>,
+    input: UserUpdateInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can update users".to_string());
+    }
+    audit_db(&state.pool, "update_user_flags", &role_val, &actor_val).await;
+
+    let existing = sqlx::query!(
+        r#"
+        SELECT email, telephone,
+               physical_address_line1, physical_address_line2, physical_address_city,
+               physical_address_state, physical_address_postal_code,
+               mailing_address_line1, mailing_address_line2, mailing_address_city,
+               mailing_address_state, mailing_address_postal_code,
+               availability_notes, availability_schedule,
+               driver_license_status, driver_license_number, driver_license_expires_on,
+               vehicle, hipaa_certified, is_driver
+        FROM users
+        WHERE id = ? AND is_deleted = 0
+        "#,
+        input.id
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let status_clean = input
+        .driver_license_status
+        .as_ref()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let expiry_clean = input
+        .driver_license_expires_on
+        .as_ref()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let wants_driver = input.is_driver.unwrap_or(false);
+    let final_is_driver = wants_driver && status_clean.is_some() && expiry_clean.is_some();
+    let hipaa = input.hipaa_certified.unwrap_or(false);
+
+    sqlx::query(
+        r#"
+        UPDATE users
+        SET email = ?,
+            telephone = ?,
+            physical_address_line1 = ?,
+            physical_address_line2 = ?,
+            physical_address_city = ?,
+            physical_address_state = ?,
+            physical_address_postal_code = ?,
+            mailing_address_line1 = ?,
+            mailing_address_line2 = ?,
+            mailing_address_city = ?,
+            mailing_address_state = ?,
+            mailing_address_postal_code = ?,
+            availability_notes = ?,
+            availability_schedule = ?,
+            driver_license_status = ?,
+            driver_license_number = ?,
+            driver_license_expires_on = ?,
+            vehicle = ?,
+            hipaa_certified = ?,
+            is_driver = ?,
+            updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&input.email)
+    .bind(&input.telephone)
+    .bind(&input.physical_address_line1)
+    .bind(&input.physical_address_line2)
+    .bind(&input.physical_address_city)
+    .bind(&input.physical_address_state)
+    .bind(&input.physical_address_postal_code)
+    .bind(&input.mailing_address_line1)
+    .bind(&input.mailing_address_line2)
+    .bind(&input.mailing_address_city)
+    .bind(&input.mailing_address_state)
+    .bind(&input.mailing_address_postal_code)
+    .bind(&input.availability_notes)
+    .bind(&input.availability_schedule)
+    .bind(&status_clean)
+    .bind(&input.driver_license_number)
+    .bind(&expiry_clean)
+    .bind(&input.vehicle)
+    .bind(if hipaa { 1 } else { 0 })
+    .bind(if final_is_driver { 1 } else { 0 })
+    .bind(&input.id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Some(prev) = existing {
+        let log_field = |field: &str, old_val: Option<String>, new_val: Option<String>| {
+            if old_val != new_val {
+                let pool = state.pool.clone();
+                let role = role_val.clone();
+                let actor = actor_val.clone();
+                let entity_id = input.id.clone();
+                let field = field.to_string();
+                tauri::async_runtime::spawn(async move {
+                    audit_change(
+                        &pool,
+                        "update_user_flags",
+                        &role,
+                        &actor,
+                        "users",
+                        &entity_id,
+                        &field,
+                        old_val,
+                        new_val,
+                    )
+                    .await;
+                });
+            }
+        };
+        log_field("email", prev.email, input.email.clone());
+        log_field("telephone", prev.telephone, input.telephone.clone());
+        log_field("physical_address_line1", prev.physical_address_line1, input.physical_address_line1.clone());
+        log_field("physical_address_line2", prev.physical_address_line2, input.physical_address_line2.clone());
+        log_field("physical_address_city", prev.physical_address_city, input.physical_address_city.clone());
+        log_field("physical_address_state", prev.physical_address_state, input.physical_address_state.clone());
+        log_field("physical_address_postal_code", prev.physical_address_postal_code, input.physical_address_postal_code.clone());
+        log_field("mailing_address_line1", prev.mailing_address_line1, input.mailing_address_line1.clone());
+        log_field("mailing_address_line2", prev.mailing_address_line2, input.mailing_address_line2.clone());
+        log_field("mailing_address_city", prev.mailing_address_city, input.mailing_address_city.clone());
+        log_field("mailing_address_state", prev.mailing_address_state, input.mailing_address_state.clone());
+        log_field("mailing_address_postal_code", prev.mailing_address_postal_code, input.mailing_address_postal_code.clone());
+        log_field("availability_notes", prev.availability_notes, input.availability_notes.clone());
+        log_field("availability_schedule", prev.availability_schedule, input.availability_schedule.clone());
+        log_field("driver_license_status", prev.driver_license_status, status_clean.clone());
+        log_field("driver_license_number", prev.driver_license_number, input.driver_license_number.clone());
+        log_field("driver_license_expires_on", prev.driver_license_expires_on, expiry_clean.clone());
+        log_field("vehicle", prev.vehicle, input.vehicle.clone());
+        log_field("hipaa_certified", Some(prev.hipaa_certified.to_string()), Some(hipaa.to_string()));
+        log_field("is_driver", Some(prev.is_driver.to_string()), Some(final_is_driver.to_string()));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateUserInput {
+    name: String,
+    email: Option<String>,
+    telephone: Option<String>,
+    physical_address_line1: Option<String>,
+    physical_address_line2: Option<String>,
+    physical_address_city: Option<String>,
+    physical_address_state: Option<String>,
+    physical_address_postal_code: Option<String>,
+    mailing_address_line1: Option<String>,
+    mailing_address_line2: Option<String>,
+    mailing_address_city: Option<String>,
+    mailing_address_state: Option<String>,
+    mailing_address_postal_code: Option<String>,
+    role: String,
+    is_driver: Option<bool>,
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateInvoiceInput {
+    work_order_id: String,
+}
+
+#[tauri::command]
+async fn create_user(
+    state: State<'_, AppState>,
+    input: CreateUserInput,
+    role: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can create users".to_string());
+    }
+    audit_db(&state.pool, "create_user", &role_val, &input.name).await;
+
+    let username = input.username.trim().to_lowercase();
+    if username.is_empty() {
+        return Err("Username is required".to_string());
+    }
+    let password = input.password.trim().to_string();
+    if password.is_empty() {
+        return Err("Password is required".to_string());
+    }
+    let existing_login = sqlx::query!(
+        r#"
+        SELECT id FROM auth_users
+        WHERE lower(username) = lower(?)
+          AND is_deleted = 0
+        LIMIT 1
+        "#,
+        username
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if existing_login.is_some() {
+        return Err("Username already exists".to_string());
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let is_driver = if input.is_driver.unwrap_or(false) {
+        1
+    } else {
+        0
+    };
+
+    sqlx::query(
+        r#"
+        INSERT INTO users (
+            id, name, email, telephone,
+            physical_address_line1, physical_address_line2, physical_address_city, physical_address_state, physical_address_postal_code,
+            mailing_address_line1, mailing_address_line2, mailing_address_city, mailing_address_state, mailing_address_postal_code,
+            role, is_driver, created_at, updated_at, is_deleted
+        )
+        VALUES (
+            ?, ?, ?, ?,
+            ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?,
+            ?, ?, datetime('now'), datetime('now'), 0
+        )
+        "#
+    )
+    .bind(&id)
+    .bind(&input.name)
+    .bind(&input.email)
+    .bind(&input.telephone)
+    .bind(&input.physical_address_line1)
+    .bind(&input.physical_address_line2)
+    .bind(&input.physical_address_city)
+    .bind(&input.physical_address_state)
+    .bind(&input.physical_address_postal_code)
+    .bind(&input.mailing_address_line1)
+    .bind(&input.mailing_address_line2)
+    .bind(&input.mailing_address_city)
+    .bind(&input.mailing_address_state)
+    .bind(&input.mailing_address_postal_code)
+    .bind(&input.role)
+    .bind(is_driver)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let login_id = Uuid::new_v4().to_string();
+    let hashed = hash(&password, DEFAULT_COST).map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        INSERT INTO auth_users (id, user_id, username, password, created_at, updated_at, is_deleted)
+        VALUES (?, ?, ?, ?, datetime('now'), datetime('now'), 0)
+        "#,
+    )
+    .bind(&login_id)
+    .bind(&id)
+    .bind(&username)
+    .bind(&hashed)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(id)
+}
+
+#[tauri::command]
+async fn list_invoices(state: State<'_, AppState>) -> Result<Vec<InvoiceRow>, String> {
+    let rows = sqlx::query_as::<_, InvoiceRow>(
+        r#"
+        SELECT
+            id,
+            work_order_id,
+            invoice_number,
+            invoice_date,
+            subtotal,
+            tax,
+            total,
+            client_snapshot_json,
+            notes,
+            status,
+            created_at
+        FROM invoices
+        WHERE is_deleted = 0
+        ORDER BY invoice_date DESC, created_at DESC
+        "#,
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(&state.pool, "list_invoices", "unknown", "unknown").await;
+    Ok(rows)
+}
+
+#[tauri::command]
+async fn list_pending_changes(state: State<'_, AppState>) -> Result<Vec<SyncRecord>, String> {
+    let service = SyncService::new(state.pool.clone());
+    service.list_pending_changes().await
+}
+
+#[tauri::command]
+fn print_invoice(window: tauri::WebviewWindow) -> Result<(), String> {
+    window.eval("window.print()").map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn create_invoice_from_work_order(
+    state: State<'_, AppState>,
+    input: CreateInvoiceInput,
+) -> Result<String, String> {
+    let work_order = sqlx::query!(
+        r#"
+        SELECT
+            id,
+            client_id,
+            client_name,
+            physical_address_line1,
+            physical_address_line2,
+            physical_address_city,
+            physical_address_state,
+            physical_address_postal_code,
+            mailing_address_line1,
+            mailing_address_line2,
+            mailing_address_city,
+            mailing_address_state,
+            mailing_address_postal_code,
+            telephone,
+            email,
+            scheduled_date,
+            delivery_size_label,
+            delivery_size_cords,
+            notes
+        FROM work_orders
+        WHERE id = ?
+          AND is_deleted = 0
+        LIMIT 1
+        "#,
+        input.work_order_id
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Work order not found".to_string())?;
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let invoice_count = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM invoices
+        WHERE invoice_date = ?
+          AND is_deleted = 0
+        "#,
+        today
+    )
+    .fetch_one(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let seq = invoice_count.count + 1;
+    let invoice_number = format!("INV-{}-{:03}", today.replace("-", ""), seq);
+
+    let snapshot = serde_json::json!({
+        "client_id": work_order.client_id,
+        "client_name": work_order.client_name,
+        "physical_address_line1": work_order.physical_address_line1,
+        "physical_address_line2": work_order.physical_address_line2,
+        "physical_address_city": work_order.physical_address_city,
+        "physical_address_state": work_order.physical_address_state,
+        "physical_address_postal_code": work_order.physical_address_postal_code,
+        "mailing_address_line1": work_order.mailing_address_line1,
+        "mailing_address_line2": work_order.mailing_address_line2,
+        "mailing_address_city": work_order.mailing_address_city,
+        "mailing_address_state": work_order.mailing_address_state,
+        "mailing_address_postal_code": work_order.mailing_address_postal_code,
+        "telephone": work_order.telephone,
+        "email": work_order.email,
+        "scheduled_date": work_order.scheduled_date,
+        "delivery_size_label": work_order.delivery_size_label,
+        "delivery_size_cords": work_order.delivery_size_cords,
+        "notes": work_order.notes
+    });
+
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO invoices (
+            id, work_order_id, invoice_number, invoice_date,
+            subtotal, tax, total, client_snapshot_json, notes, status,
+            created_at, updated_at, is_deleted
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 'draft', datetime('now'), datetime('now'), 0)
+        "#,
+    )
+    .bind(&id)
+    .bind(&work_order.id)
+    .bind(&invoice_number)
+    .bind(&today)
+    .bind(0.0)
+    .bind(0.0)
+    .bind(0.0)
+    .bind(snapshot.to_string())
+    .bind(work_order.notes)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let item_id = Uuid::new_v4().to_string();
+    let description = match work_order.delivery_size_label.as_deref() {
+        Some(label) if !label.is_empty() => format!("Firewood delivery ({})", label),
+        _ => "Firewood delivery".to_string(),
+    };
+    let quantity = work_order.delivery_size_cords.unwrap_or(1.0);
+    sqlx::query(
+        r#"
+        INSERT INTO invoice_line_items (
+            id, invoice_id, description, quantity, unit_price, total,
+            created_at, updated_at, is_deleted
+        )
+        VALUES (?, ?, ?, ?, ?, ?, datetime('now'), datetime('now'), 0)
+        "#,
+    )
+    .bind(&item_id)
+    .bind(&id)
+    .bind(&description)
+    .bind(quantity)
+    .bind(0.0)
+    .bind(0.0)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(&state.pool, "create_invoice_from_work_order", "unknown", "unknown").await;
+    Ok(id)
+}
+
+#[derive(Debug, Deserialize)]
+struct LoginInput {
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct LoginResponse {
+    user_id: String,
+    name: String,
+    username: String,
+    role: String,
+    email: Option<String>,
+    telephone: Option<String>,
+    hipaa_certified: i64,
+    is_driver: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChangePasswordInput {
+    username: String,
+    current_password: String,
+    new_password: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResetPasswordInput {
+    user_id: String,
+    new_password: String,
+}
+
+#[tauri::command]
+async fn login_user(state: State<'_, AppState>, input: LoginInput) -> Result<LoginResponse, String> {
+    let username = input.username.trim().to_lowercase();
+    let password = input.password.trim().to_string();
+    if username.is_empty() || password.is_empty() {
+        return Err("Username and password are required".to_string());
+    }
+
+    let row = sqlx::query!(
+        r#"
+        SELECT
+            au.user_id as user_id,
+            u.name as name,
+            au.username as username,
+            u.role as role,
+            u.email as email,
+            u.telephone as telephone,
+            COALESCE(u.hipaa_certified, 0) as hipaa_certified,
+            COALESCE(u.is_driver, 0) as is_driver,
+            au.password as password
+        FROM auth_users au
+        JOIN users u ON u.id = au.user_id
+        WHERE lower(au.username) = lower(?)
+          AND au.is_deleted = 0
+          AND u.is_deleted = 0
+        LIMIT 1
+        "#
+    , username
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let row = row.ok_or_else(|| "Invalid username or password".to_string())?;
+    if !verify(&password, &row.password).map_err(|e| e.to_string())? {
+        return Err("Invalid username or password".to_string());
+    }
+
+    audit_db(&state.pool, "login_user", &row.role, &row.username).await;
+    Ok(LoginResponse {
+        user_id: row.user_id,
+        name: row.name,
+        username: row.username,
+        role: row.role,
+        email: row.email,
+        telephone: row.telephone,
+        hipaa_certified: row.hipaa_certified,
+        is_driver: row.is_driver,
+    })
+}
+
+async fn change_password_with_pool(
+    pool: &SqlitePool,
+    input: ChangePasswordInput,
+) -> Result<(), String> {
+    let username = input.username.trim().to_lowercase();
+    let current_password = input.current_password.trim();
+    let new_password = input.new_password.trim();
+    if username.is_empty() || current_password.is_empty() || new_password.is_empty() {
+        return Err("Username, current password, and new password are required".to_string());
+    }
+
+    let row = sqlx::query!(
+        r#"
+        SELECT id, password
+        FROM auth_users
+        WHERE lower(username) = lower(?)
+          AND is_deleted = 0
+        LIMIT 1
+        "#,
+        username
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "User not found".to_string())?;
+
+    if !verify(current_password, &row.password).map_err(|e| e.to_string())? {
+        return Err("Current password is incorrect".to_string());
+    }
+
+    let hashed = hash(new_password, DEFAULT_COST).map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        UPDATE auth_users
+        SET password = ?, updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&hashed)
+    .bind(&row.id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(pool, "change_password", "unknown", &username).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn change_password(state: State<'_, AppState>, input: ChangePasswordInput) -> Result<(), String> {
+    change_password_with_pool(&state.pool, input).await
+}
+
+async fn reset_password_with_pool(
+    pool: &SqlitePool,
+    input: ResetPasswordInput,
+    role: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    if role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can reset passwords".to_string());
+    }
+    let new_password = input.new_password.trim();
+    if new_password.is_empty() {
+        return Err("New password is required".to_string());
+    }
+    let hashed = hash(new_password, DEFAULT_COST).map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        UPDATE auth_users
+        SET password = ?, updated_at = datetime('now')
+        WHERE user_id = ?
+          AND is_deleted = 0
+        "#,
+    )
+    .bind(&hashed)
+    .bind(&input.user_id)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(pool, "reset_password", &role_val, &input.user_id).await;
+    Ok(())
+}
+
+#[tauri::command]
+async fn reset_password(
+    state: State<'_, AppState>,
+    input: ResetPasswordInput,
+    role: Option<String>,
+) -> Result<(), String> {
+    reset_password_with_pool(&state.pool, input, role).await
+}
+
+const ASSIGNMENT_ROLES: [&str; 3] = ["driver", "helper", "lead"];
+
+fn parse_assignee_names(assignees_json: Option<&str>) -> Result<Vec<String>, String> {
+    let names: Vec<String> = serde_json::from_str(assignees_json.unwrap_or("[]"))
+        .map_err(|_| "Assignees must be a JSON list of names".to_string())?;
+    Ok(names
+        .into_iter()
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .collect())
+}
+
+/// User ids for assignee entries. A user id or username picks that user; a display name only
+/// counts when exactly one user has it, so two people sharing a name are never both assigned.
+/// Entries matching nobody are free-text helpers and are skipped.
+async fn resolve_assignee_ids(
+    conn: &mut sqlx::SqliteConnection,
+    names: &[String],
+) -> Result<Vec<String>, String> {
+    let mut ids: Vec<String> = Vec::new();
+    for name in names {
+        let by_login: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT u.id
+            FROM users u
+            WHERE u.is_deleted = 0
+              AND (
+                u.id = ?1
+                OR EXISTS (
+                    SELECT 1 FROM auth_users a
+                    WHERE a.user_id = u.id AND a.is_deleted = 0 AND lower(a.username) = lower(?1)
+                )
+              )
+            LIMIT 1
+            "#,
+        )
+        .bind(name)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        let id = match by_login {
+            Some(id) => Some(id),
+            None => {
+                let by_name: Vec<String> = sqlx::query_scalar(
+                    "SELECT id FROM users WHERE is_deleted = 0 AND lower(name) = lower(?)",
+                )
+                .bind(name)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| e.to_string())?;
+                if by_name.len() > 1 {
+                    return Err(format!(
+                        "{} people are named '{}'; assign them by username",
+                        by_name.len(),
+                        name
+                    ));
+                }
+                by_name.into_iter().next()
+            }
+        };
+        if let Some(id) = id {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    Ok(ids)
+}
+
+/// Links each name that resolves to a user (see `resolve_assignee_ids`). Names with no user
+/// record (free-text helpers) stay only in `assignees_json`.
+async fn sync_assignments_from_names(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+    names: &[String],
+    actor_val: &str,
+) -> Result<(), String> {
+    let user_ids = resolve_assignee_ids(&mut *conn, names).await?;
+    sqlx::query("DELETE FROM work_order_assignments WHERE work_order_id = ?")
+        .bind(work_order_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        INSERT OR IGNORE INTO work_order_assignments (
+            id, work_order_id, user_id, assignment_role, created_by
+        )
+        SELECT
+            lower(hex(randomblob(16))), ?1, u.id,
+            CASE
+                WHEN u.is_driver = 1 OR trim(COALESCE(u.driver_license_status, '')) != '' THEN 'driver'
+                WHEN lower(u.role) = 'lead' THEN 'lead'
+                ELSE 'helper'
+            END,
+            ?3
+        FROM json_each(?2) j
+        JOIN users u ON u.id = j.value
+        "#,
+    )
+    .bind(work_order_id)
+    .bind(serde_json::to_string(&user_ids).map_err(|e| e.to_string())?)
+    .bind(actor_val)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Sets the order's delivery events to the ids of its assigned users. Free-text helpers have no
+/// id and stay only in the order's `assignees_json`.
+async fn sync_event_assignees(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        UPDATE delivery_events
+        SET assigned_user_ids_json = (
+                SELECT json_group_array(user_id)
+                FROM (
+                    SELECT user_id FROM work_order_assignments
+                    WHERE work_order_id = ?1
+                    ORDER BY user_id
+                )
+            ),
+            updated_at = datetime('now')
+        WHERE work_order_id = ?1
+        "#,
+    )
+    .bind(work_order_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn replace_assignments(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+    assignments: &[AssignmentInput],
+    actor_val: &str,
+) -> Result<(), String> {
+    for assignment in assignments {
+        if !ASSIGNMENT_ROLES.contains(&assignment.assignment_role.as_str()) {
+            return Err(format!(
+                "Unknown assignment role '{}'; expected one of {}",
+                assignment.assignment_role,
+                ASSIGNMENT_ROLES.join(", ")
+            ));
+        }
+    }
+    sqlx::query("DELETE FROM work_order_assignments WHERE work_order_id = ?")
+        .bind(work_order_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    for assignment in assignments {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO work_order_assignments (
+                id, work_order_id, user_id, assignment_role, created_by
+            )
+            SELECT ?, ?, id, ?, ? FROM users WHERE id = ? AND is_deleted = 0
+            ON CONFLICT (work_order_id, user_id) DO UPDATE SET assignment_role = excluded.assignment_role
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(work_order_id)
+        .bind(&assignment.assignment_role)
+        .bind(actor_val)
+        .bind(&assignment.user_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        if inserted.rows_affected() == 0 {
+            return Err(format!("User {} not found", assignment.user_id));
+        }
+    }
+    Ok(())
+}
+
+/// "name (role)" per assigned user, for the audit trail.
+async fn load_assignment_summary(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(
+        r#"
+        SELECT u.name || ' (' || a.assignment_role || ')'
+        FROM work_order_assignments a
+        JOIN users u ON u.id = a.user_id
+        WHERE a.work_order_id = ?
+        ORDER BY u.name
+        "#,
+    )
+    .bind(work_order_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn update_work_order_assignees(
+    state: State<'_, AppState>,
+    input: WorkOrderAssignmentInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff or admins can assign drivers/helpers".to_string());
+    }
+    audit_db(
+        &state.pool,
+        "update_work_order_assignees",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+
+    let mut tx = state.pool.begin().await.map_err(|e| e.to_string())?;
+
+    let existing = sqlx::query!(
+        r#"SELECT assignees_json FROM work_orders WHERE id = ? AND is_deleted = 0"#,
+        input.work_order_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let previous_assignments = load_assignment_summary(&mut tx, &input.work_order_id).await?;
+
+    let assignees_json = match &input.assignments {
+        Some(assignments) => {
+            replace_assignments(&mut tx, &input.work_order_id, assignments, &actor_val).await?;
+            match &input.assignees_json {
+                Some(json) => {
+                    parse_assignee_names(Some(json))?;
+                    Some(json.clone())
+                }
+                None => {
+                    // Display names of the assigned users, for screens still reading the JSON list.
+                    let names: Vec<String> = sqlx::query_scalar(
+                        r#"
+                        SELECT u.name FROM work_order_assignments a
+                        JOIN users u ON u.id = a.user_id
+                        WHERE a.work_order_id = ?
+                        ORDER BY a.assignment_role, u.name
+                        "#,
+                    )
+                    .bind(&input.work_order_id)
+                    .fetch_all(&mut *tx)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                    Some(serde_json::to_string(&names).map_err(|e| e.to_string())?)
+                }
+            }
+        }
+        None => {
+            let names = parse_assignee_names(input.assignees_json.as_deref())?;
+            sync_assignments_from_names(&mut tx, &input.work_order_id, &names, &actor_val).await?;
+            input.assignees_json.clone()
+        }
+    };
+    let current_assignments = load_assignment_summary(&mut tx, &input.work_order_id).await?;
+    let mut partner_audits = PendingAudits::new();
+    if let Some(partner_id) = open_partner_id(&mut tx, &input.work_order_id).await? {
+        share_pair_drivers(
+            &mut tx,
+            &input.work_order_id,
+            &partner_id,
+            &actor_val,
+            &mut partner_audits,
+        )
+        .await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET assignees_json = ?, updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&assignees_json)
+    .bind(&input.work_order_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    sync_event_assignees(&mut tx, &input.work_order_id).await?;
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if let Some(prev) = existing {
+        let old_val = prev.assignees_json;
+        if old_val != assignees_json {
+            audit_change(
+                &state.pool,
+                "update_work_order_assignees",
+                &role_val,
+                &actor_val,
+                "work_orders",
+                &input.work_order_id,
+                "assignees_json",
+                old_val,
+                assignees_json,
+            )
+            .await;
+        }
+    }
+    if previous_assignments != current_assignments {
+        audit_change(
+            &state.pool,
+            "update_work_order_assignees",
+            &role_val,
+            &actor_val,
+            "work_orders",
+            &input.work_order_id,
+            "assignments",
+            Some(previous_assignments.join(", ")),
+            Some(current_assignments.join(", ")),
+        )
+        .await;
+    }
+    flush_audits(
+        &state.pool,
+        "update_work_order_assignees",
+        &role_val,
+        &actor_val,
+        partner_audits,
+    )
+    .await;
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_my_assignments(
+    state: State<'_, AppState>,
+    username: String,
+    role: Option<String>,
+) -> Result<Vec<MyAssignmentRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    audit_db(&state.pool, "list_my_assignments", &role_val, &username).await;
+    sqlx::query_as::<_, MyAssignmentRow>(
+        r#"
+        SELECT a.work_order_id, a.assignment_role, w.scheduled_date, w.status
+        FROM auth_users au
+        JOIN work_order_assignments a ON a.user_id = au.user_id
+        JOIN work_orders w ON w.id = a.work_order_id
+        WHERE au.is_deleted = 0 AND lower(au.username) = lower(?) AND w.is_deleted = 0
+        ORDER BY (w.scheduled_date IS NULL), w.scheduled_date
+        "#,
+    )
+    .bind(username.trim())
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Field changes made inside a transaction, audited once it commits:
+/// (work order id, field, old, new).
+type PendingAudits = Vec<(String, &'static str, Option<String>, Option<String>)>;
+
+#[derive(Debug, Serialize, FromRow)]
+struct VehicleRow {
+    id: String,
+    name: String,
+    capacity_cords: f64,
+    trips_per_day: i64,
+    notes: Option<String>,
+    is_active: bool,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VehicleInput {
+    id: Option<String>,
+    name: String,
+    capacity_cords: f64,
+    trips_per_day: Option<i64>,
+    notes: Option<String>,
+    is_active: Option<bool>,
+}
+
+/// What one delivery day can carry against what is booked on it. A day with no vehicles set up
+/// is not planned and never counts as overbooked.
+#[derive(Debug, Clone, Serialize)]
+struct DayCapacity {
+    date: String,
+    planned: bool,
+    drivers_available: i64,
+    vehicles_in_use: i64,
+    trip_slots: i64,
+    capacity_cords: f64,
+    orders_booked: i64,
+    trips_booked: f64,
+    cords_booked: f64,
+    overbooked: bool,
+    message: Option<String>,
+}
+
+impl DayCapacity {
+    /// Whether one more load of `cords` still fits on the day.
+    fn fits(&self, cords: f64) -> bool {
+        !self.planned
+            || (self.trips_booked + 1.0 <= self.trip_slots as f64
+                && self.cords_booked + cords <= self.capacity_cords + 1e-9)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ScheduleCapacityReport {
+    days: Vec<DayCapacity>,
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct BookedLoadRow {
+    delivery_size_cords: Option<f64>,
+    delivery_size_label: Option<String>,
+    paired_order_id: Option<String>,
+}
+
+/// Capacity of `date` given how many drivers are available. Each available driver takes one
+/// vehicle, biggest first; paired half loads share a trip and pickups need none.
+async fn day_capacity(
+    conn: &mut sqlx::SqliteConnection,
+    date: &str,
+    drivers_available: usize,
+) -> Result<DayCapacity, String> {
+    let vehicles: Vec<(f64, i64)> = sqlx::query_as(
+        r#"
+        SELECT capacity_cords, trips_per_day
+        FROM vehicles
+        WHERE is_deleted = 0 AND is_active = 1
+        ORDER BY capacity_cords DESC, trips_per_day DESC
+        "#,
+    )
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let loads = sqlx::query_as::<_, BookedLoadRow>(
+        r#"
+        SELECT delivery_size_cords, delivery_size_label, paired_order_id
+        FROM work_orders
+        WHERE is_deleted = 0
+          AND date(scheduled_date) = date(?)
+          AND lower(status) != 'cancelled'
+          AND lower(COALESCE(pickup_delivery_type, 'delivery')) != 'pickup'
+        "#,
+    )
+    .bind(date)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let in_use = &vehicles[..vehicles.len().min(drivers_available)];
+    let trip_slots: i64 = in_use.iter().map(|(_, trips)| trips).sum();
+    let capacity_cords: f64 = in_use
+        .iter()
+        .map(|(cords, trips)| cords * *trips as f64)
+        .sum();
+    let trips_booked: f64 = loads
+        .iter()
+        .map(|load| {
+            let shared =
+                load.paired_order_id.is_some() && is_half_load(load.delivery_size_label.as_deref());
+            if shared {
+                0.5
+            } else {
+                1.0
+            }
+        })
+        .sum();
+    let cords_booked: f64 = loads
+        .iter()
+        .map(|load| load.delivery_size_cords.unwrap_or(0.0))
+        .sum();
+
+    let planned = !vehicles.is_empty();
+    let overbooked =
+        planned && (trips_booked > trip_slots as f64 || cords_booked > capacity_cords + 1e-9);
+    let message = overbooked.then(|| {
+        format!(
+            "{} is overbooked: {} trip(s) for {} slot(s) and {:.2} of {:.2} cords with {} driver(s) available.",
+            date, trips_booked, trip_slots, cords_booked, capacity_cords, drivers_available
+        )
+    });
+    Ok(DayCapacity {
+        date: date.to_string(),
+        planned,
+        drivers_available: drivers_available as i64,
+        vehicles_in_use: in_use.len() as i64,
+        trip_slots,
+        capacity_cords,
+        orders_booked: loads.len() as i64,
+        trips_booked,
+        cords_booked,
+        overbooked,
+        message,
+    })
+}
+
+/// Like `resolve_allotment`: an overbooked day is refused unless a lead or admin gives a reason,
+/// in which case the overbooking comes back as warnings.
+fn resolve_capacity(
+    days: &[DayCapacity],
+    role: &str,
+    override_reason: Option<&str>,
+) -> Result<Vec<String>, String> {
+    let messages: Vec<String> = days.iter().filter_map(|day| day.message.clone()).collect();
+    if messages.is_empty() {
+        return Ok(messages);
+    }
+    match override_reason.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(_) if role == "admin" || role == "lead" => Ok(messages),
+        Some(_) => Err("Only leads or admins can overbook a delivery day".to_string()),
+        None => Err(format!(
+            "{} A lead can overbook with a reason.",
+            messages.join(" ")
+        )),
+    }
+}
+
+/// Puts `work_order_ids` (and their half-load partners) on `scheduled_date` in one transaction,
+/// refusing the lot if that overbooks the day.
+async fn schedule_orders(
+    pool: &SqlitePool,
+    work_order_ids: &[String],
+    scheduled_date: Option<&str>,
+    capacity_override_reason: Option<&str>,
+    role_val: &str,
+    actor_val: &str,
+    event: &str,
+) -> Result<ScheduleCapacityReport, String> {
+    // Capacity is per day, whatever time of day the order was scheduled for.
+    let day = scheduled_date.map(|date| date.get(..10).unwrap_or(date));
+    let drivers_available = match day {
+        Some(date) => Some(available_driver_names(pool, date).await?.len()),
+        None => None,
+    };
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut audits = PendingAudits::new();
+    for work_order_id in work_order_ids {
+        schedule_order_tx(
+            &mut tx,
+            work_order_id,
+            scheduled_date,
+            role_val,
+            actor_val,
+            &mut audits,
+        )
+        .await?;
+        // Half loads travel together, so the partner moves with the order.
+        if let Some(partner_id) = open_partner_id(&mut tx, work_order_id).await? {
+            schedule_order_tx(
+                &mut tx,
+                &partner_id,
+                scheduled_date,
+                role_val,
+                actor_val,
+                &mut audits,
+            )
+            .await?;
+        }
+    }
+
+    let mut report = ScheduleCapacityReport::default();
+    if let (Some(date), Some(drivers)) = (day, drivers_available) {
+        report
+            .days
+            .push(day_capacity(&mut tx, date, drivers).await?);
+        report.warnings = resolve_capacity(&report.days, role_val, capacity_override_reason)?;
+        if let Some(reason) = capacity_override_reason.filter(|_| !report.warnings.is_empty()) {
+            for work_order_id in work_order_ids {
+                audits.push((
+                    work_order_id.clone(),
+                    "capacity_override",
+                    Some(report.warnings.join(" ")),
+                    Some(reason.trim().to_string()),
+                ));
+            }
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    flush_audits(pool, event, role_val, actor_val, audits).await;
+    Ok(report)
+}
+
+#[tauri::command]
+async fn list_vehicles(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<VehicleRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "list_vehicles", &role_val, &actor_val).await;
+    sqlx::query_as::<_, VehicleRow>(
+        r#"
+        SELECT id, name, capacity_cords, trips_per_day, notes, is_active, created_at, updated_at
+        FROM vehicles
+        WHERE is_deleted = 0
+        ORDER BY name
+        "#,
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_vehicle(
+    state: State<'_, AppState>,
+    input: VehicleInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "save_vehicle", &role_val, &actor_val).await;
+    if role_val != "admin" && !is_staff_like(&role_val) {
+        return Err("Only staff or admin may manage vehicles".to_string());
+    }
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err("Vehicle name is required".to_string());
+    }
+    if !input.capacity_cords.is_finite() || input.capacity_cords <= 0.0 {
+        return Err("Capacity must be more than zero cords".to_string());
+    }
+    let trips_per_day = input.trips_per_day.unwrap_or(1);
+    if !(1..=10).contains(&trips_per_day) {
+        return Err("Trips per day must be between 1 and 10".to_string());
+    }
+    let is_active = input.is_active.unwrap_or(true);
+
+    let id = match &input.id {
+        Some(id) => {
+            let updated = sqlx::query(
+                r#"
+                UPDATE vehicles
+                SET name = ?, capacity_cords = ?, trips_per_day = ?, notes = ?, is_active = ?,
+                    updated_at = datetime('now'), version = version + 1
+                WHERE id = ? AND is_deleted = 0
+                "#,
+            )
+            .bind(name)
+            .bind(input.capacity_cords)
+            .bind(trips_per_day)
+            .bind(&input.notes)
+            .bind(is_active)
+            .bind(id)
+            .execute(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            if updated.rows_affected() == 0 {
+                return Err("Vehicle not found".to_string());
+            }
+            id.clone()
+        }
+        None => {
+            let id = Uuid::new_v4().to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO vehicles (id, name, capacity_cords, trips_per_day, notes, is_active)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&id)
+            .bind(name)
+            .bind(input.capacity_cords)
+            .bind(trips_per_day)
+            .bind(&input.notes)
+            .bind(is_active)
+            .execute(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            id
+        }
+    };
+
+    audit_change(
+        &state.pool,
+        "save_vehicle",
+        &role_val,
+        &actor_val,
+        "vehicles",
+        &id,
+        "vehicle",
+        None,
+        Some(format!(
+            "{} {} cords x {} trip(s) active={}",
+            name, input.capacity_cords, trips_per_day, is_active
+        )),
+    )
+    .await;
+    Ok(id)
+}
+
+#[tauri::command]
+async fn delete_vehicle(
+    state: State<'_, AppState>,
+    id: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "delete_vehicle", &role_val, &actor_val).await;
+    if role_val != "admin" && !is_staff_like(&role_val) {
+        return Err("Only staff or admin may manage vehicles".to_string());
+    }
+    sqlx::query(
+        r#"
+        UPDATE vehicles
+        SET is_deleted = 1, updated_at = datetime('now'), version = version + 1
+        WHERE id = ?
+        "#,
+    )
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    audit_change(
+        &state.pool,
+        "delete_vehicle",
+        &role_val,
+        &actor_val,
+        "vehicles",
+        &id,
+        "is_deleted",
+        Some("0".to_string()),
+        Some("1".to_string()),
+    )
+    .await;
+    Ok(())
+}
+
+/// Capacity utilization for each day from `start_date` through `end_date`.
+#[tauri::command]
+async fn get_delivery_capacity(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<DayCapacity>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "get_delivery_capacity", &role_val, &actor_val).await;
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads or admins can view delivery capacity".to_string());
+    }
+    let parse = |d: &str| {
+        chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("'{}' is not a YYYY-MM-DD date", d))
+    };
+    let (start, end) = (parse(&start_date)?, parse(&end_date)?);
+    if end < start || (end - start).num_days() > 92 {
+        return Err("Pick a range of at most three months".to_string());
+    }
+
+    let mut days = Vec::new();
+    let mut date = start;
+    while date <= end {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let drivers = available_driver_names(&state.pool, &date_str).await?.len();
+        let mut conn = state.pool.acquire().await.map_err(|e| e.to_string())?;
+        days.push(day_capacity(&mut conn, &date_str, drivers).await?);
+        date += chrono::Duration::days(1);
+    }
+    Ok(days)
+}
+
+/// Moves one order to `scheduled_date`, keeping its delivery event in step. Giving a draft or
+/// received order a date schedules it; orders further along keep their status.
+async fn schedule_order_tx(
+    tx: &mut Transaction<'_, Sqlite>,
+    work_order_id: &str,
+    scheduled_date: Option<&str>,
+    role_val: &str,
+    actor_val: &str,
+    audits: &mut PendingAudits,
+) -> Result<(), String> {
+    let existing = sqlx::query!(
+        r#"
+        SELECT scheduled_date, client_name, status, delivery_size_cords
+        FROM work_orders
+        WHERE id = ? AND is_deleted = 0
+        "#,
+        work_order_id
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let existing = match existing {
+        Some(e) => e,
+        None => return Err("Work order not found or has been deleted".to_string()),
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET scheduled_date = ?, updated_at = datetime('now')
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(scheduled_date)
+    .bind(work_order_id)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let current = WorkOrderStatus::parse(&existing.status)?;
+    let next_status = match scheduled_date {
+        Some(_) if matches!(current, WorkOrderStatus::Draft | WorkOrderStatus::Received) => {
+            Some(WorkOrderStatus::Scheduled.as_str().to_string())
+        }
+        _ => None,
+    };
+
+    if let Some(status) = &next_status {
+        sqlx::query(
+            r#"
+            UPDATE work_orders
+            SET status = ?, updated_at = datetime('now')
+            WHERE id = ? AND is_deleted = 0
+            "#,
+        )
+        .bind(status)
+        .bind(work_order_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        adjust_inventory_for_transition_tx(
+            tx,
+            &existing.status,
+            status,
+            existing.delivery_size_cords.unwrap_or(0.0),
+        )
+        .await?;
+        record_status_change(
+            tx,
+            work_order_id,
+            Some(&existing.status),
+            status,
+            role_val,
+            actor_val,
+            None,
+        )
+        .await?;
+    }
+
+    if let Some(start_date) = scheduled_date {
+        let existing_delivery = sqlx::query!(
+            r#"SELECT id FROM delivery_events WHERE work_order_id = ? AND is_deleted = 0 LIMIT 1"#,
+            work_order_id
+        )
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if let Some(row) = existing_delivery {
+            sqlx::query(
+                r#"
+                UPDATE delivery_events
+                SET start_date = ?, updated_at = datetime('now')
+                WHERE id = ?
+                "#,
+            )
+            .bind(start_date)
+            .bind(&row.id)
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        } else {
+            let delivery_id = Uuid::new_v4().to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO delivery_events (
+                    id, title, description, event_type, work_order_id,
+                    start_date, end_date, color_code, assigned_user_ids_json
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&delivery_id)
+            .bind(format!("Delivery for {}", existing.client_name))
+            .bind::<Option<String>>(None)
+            .bind("delivery")
+            .bind(work_order_id)
+            .bind(start_date)
+            .bind::<Option<String>>(None)
+            .bind("#e67f1e")
+            .bind("[]")
+            .execute(&mut **tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    if existing.scheduled_date.as_deref() != scheduled_date {
+        audits.push((
+            work_order_id.to_string(),
+            "scheduled_date",
+            existing.scheduled_date,
+            scheduled_date.map(str::to_string),
+        ));
+    }
+    if let Some(status) = next_status {
+        if existing.status != status {
+            audits.push((
+                work_order_id.to_string(),
+                "status",
+                Some(existing.status),
+                Some(status),
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn flush_audits(
+    pool: &SqlitePool,
+    event: &str,
+    role_val: &str,
+    actor_val: &str,
+    audits: PendingAudits,
+) {
+    for (work_order_id, field, old_value, new_value) in audits {
+        audit_change(
+            pool,
+            event,
+            role_val,
+            actor_val,
+            "work_orders",
+            &work_order_id,
+            field,
+            old_value,
+            new_value,
+        )
+        .await;
+    }
+}
+
+#[tauri::command]
+async fn update_work_order_schedule(
+    state: State<'_, AppState>,
+    input: WorkOrderScheduleInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<ScheduleCapacityReport, String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff or admin may schedule work orders".to_string());
+    }
+    audit_db(
+        &state.pool,
+        "update_work_order_schedule",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+
+    schedule_orders(
+        &state.pool,
+        std::slice::from_ref(&input.work_order_id),
+        input.scheduled_date.as_deref(),
+        input.capacity_override_reason.as_deref(),
+        &role_val,
+        &actor_val,
+        "update_work_order_schedule",
+    )
+    .await
+}
+
+#[tauri::command]
+async fn bulk_schedule_work_orders(
+    state: State<'_, AppState>,
+    input: BulkScheduleInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<ScheduleCapacityReport, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "bulk_schedule_work_orders",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff or admin may schedule work orders".to_string());
+    }
+    if input.work_order_ids.is_empty() {
+        return Err("Pick at least one work order to schedule".to_string());
+    }
+
+    schedule_orders(
+        &state.pool,
+        &input.work_order_ids,
+        input.scheduled_date.as_deref(),
+        input.capacity_override_reason.as_deref(),
+        &role_val,
+        &actor_val,
+        "bulk_schedule_work_orders",
+    )
+    .await
+}
+
+#[tauri::command]
+async fn update_work_order_status(
+    state: State<'_, AppState>,
+    input: WorkOrderStatusInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<WorkOrderStatusResult, String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "update_work_order_status",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    change_work_order_status(&state.pool, input, &role_val, &actor_val).await
+}
+
+/// Moves an order to a new status, adjusting stock and history in one transaction. The audit
+/// rows are written after the commit so they never wait on the transaction's write lock.
+async fn change_work_order_status(
+    pool: &SqlitePool,
+    input: WorkOrderStatusInput,
+    role_val: &str,
+    actor_val: &str,
+) -> Result<WorkOrderStatusResult, String> {
+    let driver_capable = input.is_driver.unwrap_or(false);
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    let existing = sqlx::query_as::<_, WorkOrderStatusRow>(
+        r#"
+        SELECT client_id, scheduled_date, status, mileage, work_hours,
+               delivery_size_cords, pickup_quantity_cords
+        FROM work_orders
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(&input.work_order_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let existing = match existing {
+        Some(e) => e,
+        None => return Err("Work order not found or has been deleted".to_string()),
+    };
+
+    // Volunteers can only update if they are marked as drivers.
+    let status_actor = StatusActor::from_role(role_val, driver_capable)
+        .ok_or_else(|| "Volunteers cannot update status/mileage".to_string())?;
+    if input.work_hours.is_some() && role_val != "admin" && role_val != "lead" {
+        return Err("Only admins or leads can set work hours".to_string());
+    }
+
+    let current = WorkOrderStatus::parse(&existing.status)?;
+    let next = match input.status.as_deref() {
+        Some(status) => WorkOrderStatus::parse(status)?,
+        None => current,
+    };
+    if next == WorkOrderStatus::Cancelled && current != WorkOrderStatus::Cancelled {
+        return Err("Cancel orders through cancel_work_order with a reason code".to_string());
+    }
+    // Legacy spellings ("pending", "rescheduled") are left alone unless the status really changes.
+    let current_status = existing.status;
+    let next_status = if next == current {
+        current_status.clone()
+    } else {
+        next.as_str().to_string()
+    };
+    if next != current {
+        let requires = work_order_status::authorize_transition(current, next, status_actor)?;
+        let missing: Vec<&str> = requires
+            .iter()
+            .filter(|requirement| match requirement {
+                Requirement::ScheduledDate => existing
+                    .scheduled_date
+                    .as_deref()
+                    .map_or(true, |d| d.trim().is_empty()),
+                Requirement::Mileage => input.mileage.or(existing.mileage).is_none(),
+                Requirement::WorkHours => input.work_hours.or(existing.work_hours).is_none(),
+            })
+            .map(|requirement| requirement.message())
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "Moving to {} requires {}",
+                next.as_str(),
+                missing.join(" and ")
+            ));
+        }
+    }
+    let delivery_size = if next == WorkOrderStatus::PickedUp || current == WorkOrderStatus::PickedUp
+    {
+        existing.pickup_quantity_cords.unwrap_or(0.0)
+    } else {
+        existing.delivery_size_cords.unwrap_or(0.0)
+    };
+
+    // Re-check the season allotment when a draft/cancelled order starts counting again.
+    let mut allotment_note = None;
+    if counts_toward_allotment(&next_status) && !counts_toward_allotment(&current_status) {
+        let check = check_allotment(
+            &mut tx,
+            &existing.client_id,
+            Some(&input.work_order_id),
+            existing.scheduled_date.as_deref(),
+            delivery_size,
+        )
+        .await?;
+        allotment_note =
+            resolve_allotment(&check, role_val, input.allotment_override_reason.as_deref())?
+                .map(|(field, value)| (field, check.message.clone(), value));
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET status = ?,
+            mileage = COALESCE(?, mileage),
+            work_hours = COALESCE(?, work_hours),
+            updated_at = datetime('now')
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(&next_status)
+    .bind(&input.mileage)
+    .bind(&input.work_hours)
+    .bind(&input.work_order_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut delivered_note = None;
+    if current_status != next_status {
+        let consumed = if next == WorkOrderStatus::Completed {
+            let settled =
+                settle_delivered_cords(&mut tx, &input.work_order_id, delivery_size).await?;
+            delivered_note = Some(settled);
+            settled
+        } else {
+            delivery_size
+        };
+        adjust_inventory_tx(
+            &mut tx,
+            &current_status,
+            &next_status,
+            delivery_size,
+            consumed,
+        )
+        .await?;
+        record_status_change(
+            &mut tx,
+            &input.work_order_id,
+            Some(&current_status),
+            &next_status,
+            role_val,
+            actor_val,
+            input.reason.as_deref(),
+        )
+        .await?;
+        // Reinstating clears the cancellation that cancel_work_order recorded.
+        if current == WorkOrderStatus::Cancelled {
+            sqlx::query(
+                r#"
+                UPDATE work_orders
+                SET cancel_reason_code = NULL, cancel_reason_note = NULL,
+                    cancelled_at = NULL, cancelled_by = NULL
+                WHERE id = ?
+                "#,
+            )
+            .bind(&input.work_order_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+        }
+    }
+
+    // Save mileage to client on first completed order (if not a paired half order)
+    if next_status.eq_ignore_ascii_case("completed") || next_status.eq_ignore_ascii_case("picked_up") {
+        if let Some(mileage) = input.mileage {
+            // Get work order details to check if it's a half order
+            let wo_info = sqlx::query!(
+                r#"SELECT client_id, paired_order_id, delivery_size_label FROM work_orders WHERE id = ?"#,
+                input.work_order_id
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+
+            if let Some(wo) = wo_info {
+                let is_half_order = wo.delivery_size_label.as_deref() == Some("F-250 1/2") 
+                    || wo.paired_order_id.is_some();
+                
+                // Only save mileage to client if NOT a half order
+                if !is_half_order {
+                    let client_id = &wo.client_id;
+                    if !client_id.is_empty() {
+                        // Check if client already has default mileage
+                        let client_mileage = sqlx::query!(
+                            r#"SELECT default_mileage FROM clients WHERE id = ?"#,
+                            client_id
+                        )
+                        .fetch_optional(&mut *tx)
+                        .await
+                        .map_err(|e| e.to_string())?;
+
+                        // Only save if client doesn't already have a default mileage
+                        if let Some(cm) = client_mileage {
+                            if cm.default_mileage.is_none() {
+                                sqlx::query(
+                                    r#"UPDATE clients SET default_mileage = ?, updated_at = datetime('now') WHERE id = ?"#
+                                )
+                                .bind(mileage)
+                                .bind(client_id)
+                                .execute(&mut *tx)
+                                .await
+                                .map_err(|e| e.to_string())?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if current_status != next_status {
+        audit_change(
+            pool,
+            "update_work_order_status",
+            role_val,
+            actor_val,
+            "work_orders",
+            &input.work_order_id,
+            "status",
+            Some(current_status),
+            Some(next_status.clone()),
+        )
+        .await;
+    }
+    let allotment_warning = allotment_warning(&allotment_note);
+    if let Some((field, message, value)) = allotment_note {
+        audit_change(
+            pool,
+            "update_work_order_status",
+            role_val,
+            actor_val,
+            "work_orders",
+            &input.work_order_id,
+            field,
+            message,
+            Some(value),
+        )
+        .await;
+    }
+    if let Some(settled) = delivered_note {
+        audit_change(
+            pool,
+            "update_work_order_status",
+            role_val,
+            actor_val,
+            "work_orders",
+            &input.work_order_id,
+            "delivered_cords",
+            None,
+            Some(settled.to_string()),
+        )
+        .await;
+    }
+    let prev_mileage = existing.mileage;
+    if prev_mileage != input.mileage {
+        audit_change(
+            pool,
+            "update_work_order_status",
+            role_val,
+            actor_val,
+            "work_orders",
+            &input.work_order_id,
+            "mileage",
+            prev_mileage.map(|v| v.to_string()),
+            input.mileage.map(|v| v.to_string()),
+        )
+        .await;
+    }
+    let prev_hours = existing.work_hours;
+    if prev_hours != input.work_hours {
+        audit_change(
+            pool,
+            "update_work_order_status",
+            role_val,
+            actor_val,
+            "work_orders",
+            &input.work_order_id,
+            "work_hours",
+            prev_hours.map(|v| v.to_string()),
+            input.work_hours.map(|v| v.to_string()),
+        )
+        .await;
+    }
+    Ok(WorkOrderStatusResult { allotment_warning })
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct WorkOrderStatusHistoryRow {
+    id: String,
+    from_status: Option<String>,
+    to_status: String,
+    changed_at: String,
+    changed_by: Option<String>,
+    role: Option<String>,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AllowedStatusTransition {
+    status: WorkOrderStatus,
+    requires: Vec<Requirement>,
+}
+
+#[tauri::command]
+async fn list_work_order_status_history(
+    state: State<'_, AppState>,
+    work_order_id: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<WorkOrderStatusHistoryRow>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads or admins may view status history".to_string());
+    }
+    audit_db(
+        &state.pool,
+        "list_work_order_status_history",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+
+    sqlx::query_as::<_, WorkOrderStatusHistoryRow>(
+        r#"
+        SELECT id, from_status, to_status, changed_at, changed_by, role, reason
+        FROM work_order_status_history
+        WHERE work_order_id = ?
+        ORDER BY changed_at ASC, rowid ASC
+        "#,
+    )
+    .bind(&work_order_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// The statuses this user could move the order to next, so the UI only offers valid choices.
+#[tauri::command]
+async fn list_allowed_status_transitions(
+    state: State<'_, AppState>,
+    work_order_id: String,
+    role: Option<String>,
+    is_driver: Option<bool>,
+) -> Result<Vec<AllowedStatusTransition>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let status: String =
+        sqlx::query_scalar("SELECT status FROM work_orders WHERE id = ? AND is_deleted = 0")
+            .bind(&work_order_id)
+            .fetch_optional(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Work order not found or has been deleted".to_string())?;
+    let current = WorkOrderStatus::parse(&status)?;
+    let Some(status_actor) = StatusActor::from_role(&role_val, is_driver.unwrap_or(false)) else {
+        return Ok(Vec::new());
+    };
+    Ok(work_order_status::TRANSITIONS
+        .iter()
+        .filter(|t| t.from == current && t.actors.contains(&status_actor))
+        .map(|t| AllowedStatusTransition {
+            status: t.to,
+            requires: t.requires.to_vec(),
+        })
+        .collect())
+}
+
+/// The order-specific fields of a work order, sent in full like `ClientUpdateInput`.
+/// Client details follow `sync_client_to_open_orders`; status, mileage and hours go through
+/// `update_work_order_status`.
+#[derive(Debug, Deserialize)]
+struct WorkOrderUpdateInput {
+    id: String,
+    directions: Option<String>,
+    gate_combo: Option<String>,
+    notes: Option<String>,
+    other_heat_source_gas: bool,
+    other_heat_source_electric: bool,
+    other_heat_source_other: Option<String>,
+    wood_size_label: Option<String>,
+    wood_size_other: Option<String>,
+    delivery_size_label: Option<String>,
+    delivery_size_cords: Option<f64>,
+    pickup_delivery_type: Option<String>,
+    pickup_quantity_cords: Option<f64>,
+    pickup_length: Option<f64>,
+    pickup_width: Option<f64>,
+    pickup_height: Option<f64>,
+    pickup_units: Option<String>,
+    allotment_override_reason: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct WorkOrderEditRow {
+    client_id: String,
+    status: String,
+    scheduled_date: Option<String>,
+    directions: Option<String>,
+    gate_combo: Option<String>,
+    notes: Option<String>,
+    other_heat_source_gas: bool,
+    other_heat_source_electric: bool,
+    other_heat_source_other: Option<String>,
+    wood_size_label: Option<String>,
+    wood_size_other: Option<String>,
+    delivery_size_label: Option<String>,
+    delivery_size_cords: Option<f64>,
+    pickup_delivery_type: Option<String>,
+    pickup_quantity_cords: Option<f64>,
+    pickup_length: Option<f64>,
+    pickup_width: Option<f64>,
+    pickup_height: Option<f64>,
+    pickup_units: Option<String>,
+}
+
+/// Rewrites the editable fields of an order, moving its inventory reservation and re-checking
+/// the allotment when the cords change. Returns the (field, old, new) triples that changed.
+async fn edit_work_order(
+    pool: &SqlitePool,
+    mut input: WorkOrderUpdateInput,
+    role_val: &str,
+    actor_val: &str,
+    hipaa: bool,
+) -> Result<Vec<(&'static str, Option<String>, Option<String>)>, String> {
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(role_val) {
+        return Err("Only staff, leads or admins can edit work orders".to_string());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let existing = sqlx::query_as::<_, WorkOrderEditRow>(
+        r#"
+        SELECT
+            client_id, status, scheduled_date, directions, gate_combo, notes,
+            other_heat_source_gas, other_heat_source_electric, other_heat_source_other,
+            wood_size_label, wood_size_other, delivery_size_label, delivery_size_cords,
+            pickup_delivery_type, pickup_quantity_cords,
+            pickup_length, pickup_width, pickup_height, pickup_units
+        FROM work_orders
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(&input.id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Work order not found or has been deleted".to_string())?;
+
+    // The list hands these callers NULL for what they cannot see; keep the stored values.
+    let scope = ListScope::for_work_orders(role_val, actor_val, hipaa, false);
+    if scope.hide_gate {
+        input.gate_combo = existing.gate_combo.clone();
+    }
+    if scope.hide_notes {
+        input.notes = existing.notes.clone();
+    }
+
+    let status = WorkOrderStatus::parse(&existing.status)?;
+    let closed = matches!(
+        status,
+        WorkOrderStatus::Completed | WorkOrderStatus::PickedUp | WorkOrderStatus::Cancelled
+    );
+    if closed && role_val != "admin" {
+        return Err(format!(
+            "Only admins can edit a {} work order",
+            status.as_str()
+        ));
+    }
+
+    let (old_cords, new_cords) = if status == WorkOrderStatus::PickedUp {
+        (existing.pickup_quantity_cords, input.pickup_quantity_cords)
+    } else {
+        (existing.delivery_size_cords, input.delivery_size_cords)
+    };
+    let old_cords = old_cords.unwrap_or(0.0);
+    let new_cords = new_cords.unwrap_or(0.0);
+    let mut allotment_note = None;
+    if old_cords != new_cords {
+        if matches!(
+            status,
+            WorkOrderStatus::Completed | WorkOrderStatus::PickedUp
+        ) {
+            return Err(
+                "Wood for this order has already left inventory; record the correction as an inventory adjustment"
+                    .to_string(),
+            );
+        }
+        if new_cords > old_cords && counts_toward_allotment(&existing.status) {
+            let check = check_allotment(
+                &mut tx,
+                &existing.client_id,
+                Some(&input.id),
+                existing.scheduled_date.as_deref(),
+                new_cords,
+            )
+            .await?;
+            allotment_note =
+                resolve_allotment(&check, role_val, input.allotment_override_reason.as_deref())?
+                    .map(|(field, value)| (field, check.message.clone(), value));
+        }
+        // Release the old reservation and take the new one, so the availability check sees
+        // this order's own cords as free.
+        if matches!(
+            status,
+            WorkOrderStatus::Scheduled | WorkOrderStatus::InProgress | WorkOrderStatus::Delivered
+        ) {
+            adjust_inventory_for_transition_tx(&mut tx, &existing.status, "draft", old_cords)
+                .await?;
+            adjust_inventory_for_transition_tx(&mut tx, "draft", &existing.status, new_cords)
+                .await?;
+        }
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET directions = ?,
+            gate_combo = ?,
+            notes = ?,
+            other_heat_source_gas = ?,
+            other_heat_source_electric = ?,
+            other_heat_source_other = ?,
+            wood_size_label = ?,
+            wood_size_other = ?,
+            delivery_size_label = ?,
+            delivery_size_cords = ?,
+            pickup_delivery_type = ?,
+            pickup_quantity_cords = ?,
+            pickup_length = ?,
+            pickup_width = ?,
+            pickup_height = ?,
+            pickup_units = ?,
+            updated_at = datetime('now'),
+            version = version + 1
+        WHERE id = ?
+        "#,
+    )
+    .bind(&input.directions)
+    .bind(&input.gate_combo)
+    .bind(&input.notes)
+    .bind(input.other_heat_source_gas)
+    .bind(input.other_heat_source_electric)
+    .bind(&input.other_heat_source_other)
+    .bind(&input.wood_size_label)
+    .bind(&input.wood_size_other)
+    .bind(&input.delivery_size_label)
+    .bind(input.delivery_size_cords)
+    .bind(&input.pickup_delivery_type)
+    .bind(input.pickup_quantity_cords)
+    .bind(input.pickup_length)
+    .bind(input.pickup_width)
+    .bind(input.pickup_height)
+    .bind(&input.pickup_units)
+    .bind(&input.id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    let number = |v: Option<f64>| v.map(|v| v.to_string());
+    let flag = |v: bool| Some(v.to_string());
+    let mut changes = vec![
+        ("directions", existing.directions, input.directions),
+        ("gate_combo", existing.gate_combo, input.gate_combo),
+        ("notes", existing.notes, input.notes),
+        (
+            "other_heat_source_gas",
+            flag(existing.other_heat_source_gas),
+            flag(input.other_heat_source_gas),
+        ),
+        (
+            "other_heat_source_electric",
+            flag(existing.other_heat_source_electric),
+            flag(input.other_heat_source_electric),
+        ),
+        (
+            "other_heat_source_other",
+            existing.other_heat_source_other,
+            input.other_heat_source_other,
+        ),
+        (
+            "wood_size_label",
+            existing.wood_size_label,
+            input.wood_size_label,
+        ),
+        (
+            "wood_size_other",
+            existing.wood_size_other,
+            input.wood_size_other,
+        ),
+        (
+            "delivery_size_label",
+            existing.delivery_size_label,
+            input.delivery_size_label,
+        ),
+        (
+            "delivery_size_cords",
+            number(existing.delivery_size_cords),
+            number(input.delivery_size_cords),
+        ),
+        (
+            "pickup_delivery_type",
+            existing.pickup_delivery_type,
+            input.pickup_delivery_type,
+        ),
+        (
+            "pickup_quantity_cords",
+            number(existing.pickup_quantity_cords),
+            number(input.pickup_quantity_cords),
+        ),
+        (
+            "pickup_length",
+            number(existing.pickup_length),
+            number(input.pickup_length),
+        ),
+        (
+            "pickup_width",
+            number(existing.pickup_width),
+            number(input.pickup_width),
+        ),
+        (
+            "pickup_height",
+            number(existing.pickup_height),
+            number(input.pickup_height),
+        ),
+        ("pickup_units", existing.pickup_units, input.pickup_units),
+    ];
+    changes.retain(|(_, old, new)| old != new);
+    if let Some((field, message, value)) = allotment_note {
+        changes.push((field, message, Some(value)));
+    }
+    for (field, old, new) in &changes {
+        audit_change(
+            pool,
+            "update_work_order",
+            role_val,
+            actor_val,
+            "work_orders",
+            &input.id,
+            field,
+            old.clone(),
+            new.clone(),
+        )
+        .await;
+    }
+    Ok(changes)
+}
+
+#[tauri::command]
+async fn update_work_order(
+    state: State<'_, AppState>,
+    input: WorkOrderUpdateInput,
+    role: Option<String>,
+    actor: Option<String>,
+    hipaa_certified: Option<bool>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "update_work_order", &role_val, &actor_val).await;
+    edit_work_order(
+        &state.pool,
+        input,
+        &role_val,
+        &actor_val,
+        hipaa_certified.unwrap_or(false),
+    )
+    .await?;
+    Ok(())
+}
+
+const CANCEL_REASON_CODES: [&str; 7] = [
+    "client_request",
+    "no_longer_eligible",
+    "unreachable",
+    "duplicate",
+    "weather",
+    "out_of_stock",
+    "other",
+];
+
+#[derive(Debug, Deserialize)]
+struct CancelWorkOrderInput {
+    work_order_id: String,
+    reason_code: String,
+    // Required when the reason code is "other".
+    reason_note: Option<String>,
+    return_to_waitlist: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct CancelWorkOrderResult {
+    unpaired_order_id: Option<String>,
+    waitlist_entry_id: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct CancelOrderRow {
+    client_id: String,
+    status: String,
+    delivery_size_cords: Option<f64>,
+    pickup_quantity_cords: Option<f64>,
+}
+
+/// Takes a cancelled order off the calendar and out of its pairing. Returns the former partner.
+async fn detach_cancelled_order(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+) -> Result<Option<String>, String> {
+    sqlx::query(
+        r#"
+        UPDATE delivery_events
+        SET is_deleted = 1, updated_at = datetime('now')
+        WHERE work_order_id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(work_order_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    unpair_order_tx(conn, work_order_id).await
+}
+
+const HALF_LOAD_LABEL: &str = "F-250 1/2";
+
+/// The form has stored both "F-250 1/2" and "Ford F-250 1/2" over time.
+fn is_half_load(delivery_size_label: Option<&str>) -> bool {
+    delivery_size_label.is_some_and(|label| label.contains(HALF_LOAD_LABEL))
+}
+
+fn is_closed_status(status: &str) -> bool {
+    matches!(
+        status.to_lowercase().as_str(),
+        "completed" | "picked_up" | "cancelled"
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct PairWorkOrdersInput {
+    work_order_id: String,
+    partner_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PairIssue {
+    work_order_id: String,
+    paired_order_id: String,
+    issue: &'static str,
+    detail: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct PairCandidateRow {
+    id: String,
+    client_id: String,
+    status: String,
+    scheduled_date: Option<String>,
+    delivery_size_label: Option<String>,
+    paired_order_id: Option<String>,
+}
+
+/// The order's partner, if it has one that is still open.
+async fn open_partner_id(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+) -> Result<Option<String>, String> {
+    sqlx::query_scalar(
+        r#"
+        SELECT p.id
+        FROM work_orders w
+        JOIN work_orders p ON p.id = w.paired_order_id
+        WHERE w.id = ?
+          AND p.id <> w.id
+          AND p.is_deleted = 0
+          AND lower(p.status) NOT IN ('completed', 'picked_up', 'cancelled')
+        "#,
+    )
+    .bind(work_order_id)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Gives `to_id` the same drivers as `from_id`; helpers and leads are left alone.
+async fn share_pair_drivers(
+    conn: &mut sqlx::SqliteConnection,
+    from_id: &str,
+    to_id: &str,
+    actor_val: &str,
+    audits: &mut PendingAudits,
+) -> Result<(), String> {
+    let before = load_assignment_summary(&mut *conn, to_id).await?;
+    sqlx::query(
+        "DELETE FROM work_order_assignments WHERE work_order_id = ? AND assignment_role = 'driver'",
+    )
+    .bind(to_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        INSERT INTO work_order_assignments (
+            id, work_order_id, user_id, assignment_role, created_by
+        )
+        SELECT lower(hex(randomblob(16))), ?, user_id, 'driver', ?
+        FROM work_order_assignments
+        WHERE work_order_id = ? AND assignment_role = 'driver'
+        ON CONFLICT (work_order_id, user_id) DO UPDATE SET assignment_role = 'driver'
+        "#,
+    )
+    .bind(to_id)
+    .bind(actor_val)
+    .bind(from_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let after = load_assignment_summary(&mut *conn, to_id).await?;
+    if before == after {
+        return Ok(());
+    }
+    audits.push((
+        to_id.to_string(),
+        "assignments",
+        Some(before.join(", ")),
+        Some(after.join(", ")),
+    ));
+
+    // Rebuild the partner's display list: its assigned users, then any free-text helpers.
+    let mut names: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT u.name FROM work_order_assignments a
+        JOIN users u ON u.id = a.user_id
+        WHERE a.work_order_id = ?
+        ORDER BY a.assignment_role, u.name
+        "#,
+    )
+    .bind(to_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    let helpers: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT trim(j.value)
+        FROM work_orders w
+        JOIN json_each(CASE WHEN json_valid(w.assignees_json) THEN w.assignees_json ELSE '[]' END) j
+        WHERE w.id = ?
+          AND trim(j.value) != ''
+          AND NOT EXISTS (
+            SELECT 1 FROM users u
+            WHERE u.is_deleted = 0
+              AND (
+                lower(u.name) = lower(trim(j.value))
+                OR EXISTS (
+                    SELECT 1 FROM auth_users au
+                    WHERE au.user_id = u.id AND au.is_deleted = 0
+                      AND lower(au.username) = lower(trim(j.value))
+                )
+              )
+          )
+        "#,
+    )
+    .bind(to_id)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    names.extend(helpers);
+    let assignees_json = serde_json::to_string(&names).map_err(|e| e.to_string())?;
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET assignees_json = ?, updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&assignees_json)
+    .bind(to_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    sync_event_assignees(&mut *conn, to_id).await
+}
+
+/// Pairs two open half loads for different clients, then puts both on the same date (the first
+/// order's, or the partner's if the first has none) and the same driver.
+async fn link_pair_tx(
+    tx: &mut Transaction<'_, Sqlite>,
+    work_order_id: &str,
+    partner_id: &str,
+    role_val: &str,
+    actor_val: &str,
+    audits: &mut PendingAudits,
+) -> Result<(), String> {
+    if work_order_id == partner_id {
+        return Err("A work order cannot be paired with itself".to_string());
+    }
+    let mut orders = Vec::with_capacity(2);
+    for id in [work_order_id, partner_id] {
+        let order = sqlx::query_as::<_, PairCandidateRow>(
+            r#"
+            SELECT id, client_id, status, scheduled_date, delivery_size_label, paired_order_id
+            FROM work_orders
+            WHERE id = ? AND is_deleted = 0
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Work order {} not found or has been deleted", id))?;
+        orders.push(order);
+    }
+    let (order, partner) = (&orders[0], &orders[1]);
+    for (side, other) in [(order, partner), (partner, order)] {
+        if !is_half_load(side.delivery_size_label.as_deref()) {
+            return Err("Only half F-250 orders can be paired".to_string());
+        }
+        if is_closed_status(&side.status) {
+            return Err("Completed, picked-up or cancelled orders cannot be paired".to_string());
+        }
+        let current = side.paired_order_id.as_deref().filter(|p| !p.is_empty());
+        if current.is_some_and(|p| p != other.id) {
+            return Err(format!(
+                "Work order {} is already paired with another order",
+                side.id
+            ));
+        }
+    }
+    if order.client_id == partner.client_id {
+        return Err("The two halves of a load must be for different clients".to_string());
+    }
+
+    for (side, other) in [(order, partner), (partner, order)] {
+        if side.paired_order_id.as_deref() == Some(other.id.as_str()) {
+            continue;
+        }
+        sqlx::query(
+            r#"
+            UPDATE work_orders
+            SET paired_order_id = ?, updated_at = datetime('now'), version = version + 1
+            WHERE id = ?
+            "#,
+        )
+        .bind(&other.id)
+        .bind(&side.id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        audits.push((
+            side.id.clone(),
+            "paired_order_id",
+            side.paired_order_id.clone(),
+            Some(other.id.clone()),
+        ));
+    }
+
+    let shared_date = order
+        .scheduled_date
+        .clone()
+        .or_else(|| partner.scheduled_date.clone());
+    if let Some(date) = &shared_date {
+        for side in [order, partner] {
+            if side.scheduled_date.as_ref() != Some(date) {
+                schedule_order_tx(tx, &side.id, Some(date), role_val, actor_val, audits).await?;
+            }
+        }
+    }
+
+    let order_drivers: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM work_order_assignments
+        WHERE work_order_id = ? AND assignment_role = 'driver'
+        "#,
+    )
+    .bind(&order.id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let (from, to) = if order_drivers > 0 {
+        (&order.id, &partner.id)
+    } else {
+        (&partner.id, &order.id)
+    };
+    share_pair_drivers(tx, from, to, actor_val, audits).await
+}
+
+/// Clears the order's pairing, and the partner's if it points back. Returns the former partner.
+async fn unpair_order_tx(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+) -> Result<Option<String>, String> {
+    let partner: Option<Option<String>> =
+        sqlx::query_scalar("SELECT paired_order_id FROM work_orders WHERE id = ?")
+            .bind(work_order_id)
+            .fetch_optional(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    let Some(partner) = partner.flatten().filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET paired_order_id = NULL, updated_at = datetime('now'), version = version + 1
+        WHERE id = ? OR (id = ? AND paired_order_id = ?)
+        "#,
+    )
+    .bind(work_order_id)
+    .bind(&partner)
+    .bind(work_order_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(Some(partner))
+}
+
+async fn pair_orders(
+    pool: &SqlitePool,
+    input: &PairWorkOrdersInput,
+    role_val: &str,
+    actor_val: &str,
+) -> Result<(), String> {
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(role_val) {
+        return Err("Only staff, leads or admins can pair work orders".to_string());
+    }
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut audits = PendingAudits::new();
+    link_pair_tx(
+        &mut tx,
+        &input.work_order_id,
+        &input.partner_id,
+        role_val,
+        actor_val,
+        &mut audits,
+    )
+    .await?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    flush_audits(pool, "pair_work_orders", role_val, actor_val, audits).await;
+    Ok(())
+}
+
+async fn unpair_orders(
+    pool: &SqlitePool,
+    work_order_id: &str,
+    role_val: &str,
+    actor_val: &str,
+) -> Result<String, String> {
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(role_val) {
+        return Err("Only staff, leads or admins can unpair work orders".to_string());
+    }
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let partner = unpair_order_tx(&mut tx, work_order_id)
+        .await?
+        .ok_or_else(|| "Work order is not paired".to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+    let audits = vec![
+        (
+            work_order_id.to_string(),
+            "paired_order_id",
+            Some(partner.clone()),
+            None,
+        ),
+        (
+            partner.clone(),
+            "paired_order_id",
+            Some(work_order_id.to_string()),
+            None,
+        ),
+    ];
+    flush_audits(pool, "unpair_work_order", role_val, actor_val, audits).await;
+    Ok(partner)
+}
+
+/// Pairs that no longer hold together: dangling or one-sided links, and open pairs whose halves
+/// have drifted onto different dates or drivers. Pair-wide problems are reported once per pair.
+async fn find_pair_issues(pool: &SqlitePool) -> Result<Vec<PairIssue>, String> {
+    #[derive(sqlx::FromRow)]
+    struct PairedRow {
+        id: String,
+        paired_order_id: String,
+        client_id: String,
+        status: String,
+        scheduled_date: Option<String>,
+        delivery_size_label: Option<String>,
+        drivers: Option<String>,
+        partner_id: Option<String>,
+        partner_deleted: Option<bool>,
+        partner_paired_order_id: Option<String>,
+        partner_client_id: Option<String>,
+        partner_status: Option<String>,
+        partner_scheduled_date: Option<String>,
+        partner_drivers: Option<String>,
+    }
+
+    let rows = sqlx::query_as::<_, PairedRow>(
+        r#"
+        SELECT
+            w.id, w.paired_order_id, w.client_id, w.status, w.scheduled_date,
+            w.delivery_size_label,
+            (SELECT group_concat(user_id) FROM (
+                SELECT user_id FROM work_order_assignments
+                WHERE work_order_id = w.id AND assignment_role = 'driver'
+                ORDER BY user_id
+            )) AS drivers,
+            p.id AS partner_id,
+            p.is_deleted AS partner_deleted,
+            p.paired_order_id AS partner_paired_order_id,
+            p.client_id AS partner_client_id,
+            p.status AS partner_status,
+            p.scheduled_date AS partner_scheduled_date,
+            (SELECT group_concat(user_id) FROM (
+                SELECT user_id FROM work_order_assignments
+                WHERE work_order_id = p.id AND assignment_role = 'driver'
+                ORDER BY user_id
+            )) AS partner_drivers
+        FROM work_orders w
+        LEFT JOIN work_orders p ON p.id = w.paired_order_id
+        WHERE w.is_deleted = 0
+          AND COALESCE(w.paired_order_id, '') <> ''
+        ORDER BY w.id
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut issues = Vec::new();
+    for row in rows {
+        let mut flag = |issue: &'static str, detail: String| {
+            issues.push(PairIssue {
+                work_order_id: row.id.clone(),
+                paired_order_id: row.paired_order_id.clone(),
+                issue,
+                detail,
+            })
+        };
+        if row.paired_order_id == row.id {
+            flag(
+                "paired_with_itself",
+                "The order is paired with itself".to_string(),
+            );
+            continue;
+        }
+        if row.partner_id.is_none() {
+            flag(
+                "missing_partner",
+                "The partner order does not exist".to_string(),
+            );
+            continue;
+        }
+        if row.partner_deleted.unwrap_or(false) {
+            flag(
+                "partner_deleted",
+                "The partner order has been deleted".to_string(),
+            );
+            continue;
+        }
+        if row.partner_paired_order_id.as_deref() != Some(row.id.as_str()) {
+            flag(
+                "one_sided",
+                match &row.partner_paired_order_id {
+                    Some(other) if !other.is_empty() => {
+                        format!("The partner is paired with {} instead", other)
+                    }
+                    _ => "The partner is not paired back".to_string(),
+                },
+            );
+            continue;
+        }
+        if !is_half_load(row.delivery_size_label.as_deref()) {
+            flag(
+                "not_half_load",
+                format!(
+                    "Delivery size is {}",
+                    row.delivery_size_label.as_deref().unwrap_or("not set")
+                ),
+            );
+        }
+        if row.status.eq_ignore_ascii_case("cancelled") {
+            flag(
+                "cancelled_but_paired",
+                "A cancelled order is still paired".to_string(),
+            );
+        }
+        // The remaining checks cover the pair as a whole; report them from one side only.
+        if row.id > row.paired_order_id {
+            continue;
+        }
+        if row.partner_client_id.as_deref() == Some(row.client_id.as_str()) {
+            flag(
+                "same_client",
+                "Both halves are for the same client".to_string(),
+            );
+        }
+        let partner_status = row.partner_status.as_deref().unwrap_or_default();
+        if is_closed_status(&row.status) || is_closed_status(partner_status) {
+            continue;
+        }
+        if row.scheduled_date != row.partner_scheduled_date {
+            flag(
+                "date_mismatch",
+                format!(
+                    "Scheduled for {} and {}",
+                    row.scheduled_date.as_deref().unwrap_or("no date"),
+                    row.partner_scheduled_date.as_deref().unwrap_or("no date")
+                ),
+            );
+        }
+        if row.drivers != row.partner_drivers {
+            flag(
+                "driver_mismatch",
+                "The two halves have different drivers".to_string(),
+            );
+        }
+    }
+    Ok(issues)
+}
+
+#[tauri::command]
+async fn pair_work_orders(
+    state: State<'_, AppState>,
+    input: PairWorkOrdersInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "pair_work_orders", &role_val, &actor_val).await;
+    pair_orders(&state.pool, &input, &role_val, &actor_val).await
+}
+
+/// Returns the id of the former partner.
+#[tauri::command]
+async fn unpair_work_order(
+    state: State<'_, AppState>,
+    work_order_id: String,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "unpair_work_order", &role_val, &actor_val).await;
+    unpair_orders(&state.pool, &work_order_id, &role_val, &actor_val).await
+}
+
+#[tauri::command]
+async fn list_pair_integrity_issues(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<Vec<PairIssue>, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(
+        &state.pool,
+        "list_pair_integrity_issues",
+        &role_val,
+        &actor_val,
+    )
+    .await;
+    if role_val != "admin" && role_val != "lead" && !is_staff_like(&role_val) {
+        return Err("Only staff, leads or admins can review paired orders".to_string());
+    }
+    find_pair_issues(&state.pool).await
+}
+
+async fn cancel_order(
+    pool: &SqlitePool,
+    input: CancelWorkOrderInput,
+    role_val: &str,
+    actor_val: &str,
+) -> Result<CancelWorkOrderResult, String> {
+    let reason_code = input.reason_code.trim().to_lowercase();
+    if !CANCEL_REASON_CODES.contains(&reason_code.as_str()) {
+        return Err(format!(
+            "Unknown cancellation reason '{}'; expected one of {}",
+            input.reason_code,
+            CANCEL_REASON_CODES.join(", ")
+        ));
+    }
+    let reason_note = input
+        .reason_note
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty());
+    if reason_code == "other" && reason_note.is_none() {
+        return Err("Describe the reason when cancelling for 'other'".to_string());
+    }
+    let actor_kind = StatusActor::from_role(role_val, false)
+        .ok_or_else(|| "Only staff, leads or admins can cancel work orders".to_string())?;
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let existing = sqlx::query_as::<_, CancelOrderRow>(
+        r#"
+        SELECT client_id, status, delivery_size_cords, pickup_quantity_cords
+        FROM work_orders
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(&input.work_order_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Work order not found or has been deleted".to_string())?;
+    let current = WorkOrderStatus::parse(&existing.status)?;
+    work_order_status::authorize_transition(current, WorkOrderStatus::Cancelled, actor_kind)?;
+    let cancelled = WorkOrderStatus::Cancelled.as_str();
+
+    sqlx::query(
+        r#"
+        UPDATE work_orders
+        SET status = ?,
+            cancel_reason_code = ?,
+            cancel_reason_note = ?,
+            cancelled_at = datetime('now'),
+            cancelled_by = ?,
+            updated_at = datetime('now'),
+            version = version + 1
+        WHERE id = ?
+        "#,
+    )
+    .bind(cancelled)
+    .bind(&reason_code)
+    .bind(&reason_note)
+    .bind(actor_val)
+    .bind(&input.work_order_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let cords = existing.delivery_size_cords.unwrap_or(0.0);
+    adjust_inventory_for_transition_tx(&mut tx, &existing.status, cancelled, cords).await?;
+    let history_reason = match &reason_note {
+        Some(note) => format!("{}: {}", reason_code, note),
+        None => reason_code.clone(),
+    };
+    record_status_change(
+        &mut tx,
+        &input.work_order_id,
+        Some(&existing.status),
+        cancelled,
+        role_val,
+        actor_val,
+        Some(&history_reason),
+    )
+    .await?;
+    let unpaired_order_id = detach_cancelled_order(&mut tx, &input.work_order_id).await?;
+
+    let mut waitlist_entry_id = None;
+    if input.return_to_waitlist.unwrap_or(false) {
+        let approval: Option<String> = sqlx::query_scalar(
+            "SELECT approval_status FROM clients WHERE id = ? AND is_deleted = 0",
+        )
+        .bind(&existing.client_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        if !approval.is_some_and(|s| s.eq_ignore_ascii_case("approved")) {
+            return Err("Only approved clients can join the waitlist".to_string());
+        }
+        let requested = existing
+            .delivery_size_cords
+            .or(existing.pickup_quantity_cords)
+            .filter(|c| *c > 0.0)
+            .ok_or_else(|| {
+                "The order has no cord amount to put back on the waitlist".to_string()
+            })?;
+        // A household promoted from the waitlist keeps its flags and original place in line.
+        let id = Uuid::new_v4().to_string();
+        let inserted = sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO waitlist_entries (
+                id, client_id, requested_cords, priority_flags_json, distance_miles, notes,
+                requested_at
+            )
+            SELECT ?, ?, ?,
+                   COALESCE(prev.priority_flags_json, '[]'),
+                   prev.distance_miles,
+                   ?,
+                   COALESCE(prev.requested_at, datetime('now'))
+            FROM (SELECT 1) AS one
+            LEFT JOIN (
+                SELECT priority_flags_json, distance_miles, requested_at
+                FROM waitlist_entries
+                WHERE work_order_id = ? AND is_deleted = 0
+                ORDER BY requested_at ASC
+                LIMIT 1
+            ) AS prev ON 1 = 1
+            "#,
+        )
+        .bind(&id)
+        .bind(&existing.client_id)
+        .bind(requested)
+        .bind(format!(
+            "Returned after cancelled order ({})",
+            history_reason
+        ))
+        .bind(&input.work_order_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        if inserted.rows_affected() > 0 {
+            waitlist_entry_id = Some(id);
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    audit_change(
+        pool,
+        "cancel_work_order",
+        role_val,
+        actor_val,
+        "work_orders",
+        &input.work_order_id,
+        "status",
+        Some(existing.status),
+        Some(cancelled.to_string()),
+    )
+    .await;
+    audit_change(
+        pool,
+        "cancel_work_order",
+        role_val,
+        actor_val,
+        "work_orders",
+        &input.work_order_id,
+        "cancel_reason_code",
+        None,
+        Some(history_reason),
+    )
+    .await;
+    if let Some(partner) = &unpaired_order_id {
+        for (id, other) in [
+            (&input.work_order_id, partner),
+            (partner, &input.work_order_id),
+        ] {
+            audit_change(
+                pool,
+                "cancel_work_order",
+                role_val,
+                actor_val,
+                "work_orders",
+                id,
+                "paired_order_id",
+                Some(other.clone()),
+                None,
+            )
+            .await;
+        }
+    }
+    if let Some(entry_id) = &waitlist_entry_id {
+        audit_change(
+            pool,
+            "cancel_work_order",
+            role_val,
+            actor_val,
+            "waitlist_entries",
+            entry_id,
+            "status",
+            None,
+            Some("waiting".to_string()),
+        )
+        .await;
+    }
+    Ok(CancelWorkOrderResult {
+        unpaired_order_id,
+        waitlist_entry_id,
+    })
+}
+
+#[tauri::command]
+async fn cancel_work_order(
+    state: State<'_, AppState>,
+    input: CancelWorkOrderInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<CancelWorkOrderResult, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "cancel_work_order", &role_val, &actor_val).await;
+    cancel_order(&state.pool, input, &role_val, &actor_val).await
+}
+
+#[derive(Debug, Deserialize)]
+struct DeliveredCordsInput {
+    delivery_event_id: String,
+    delivered_cords: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BackorderInput {
+    work_order_id: String,
+    scheduled_date: Option<String>,
+    allotment_override_reason: Option<String>,
+}
+
+/// Total the crew recorded across the order's delivery events; None when nothing was recorded.
+async fn recorded_delivered_cords(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+) -> Result<Option<f64>, String> {
+    sqlx::query_scalar(
+        r#"
+        SELECT SUM(delivered_cords)
+        FROM delivery_events
+        WHERE work_order_id = ? AND is_deleted = 0 AND delivered_cords IS NOT NULL
+        "#,
+    )
+    .bind(work_order_id)
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Stores what a completed delivery is settled at: the recorded quantity, or the full order.
+async fn settle_delivered_cords(
+    conn: &mut sqlx::SqliteConnection,
+    work_order_id: &str,
+    ordered_cords: f64,
+) -> Result<f64, String> {
+    let settled = recorded_delivered_cords(&mut *conn, work_order_id)
+        .await?
+        .unwrap_or(ordered_cords);
+    sqlx::query("UPDATE work_orders SET delivered_cords = ? WHERE id = ?")
+        .bind(settled)
+        .bind(work_order_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(settled)
+}
+
+async fn record_delivery_quantity(
+    pool: &SqlitePool,
+    input: &DeliveredCordsInput,
+    role_val: &str,
+    actor_val: &str,
+    is_driver: bool,
+) -> Result<(), String> {
+    #[derive(sqlx::FromRow)]
+    struct EventOrderRow {
+        work_order_id: Option<String>,
+        delivered_cords: Option<f64>,
+        status: Option<String>,
+        delivery_size_cords: Option<f64>,
+    }
+
+    StatusActor::from_role(role_val, is_driver)
+        .ok_or_else(|| "Only drivers, staff, leads or admins can record deliveries".to_string())?;
+    if !input.delivered_cords.is_finite() || input.delivered_cords < 0.0 {
+        return Err("Delivered cords must be zero or more".to_string());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let row = sqlx::query_as::<_, EventOrderRow>(
+        r#"
+        SELECT e.work_order_id, e.delivered_cords, w.status, w.delivery_size_cords
+        FROM delivery_events e
+        LEFT JOIN work_orders w ON w.id = e.work_order_id AND w.is_deleted = 0
+        WHERE e.id = ? AND e.is_deleted = 0
+        "#,
+    )
+    .bind(&input.delivery_event_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Delivery event not found or has been deleted".to_string())?;
+    let (Some(work_order_id), Some(status)) = (&row.work_order_id, &row.status) else {
+        return Err("The delivery event is not linked to a work order".to_string());
+    };
+    if role_val != "admin" && role_val != "lead" {
+        let assigned: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1
+                FROM work_order_assignments a
+                JOIN auth_users au ON au.user_id = a.user_id AND au.is_deleted = 0
+                WHERE a.work_order_id = ? AND lower(au.username) = lower(?)
+            )
+            "#,
+        )
+        .bind(work_order_id)
+        .bind(actor_val)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+        if !assigned {
+            return Err("Only the crew assigned to this delivery can record its cords".to_string());
+        }
+    }
+    if is_closed_status(status) {
+        return Err(format!(
+            "The work order is {}; its delivery has already been settled",
+            status
+        ));
+    }
+    let other_events: f64 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(delivered_cords), 0.0)
+        FROM delivery_events
+        WHERE work_order_id = ? AND id <> ? AND is_deleted = 0
+        "#,
+    )
+    .bind(work_order_id)
+    .bind(&input.delivery_event_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    let ordered = row.delivery_size_cords.unwrap_or(0.0);
+    if other_events + input.delivered_cords > ordered + f64::EPSILON {
+        return Err(format!(
+            "Only {} cords were ordered; {} already recorded on other trips",
+            ordered, other_events
+        ));
+    }
+    sqlx::query(
+        r#"
+        UPDATE delivery_events
+        SET delivered_cords = ?, updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(input.delivered_cords)
+    .bind(&input.delivery_event_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    if row.delivered_cords != Some(input.delivered_cords) {
+        audit_change(
+            pool,
+            "record_delivered_cords",
+            role_val,
+            actor_val,
+            "delivery_events",
+            &input.delivery_event_id,
+            "delivered_cords",
+            row.delivered_cords.map(|v| v.to_string()),
+            Some(input.delivered_cords.to_string()),
+        )
+        .await;
+    }
+    Ok(())
+}
+
+/// Opens a new order for the balance of a completed short delivery, linked back to it.
+async fn create_backorder_order(
+    pool: &SqlitePool,
+    input: BackorderInput,
+    role_val: &str,
+    actor_val: &str,
+) -> Result<String, String> {
+    if !can_create_work_orders(role_val) {
+        return Err("Only staff, leads or admins may create work orders".to_string());
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct ShortOrderRow {
+        client_id: String,
+        status: String,
+        scheduled_date: Option<String>,
+        delivery_size_cords: Option<f64>,
+        delivered_cords: Option<f64>,
+        other_heat_source_gas: bool,
+        other_heat_source_electric: bool,
+        other_heat_source_other: Option<String>,
+        wood_size_label: Option<String>,
+        wood_size_other: Option<String>,
+    }
+
+    let parent = sqlx::query_as::<_, ShortOrderRow>(
+        r#"
+        SELECT client_id, status, scheduled_date, delivery_size_cords, delivered_cords,
+               other_heat_source_gas, other_heat_source_electric, other_heat_source_other,
+               wood_size_label, wood_size_other
+        FROM work_orders
+        WHERE id = ? AND is_deleted = 0
+        "#,
+    )
+    .bind(&input.work_order_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Work order not found or has been deleted".to_string())?;
+    if !parent.status.eq_ignore_ascii_case("completed") {
+        return Err("Only completed deliveries can be backordered".to_string());
+    }
+    let ordered = parent.delivery_size_cords.unwrap_or(0.0);
+    let delivered = parent.delivered_cords.unwrap_or(ordered);
+    // Cords are entered to the hundredth; keep float noise out of the new order.
+    let remaining = ((ordered - delivered) * 100.0).round() / 100.0;
+    if remaining <= 0.0 {
+        return Err("The order was delivered in full; there is nothing to backorder".to_string());
+    }
+    let existing: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM work_orders
+        WHERE backorder_of_id = ? AND is_deleted = 0 AND lower(status) <> 'cancelled'
+        "#,
+    )
+    .bind(&input.work_order_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if existing > 0 {
+        return Err("This order already has an open backorder".to_string());
+    }
+
+    let status = if input.scheduled_date.is_some() {
+        WorkOrderStatus::Scheduled
+    } else {
+        WorkOrderStatus::Received
+    };
+    let mut order = build_client_work_order(
+        pool,
+        ClientWorkOrderInput {
+            client_id: parent.client_id,
+            scheduled_date: input.scheduled_date,
+            status: Some(status.as_str().to_string()),
+            work_hours: None,
+            other_heat_source_gas: Some(parent.other_heat_source_gas),
+            other_heat_source_electric: Some(parent.other_heat_source_electric),
+            other_heat_source_other: parent.other_heat_source_other,
+            notes: Some(format!(
+                "Backorder: {} of {} cords still owed from the {} delivery",
+                remaining,
+                ordered,
+                parent.scheduled_date.as_deref().unwrap_or("unscheduled")
+            )),
+            wood_size_label: parent.wood_size_label,
+            wood_size_other: parent.wood_size_other,
+            delivery_size_label: None,
+            delivery_size_cords: Some(remaining),
+            pickup_delivery_type: None,
+            pickup_quantity_cords: None,
+            pickup_length: None,
+            pickup_width: None,
+            pickup_height: None,
+            pickup_units: None,
+            assignees_json: None,
+            created_by_user_id: None,
+            created_by_display: Some(actor_val.to_string()),
+            paired_order_id: None,
+            allotment_override_reason: input.allotment_override_reason,
+        },
+    )
+    .await?;
+    order.backorder_of_id = Some(input.work_order_id.clone());
+    let id = insert_work_order(pool, order, role_val, actor_val, "create_backorder")
+        .await?
+        .id;
+    audit_change(
+        pool,
+        "create_backorder",
+        role_val,
+        actor_val,
+        "work_orders",
+        &input.work_order_id,
+        "backorder_id",
+        None,
+        Some(id.clone()),
+    )
+    .await;
+    Ok(id)
+}
+
+#[tauri::command]
+async fn record_delivered_cords(
+    state: State<'_, AppState>,
+    input: DeliveredCordsInput,
+    role: Option<String>,
+    actor: Option<String>,
+    is_driver: Option<bool>,
+) -> Result<(), String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "record_delivered_cords", &role_val, &actor_val).await;
+    record_delivery_quantity(
+        &state.pool,
+        &input,
+        &role_val,
+        &actor_val,
+        is_driver.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+async fn create_backorder(
+    state: State<'_, AppState>,
+    input: BackorderInput,
+    role: Option<String>,
+    actor: Option<String>,
+) -> Result<String, String> {
+    let role_val = role.unwrap_or_else(|| "unknown".to_string()).to_lowercase();
+    let actor_val = actor.unwrap_or_else(|| "unknown".to_string());
+    audit_db(&state.pool, "create_backorder", &role_val, &actor_val).await;
+    create_backorder_order(&state.pool, input, &role_val, &actor_val).await
+}
+
+async fn load_delivery_event_rows(
+    pool: &SqlitePool,
+    scope: &ListScope,
+) -> Result<Vec<DeliveryEventRow>, String> {
+    sqlx::query_as::<_, DeliveryEventRow>(
+        r#"
+        SELECT
+            e.id,
+            CASE WHEN ?1 AND e.work_order_id IS NOT NULL THEN 'Hidden' ELSE e.title END AS title,
+            e.event_type,
+            e.start_date,
+            e.end_date,
+            e.work_order_id,
+            e.color_code,
+            COALESCE(e.assigned_user_ids_json, w.assignees_json) AS assigned_user_ids_json,
+            e.delivered_cords
+        FROM delivery_events e
+        LEFT JOIN work_orders w ON w.id = e.work_order_id
+        WHERE e.is_deleted = 0
+          AND (
+            ?2 IS NULL
+            OR e.work_order_id IN (
+                SELECT a.work_order_id
+                FROM auth_users au
+                JOIN work_order_assignments a ON a.user_id = au.user_id
+                WHERE au.is_deleted = 0 AND lower(au.username) = lower(?2)
+            )
+            OR e.id IN (
+                SELECT da.delivery_event_id
+                FROM auth_users au
+                JOIN delivery_event_assignments da ON da.user_id = au.user_id
+                WHERE au.is_deleted = 0 AND lower(au.username) = lower(?2)
+            )
+          )
+        ORDER BY datetime(e.start_date) ASC
+        "#,
+    )
+    .bind(scope.hide_contact)
+    .bind(&scope.assigned_to)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_delivery_events(
+    state: State<'_, AppState>,
+    role: Option<String>,
+    username: Option<String>,
+    hipaa_certified: Option<bool>,
+    is_driver: Option<bool>,
+) -> Result<Vec<DeliveryEventRow>, String> {
+    let role_val = role.unwrap_or_else(|| "admin".to_string()).to_lowercase();
+    let username_val = username.unwrap_or_default();
+    let scope = ListScope::for_delivery_events(
+        &role_val,
+        &username_val,
+        hipaa_certified.unwrap_or(false),
+        is_driver.unwrap_or(false),
+    );
+    audit_db(
+        &state.pool,
+        "list_delivery_events",
+        &role_val,
+        &username_val,
+    )
+    .await;
+    load_delivery_event_rows(&state.pool, &scope).await
+}
+
+#[tauri::command]
+async fn list_motd(
+    state: State<'_, AppState>,
+    active_only: Option<bool>,
+) -> Result<Vec<MotdRow>, String> {
+    let only_active = active_only.unwrap_or(true);
+    let mut query = String::from(
+        r#"
+        SELECT id, message, active_from, active_to, created_at
+        FROM motd
+        WHERE is_deleted = 0
+        "#,
+    );
+    if only_active {
+        query.push_str(
+            r#"
+              AND (active_from IS NULL OR datetime(active_from) <= datetime('now'))
+              AND (active_to IS NULL OR datetime(active_to) >= datetime('now'))
+            "#,
+        );
+    }
+    query.push_str(" ORDER BY datetime(created_at) DESC");
+
+    let rows = sqlx::query_as::<_, MotdRow>(&query)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    audit_db(&state.pool, "list_motd", "unknown", "unknown").await;
+    Ok(rows)
+}
+
+#[tauri::command]
+async fn create_motd(state: State<'_, AppState>, input: MotdInput) -> Result<String, String> {
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO motd (id, message, active_from, active_to, created_by_user_id)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.message)
+    .bind(&input.active_from)
+    .bind(&input.active_to)
+    .bind(&input.created_by_user_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    audit_db(&state.pool, "create_motd", "unknown", "unknown").await;
+    Ok(id)
+}
+
+#[tauri::command]
+async fn delete_motd(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    audit_db(&state.pool, "delete_motd", "unknown", "unknown").await;
+    sqlx::query(
+        r#"
+        UPDATE motd
+        SET is_deleted = 1, updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn create_change_request(
+    state: State<'_, AppState>,
+    input: ChangeRequestInput,
+) -> Result<String, String> {
+    let id = Uuid::new_v4().to_string();
+    audit_db(&state.pool, "create_change_request", "unknown", "unknown").await;
+    sqlx::query(
+        r#"
+        INSERT INTO change_requests (id, title, description, requested_by_user_id, status)
+        VALUES (?, ?, ?, ?, 'open')
+        "#,
+    )
+    .bind(&id)
+    .bind(&input.title)
+    .bind(&input.description)
+    .bind(&input.requested_by_user_id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+#[tauri::command]
+async fn list_change_requests(
+    state: State<'_, AppState>,
+    status: Option<String>,
+) -> Result<Vec<ChangeRequestRow>, String> {
+    let status_filter = status.unwrap_or_else(|| "open".to_string());
+    // If "all", fetch all, else filter
+    let query_str = if status_filter == "all" {
+        r#"
+        SELECT id, title, description, requested_by_user_id, status, resolution_notes, resolved_by_user_id, created_at
+        FROM change_requests
+        WHERE is_deleted = 0
+        ORDER BY created_at DESC
+        "#
+    } else {
+        r#"
+        SELECT id, title, description, requested_by_user_id, status, resolution_notes, resolved_by_user_id, created_at
+        FROM change_requests
+        WHERE is_deleted = 0 AND status = ?
+        ORDER BY created_at DESC
+        "#
+    };
+
+    let rows = if status_filter == "all" {
+        sqlx::query_as::<_, ChangeRequestRow>(query_str)
+            .fetch_all(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        sqlx::query_as::<_, ChangeRequestRow>(query_str)
+            .bind(status_filter)
+            .fetch_all(&state.pool)
+            .await
+            .map_err(|e| e.to_string())?
+    };
+
+    audit_db(&state.pool, "list_change_requests", "unknown", "unknown").await;
+    Ok(rows)
+}
+
+#[tauri::command]
+async fn resolve_change_request(
+    state: State<'_, AppState>,
+    id: String,
+    status: String,
+    resolution_notes: Option<String>,
+    resolved_by_user_id: String,
+) -> Result<(), String> {
+    audit_db(&state.pool, "resolve_change_request", "unknown", "unknown").await;
+    sqlx::query(
+        r#"
+        UPDATE change_requests
+        SET status = ?, resolution_notes = ?, resolved_by_user_id = ?, updated_at = datetime('now')
+        WHERE id = ?
+        "#,
+    )
+    .bind(&status)
+    .bind(&resolution_notes)
+    .bind(&resolved_by_user_id)
+    .bind(&id)
+    .execute(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[derive(Debug, Serialize, FromRow)]
+struct AuditLogRow {
+    id: String,
+    event: String,
+    role: Option<String>,
+    actor: Option<String>,
+    entity: Option<String>,
+    entity_id: Option<String>,
+    field: Option<String>,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    created_at: String,
+}
+
+#[tauri::command]
+async fn list_audit_logs(
+    state: State<'_, AppState>,
+    filter: Option<String>, // "day", "7days", "month", "year", "all"
+) -> Result<Vec<AuditLogRow>, String> {
+    let filter_val = filter.unwrap_or_else(|| "all".to_string());
+    let query = match filter_val.as_str() {
+        "day" => {
+            r#"
+            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
+            FROM audit_logs
+            WHERE date(created_at) = date('now')
+            ORDER BY created_at DESC
+            "#
+        }
+        "7days" => {
+            r#"
+            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
+            FROM audit_logs
+            WHERE created_at >= datetime('now', '-7 days')
+            ORDER BY created_at DESC
+            "#
+        }
+        "month" => {
+            r#"
+            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
+            FROM audit_logs
+            WHERE date(created_at) >= date('now', 'start of month')
+            ORDER BY created_at DESC
+            "#
+        }
+        "year" => {
+            r#"
+            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
+            FROM audit_logs
+            WHERE date(created_at) >= date('now', 'start of year')
+            ORDER BY created_at DESC
+            "#
+        }
+        _ => {
+            // "all" or default
+            r#"
+            SELECT id, event, role, actor, entity, entity_id, field, old_value, new_value, created_at
+            FROM audit_logs
+            ORDER BY created_at DESC
+            "#
+        }
+    };
+
+    let rows = sqlx::query_as::<_, AuditLogRow>(query)
+        .fetch_all(&state.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_auth_tables(pool: &SqlitePool) {
+        sqlx::query(
+            r#"
+            CREATE TABLE auth_users (
+                id TEXT PRIMARY KEY NOT NULL,
+                user_id TEXT NOT NULL,
+                username TEXT NOT NULL,
+                password TEXT NOT NULL,
+                created_at TEXT,
+                updated_at TEXT,
+                is_deleted INTEGER NOT NULL DEFAULT 0
+            )
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE audit_logs (
+                id TEXT PRIMARY KEY NOT NULL,
+                event TEXT NOT NULL,
+                role TEXT,
+                actor TEXT,
+                entity TEXT,
+                entity_id TEXT,
+                field TEXT,
+                old_value TEXT,
+                new_value TEXT,
+                created_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn setup_inventory_table(pool: &SqlitePool) {
+        sqlx::query(
+            r#"
+            CREATE TABLE inventory_items (
+                id TEXT PRIMARY KEY NOT NULL,
+                name TEXT NOT NULL,
+                unit TEXT NOT NULL,
+                quantity_on_hand REAL NOT NULL,
+                reserved_quantity REAL NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                updated_at TEXT,
+                is_deleted INTEGER NOT NULL DEFAULT 0
+            )
+            "#,
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn change_password_updates_hash() {
+        let pool = SqlitePool::connect(":memory:").await.unwrap();
+        setup_auth_tables(&pool).await;
+
+        let hashed = hash("oldpass", DEFAULT_COST).unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO auth_users (id, user_id, username, password, created_at, updated_at, is_deleted)
+            VALUES (?, ?, ?, ?, datetime('now'), datetime('now'), 0)
+            "#,
+        )
+        .bind("auth-id")
+        .bind("user-id")
+        .bind("tester")
+        .bind(&hashed)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let input = ChangePasswordInput {
+            username: "tester".to_string(),
+            current_password: "oldpass".to_string(),
+            new_password: "newpass".to_string(),
+        };
+        change_password_with_pool(&pool, input).await.unwrap();
+
+        let row = sqlx::query!("SELECT password FROM auth_users WHERE username = ?", "tester")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert!(verify("newpass", &row.password).unwrap());
+    }
+
+    #[tokio::test]
+    async fn adjust_inventory_reserves_on_schedule() {
+        let pool = SqlitePool::connect(":memory:").await.unwrap();
+        setup_inventory_table(&pool).await;
+
+        sqlx::query(
+            r#"
+            INSERT INTO inventory_items (id, name, unit, quantity_on_hand, reserved_quantity, created_at, is_deleted)
+            VALUES (?, ?, ?, ?, ?, datetime('now'), 0)
+            "#,
+        )
+        .bind("inv-1")
+        .bind("Firewood")
+        .bind("cords")
+        .bind(10.0)
+        .bind(0.0)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut tx = pool.begin().await.unwrap();
+        adjust_inventory_for_transition_tx(&mut tx, "received", "scheduled", 2.0)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let row = sqlx::query!(
+            "SELECT reserved_quantity FROM inventory_items WHERE id = ?",
+            "inv-1"
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(row.reserved_quantity, 2.0);
+    }
+
+    #[tokio::test]
+    async fn delivered_orders_stay_reserved_until_completed() {
+        let pool = SqlitePool::connect(":memory:").await.unwrap();
+        setup_inventory_table(&pool).await;
+        sqlx::query(
+            r#"
+            INSERT INTO inventory_items (id, name, unit, quantity_on_hand, reserved_quantity, created_at, is_deleted)
+            VALUES ('inv-1', 'Firewood', 'cords', 10.0, 0.0, datetime('now'), 0)
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let stock = || async {
+            sqlx::query_as::<_, (f64, f64)>(
+                "SELECT quantity_on_hand, reserved_quantity FROM inventory_items WHERE id = 'inv-1'",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+
+        let mut tx = pool.begin().await.unwrap();
+        adjust_inventory_for_transition_tx(&mut tx, "received", "scheduled", 2.0)
+            .await
+            .unwrap();
+        adjust_inventory_for_transition_tx(&mut tx, "scheduled", "delivered", 2.0)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(stock().await, (10.0, 2.0));
+
+        let mut tx = pool.begin().await.unwrap();
+        adjust_inventory_for_transition_tx(&mut tx, "delivered", "completed", 2.0)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(stock().await, (8.0, 0.0));
+    }
+
+    #[tokio::test]
+    async fn legacy_delivered_orders_are_reserved_again_before_completion() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            UPDATE inventory_items SET quantity_on_hand = 10, reserved_quantity = 0
+            WHERE lower(name) LIKE '%split%firewood%';
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571');
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                status, scheduled_date, delivery_size_cords
+            )
+            VALUES ('w1', 'c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571',
+                    'delivered', '2025-11-01', 2.0);
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let stock = || async {
+            sqlx::query_as::<_, (f64, f64)>(
+                r#"
+                SELECT quantity_on_hand, reserved_quantity FROM inventory_items
+                WHERE lower(name) LIKE '%split%firewood%'
+                "#,
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+
+        // The order was delivered under the old rules, which had already released its cords.
+        let rereserve = include_str!("../migrations/0039_reserve_delivered_orders.sql");
+        sqlx::query(rereserve).execute(&pool).await.unwrap();
+        assert_eq!(stock().await, (10.0, 2.0));
+
+        let complete = WorkOrderStatusInput {
+            work_order_id: "w1".to_string(),
+            status: Some("completed".to_string()),
+            mileage: Some(12.0),
+            work_hours: Some(2.0),
+            is_driver: None,
+            allotment_override_reason: None,
+            reason: None,
+        };
+        change_work_order_status(&pool, complete, "admin", "sam")
+            .await
+            .unwrap();
+        assert_eq!(stock().await, (8.0, 0.0));
+    }
+
+    #[test]
+    fn client_summary_groups_by_heating_season() {
+        assert_eq!(
+            heating_season_for_date("2025-07-01").as_deref(),
+            Some("2025-2026")
+        );
+        assert_eq!(
+            heating_season_for_date("2026-03-15 10:00:00").as_deref(),
+            Some("2025-2026")
+        );
+        assert_eq!(
+            heating_season_bounds("2024").unwrap(),
+            ("2024-07-01".to_string(), "2025-07-01".to_string())
+        );
+        assert!(heating_season_bounds("2024-2026").is_err());
+
+        let order = |id: &str, status: &str, date: &str, cords: f64, mileage: Option<f64>| {
+            ClientOrderStatRow {
+                id: id.to_string(),
+                client_id: "c1".to_string(),
+                status: status.to_string(),
+                scheduled_date: Some(date.to_string()),
+                created_at: date.to_string(),
+                pickup_delivery_type: Some("delivery".to_string()),
+                delivery_size_cords: Some(cords),
+                pickup_quantity_cords: Some(cords),
+                mileage,
+            }
+        };
+        let orders = vec![
+            order("a", "completed", "2024-11-02", 1.0, Some(10.0)),
+            order("b", "completed", "2025-12-01", 0.5, Some(20.0)),
+            order("c", "picked_up", "2026-01-10", 0.25, None),
+            order("d", "scheduled", "2026-02-01", 1.0, None),
+            order("e", "cancelled", "2026-02-03", 1.0, None),
+        ];
+        let summary = summarize_client_orders("c1", &orders, "2025-2026");
+        assert_eq!(summary.current_season_cords, 0.75);
+        assert_eq!(summary.total_cords, 1.75);
+        assert_eq!((summary.delivery_count, summary.pickup_count), (2, 1));
+        assert_eq!(summary.last_delivery_date.as_deref(), Some("2025-12-01"));
+        assert_eq!(summary.average_mileage, Some(15.0));
+        assert_eq!(summary.open_order_ids, vec!["d".to_string()]);
+        assert_eq!(summary.seasons[0].season, "2025-2026");
+    }
+
+    #[test]
+    fn waitlist_ranks_by_configured_formula() {
+        let row = |id: &str, requested_at: &str, flags: &str, miles: Option<f64>| WaitlistRow {
+            id: id.to_string(),
+            client_id: format!("client-{}", id),
+            client_name: id.to_string(),
+            requested_cords: 1.0,
+            priority_flags_json: flags.to_string(),
+            distance_miles: miles,
+            notes: None,
+            requested_at: requested_at.to_string(),
+        };
+        let settings = WaitlistSettings {
+            weight_days_waiting: 1.0,
+            weight_priority_flag: 30.0,
+            flag_weights: [("medical".to_string(), 60.0)].into_iter().collect(),
+            weight_per_mile: -0.5,
+        };
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 1, 31).unwrap();
+        let ranked = rank_waitlist(
+            vec![
+                row("old", "2025-12-01 09:00:00", "[]", Some(10.0)),
+                row(
+                    "medical",
+                    "2026-01-21 09:00:00",
+                    r#"["Medical"]"#,
+                    Some(40.0),
+                ),
+                row("elderly", "2026-01-01 09:00:00", r#"["elderly"]"#, None),
+                row("tie", "2026-01-01 10:00:00", r#"["elderly"]"#, None),
+            ],
+            &settings,
+            today,
+        );
+        let order: Vec<&str> = ranked.iter().map(|e| e.id.as_str()).collect();
+        // old: 61 - 5 = 56, medical: 10 + 60 - 20 = 50, elderly/tie: 30 + 30 = 60.
+        assert_eq!(order, vec!["elderly", "tie", "old", "medical"]);
+        assert_eq!(ranked[2].days_waiting, 61);
+        assert_eq!(ranked[2].score, 56.0);
+        assert_eq!(ranked[3].rank, 4);
+    }
+
+    #[tokio::test]
+    async fn allotment_rules_scale_by_household_and_gate_overrides() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, household_size
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Santa Fe', 'NM', '87501', 3)
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (id, status, date, cords) in [
+            ("w1", "completed", "2025-11-01", 1.5),
+            ("w2", "draft", "2025-12-01", 2.0),
+            ("w3", "cancelled", "2025-12-02", 1.0),
+            ("w4", "completed", "2025-05-01", 3.0),
+        ] {
+            sqlx::query(
+                r#"
+                INSERT INTO work_orders (
+                    id, client_id, client_name, physical_address_line1, physical_address_city,
+                    physical_address_state, physical_address_postal_code,
+                    status, scheduled_date, delivery_size_cords
+                )
+                VALUES (?, 'c1', 'Ada Lovelace', '1 Elm St', 'Santa Fe', 'NM', '87501', ?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(status)
+            .bind(date)
+            .bind(cords)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO allotment_rules (id, name, max_cords_per_household, extra_cords_per_member, max_cords_cap)
+            VALUES ('r1', 'Default', 2.0, 0.5, 2.5)
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let within = check_allotment(&mut conn, "c1", None, Some("2026-01-05"), 1.0)
+            .await
+            .unwrap();
+        assert_eq!(within.season, "2025-2026");
+        assert_eq!((within.used_cords, within.limit_cords), (1.5, Some(2.5)));
+        assert!(!within.exceeded);
+        assert_eq!(resolve_allotment(&within, "staff", None), Ok(None));
+
+        let over = check_allotment(&mut conn, "c1", None, Some("2026-01-05"), 1.5)
+            .await
+            .unwrap();
+        assert!(over.exceeded);
+        assert!(resolve_allotment(&over, "staff", None).is_err());
+        assert!(resolve_allotment(&over, "staff", Some("cold snap")).is_err());
+        assert!(resolve_allotment(&over, "lead", Some("  ")).is_err());
+        assert_eq!(
+            resolve_allotment(&over, "lead", Some("cold snap")),
+            Ok(Some(("allotment_override", "cold snap".to_string())))
+        );
+
+        // A season-specific rule wins over the catch-all one.
+        sqlx::query(
+            r#"
+            INSERT INTO allotment_rules (id, name, season, max_cords_per_household, enforcement)
+            VALUES ('r2', 'Mild winter', '2025-2026', 1.0, 'warn')
+            "#,
+        )
+        .execute(&mut *conn)
+        .await
+        .unwrap();
+        let warned = check_allotment(&mut conn, "c1", Some("w1"), Some("2026-01-05"), 1.5)
+            .await
+            .unwrap();
+        assert_eq!(warned.rule_id.as_deref(), Some("r2"));
+        assert_eq!(warned.used_cords, 0.0);
+        assert!(matches!(
+            resolve_allotment(&warned, "staff", None),
+            Ok(Some(("allotment_warning", _)))
+        ));
+        drop(conn);
+
+        // Warn-mode rules let the order through but hand the warning back to the caller.
+        let saved = insert_work_order(
+            &pool,
+            serde_json::from_value(serde_json::json!({
+                "client_id": "c1",
+                "client_name": "Ada Lovelace",
+                "physical_address_line1": "1 Elm St",
+                "physical_address_city": "Santa Fe",
+                "physical_address_state": "NM",
+                "physical_address_postal_code": "87501",
+                "other_heat_source_gas": false,
+                "other_heat_source_electric": false,
+                "scheduled_date": "2026-01-05",
+                "status": "received",
+                "delivery_size_cords": 0.5
+            }))
+            .unwrap(),
+            "staff",
+            "sam",
+            "create_work_order",
+        )
+        .await
+        .unwrap();
+        assert!(saved
+            .allotment_warning
+            .is_some_and(|w| w.contains("1.50 cords already committed")));
+        let status_input = WorkOrderStatusInput {
+            work_order_id: "w2".to_string(),
+            status: Some("received".to_string()),
+            mileage: None,
+            work_hours: None,
+            is_driver: None,
+            allotment_override_reason: None,
+            reason: None,
+        };
+        let changed = change_work_order_status(&pool, status_input, "staff", "sam")
+            .await
+            .unwrap();
+        assert!(changed.allotment_warning.is_some());
+
+        // Under a rejecting rule, staff are refused and a lead books the order with a reason.
+        sqlx::query("UPDATE allotment_rules SET enforcement = 'reject' WHERE id = 'r2'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let over_order = |reason: Option<&str>| -> WorkOrderInput {
+            serde_json::from_value(serde_json::json!({
+                "client_id": "c1",
+                "client_name": "Ada Lovelace",
+                "physical_address_line1": "1 Elm St",
+                "physical_address_city": "Santa Fe",
+                "physical_address_state": "NM",
+                "physical_address_postal_code": "87501",
+                "other_heat_source_gas": false,
+                "other_heat_source_electric": false,
+                "scheduled_date": "2026-01-05",
+                "status": "received",
+                "delivery_size_cords": 0.5,
+                "allotment_override_reason": reason
+            }))
+            .unwrap()
+        };
+        let refused =
+            insert_work_order(&pool, over_order(None), "staff", "sam", "create_work_order")
+                .await
+                .unwrap_err();
+        assert!(refused.contains("A lead can override"));
+        let saved = insert_work_order(
+            &pool,
+            over_order(Some("cold snap")),
+            "lead",
+            "lee",
+            "create_work_order",
+        )
+        .await
+        .unwrap();
+        assert_eq!(saved.allotment_warning, None);
+        let overrides: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE field = 'allotment_override' AND entity_id = ?",
+        )
+        .bind(&saved.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(overrides, 1);
+    }
+
+    #[tokio::test]
+    async fn client_timeline_merges_contacts_orders_and_approvals() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Santa Fe', 'NM', '87501')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                status, delivery_size_cords, created_at
+            )
+            VALUES ('w1', 'c1', 'Ada Lovelace', '1 Elm St', 'Santa Fe', 'NM', '87501',
+                    'scheduled', 1.0, '2025-10-02 09:00:00')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO client_contacts (id, client_id, contact_type, direction, contacted_at, outcome)
+            VALUES ('k1', 'c1', 'call', 'inbound', '2025-09-30 15:00:00', 'Requested wood')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        audit_change(
+            &pool,
+            "update_client",
+            "admin",
+            "sam",
+            "clients",
+            "c1",
+            "approval_status",
+            Some("pending".to_string()),
+            Some("approved".to_string()),
+        )
+        .await;
+        audit_change(
+            &pool,
+            "update_work_order_status",
+            "admin",
+            "sam",
+            "work_orders",
+            "w1",
+            "status",
+            Some("draft".to_string()),
+            Some("scheduled".to_string()),
+        )
+        .await;
+        audit_change(
+            &pool,
+            "update_client",
+            "admin",
+            "sam",
+            "clients",
+            "c1",
+            "notes",
+            None,
+            Some("ignored".to_string()),
+        )
+        .await;
+
+        let timeline = load_client_timeline(&pool, "c1").await.unwrap();
+        let kinds: Vec<&str> = timeline.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(timeline.len(), 4);
+        assert_eq!(&kinds[2..], &["work_order", "contact"]);
+        assert!(kinds[..2].contains(&"approval") && kinds[..2].contains(&"work_order_status"));
+        assert_eq!(timeline[3].title, "inbound call");
+        assert_eq!(
+            timeline[3].detail.as_deref(),
+            Some("Outcome: Requested wood")
+        );
+    }
+
+    #[tokio::test]
+    async fn anonymize_client_scrubs_pii_and_keeps_statistics() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, first_name, last_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, telephone, email, gate_combo
+            )
+            VALUES ('c1', 'Ada Lovelace', 'Ada', 'Lovelace', '1 Elm St', 'Santa Fe', 'NM', '87501',
+                    '(505) 555-0100', 'ada@example.org', '4321')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, telephone,
+                status, scheduled_date, delivery_size_cords, mileage
+            )
+            VALUES ('w1', 'c1', 'Ada Lovelace', '1 Elm St', 'Santa Fe', 'NM', '87501',
+                    '(505) 555-0100', 'completed', '2025-11-01', 1.5, 12.0)
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO invoices (id, work_order_id, invoice_number, invoice_date, client_snapshot_json)
+            VALUES ('i1', 'w1', 'INV-1', '2025-11-02',
+                    '{"client_name":"Ada Lovelace","telephone":"(505) 555-0100","physical_address_city":"Santa Fe","delivery_size_cords":1.5}')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        audit_change(
+            &pool,
+            "update_client",
+            "admin",
+            "sam",
+            "clients",
+            "c1",
+            "telephone",
+            None,
+            Some("(505) 555-0100".to_string()),
+        )
+        .await;
+        audit_change(
+            &pool,
+            "export_mailing_list",
+            "admin",
+            "sam",
+            "mailing_exports",
+            "x",
+            "note",
+            None,
+            Some("Exported Ada Lovelace".to_string()),
+        )
+        .await;
+
+        let dir = std::env::temp_dir().join(format!("fwb-erasure-{}", Uuid::new_v4()));
+        let certificate =
+            anonymize_client_with_pool(&pool, &dir, "c1", Some("Request".to_string()), "sam")
+                .await
+                .unwrap();
+        assert_eq!(certificate.digest, erasure_digest(&certificate));
+        assert!(certificate
+            .records
+            .iter()
+            .any(|r| r.table == "invoices" && r.rows == 1));
+
+        let (name, phone, city, deleted): (String, Option<String>, String, bool) = sqlx::query_as(
+            "SELECT name, telephone, physical_address_city, is_deleted FROM clients WHERE id = 'c1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(name, certificate.anonymized_label);
+        assert_eq!((phone, city.as_str(), deleted), (None, "Santa Fe", true));
+        let (cords, mileage, line1): (f64, f64, String) = sqlx::query_as(
+            "SELECT delivery_size_cords, mileage, physical_address_line1 FROM work_orders WHERE id = 'w1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!((cords, mileage, line1.as_str()), (1.5, 12.0, REDACTED));
+        let snapshot: String =
+            sqlx::query_scalar("SELECT client_snapshot_json FROM invoices WHERE id = 'i1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(!snapshot.contains("Ada") && !snapshot.contains("555"));
+        assert!(snapshot.contains("Santa Fe"));
+        let leaks: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE new_value LIKE '%Ada%' OR new_value LIKE '%555%'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(leaks, 0);
+        assert!(anonymize_client_with_pool(&pool, &dir, "c1", None, "sam")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn known_zip_resolves_to_gazetteer_centroid() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        seed_bundled_zip_centroids(&pool).await.unwrap();
+
+        // An admin import in the Census Gazetteer layout replaces the bundled row.
+        let (centroids, skipped) = geo::parse_centroids(
+            "GEOID\tALAND\tAWATER\tALAND_SQMI\tAWATER_SQMI\tINTPTLAT\tINTPTLONG                  \n\
+             87501\t466237394\t206468\t180.015\t0.08\t35.781559\t-105.926315                 \n",
+        )
+        .unwrap();
+        assert_eq!(skipped, 0);
+        upsert_zip_centroids(&pool, &centroids, "import", true)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            zip_centroid_for(&pool, "87501-1234").await.unwrap(),
+            Some((35.781559, -105.926315))
+        );
+        assert_eq!(zip_centroid_for(&pool, "0000").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn bundled_centroids_estimate_service_area_clients() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        seed_bundled_zip_centroids(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            UPDATE distance_settings SET yard_latitude = 35.6870, yard_longitude = -105.9378;
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571'),
+                   ('c2', 'Grace Hopper', '2 Elm St', 'Chimayo', 'NM', '');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let updated = refresh_client_distance_estimates(&pool, None)
+            .await
+            .unwrap();
+        assert_eq!(updated, 2);
+        let rows: Vec<(String, Option<f64>, Option<String>)> = sqlx::query_as(
+            "SELECT id, estimated_mileage, estimated_mileage_source FROM clients ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        // A Santa Fe yard is about 54 miles from Taos as the crow flies.
+        let taos = rows[0].1.unwrap();
+        assert!((60.0..75.0).contains(&taos), "{}", taos);
+        assert_eq!(rows[0].2.as_deref(), Some("zip"));
+        assert!(rows[1].1.is_some());
+        assert_eq!(rows[1].2.as_deref(), Some("city"));
+    }
+
+    #[tokio::test]
+    async fn distance_estimates_use_zip_then_city_centroids() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let (centroids, _) = geo::parse_centroids(
+            "zip,city,state,latitude,longitude\n10001,Pine Hollow,NM,36.0,-106.0\n10002,Cedar Flats,NM,35.5,-106.0\n",
+        )
+        .unwrap();
+        upsert_zip_centroids(&pool, &centroids, "import", true)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code
+            )
+            VALUES ('c1', 'Zip Match', '1 Elm St', 'Pine Hollow', 'NM', '10001-1234'),
+                   ('c2', 'Town Match', '2 Oak St', 'cedar flats', 'nm', '99999'),
+                   ('c3', 'Unknown', '3 Ash St', 'Nowhere', 'NM', '88888')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Without a yard nothing can be estimated.
+        refresh_client_distance_estimates(&pool, None)
+            .await
+            .unwrap();
+        let none: Option<f64> =
+            sqlx::query_scalar("SELECT estimated_mileage FROM clients WHERE id = 'c1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(none, None);
+
+        sqlx::query(
+            "UPDATE distance_settings SET yard_latitude = 35.0, yard_longitude = -106.0, road_factor = 1.3",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            refresh_client_distance_estimates(&pool, None)
+                .await
+                .unwrap(),
+            3
+        );
+        let rows: Vec<(String, Option<f64>, Option<String>)> = sqlx::query_as(
+            "SELECT id, estimated_mileage, estimated_mileage_source FROM clients ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows[0],
+            ("c1".to_string(), Some(89.8), Some("zip".to_string()))
+        );
+        assert_eq!(
+            rows[1],
+            ("c2".to_string(), Some(44.9), Some("city".to_string()))
+        );
+        assert_eq!(rows[2], ("c3".to_string(), None, None));
+    }
+
+    #[tokio::test]
+    async fn agency_links_follow_aliases_and_report_by_season() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        sqlx::query(
+            r#"
+            INSERT INTO agencies (id, name, aliases_json)
+            VALUES ('a1', 'St. Vincent''s Pantry', '["SVDP"]')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                referring_agency, how_did_they_hear_about_us, approval_status, date_of_onboarding
+            )
+            VALUES
+                ('c1', 'One', '1 Elm St', 'Taos', 'NM', '87571', 'st vincents pantry', NULL, 'approved', '2025-09-01'),
+                ('c2', 'Two', '2 Elm St', 'Taos', 'NM', '87571', NULL, 'svdp', 'denied', '2025-10-01'),
+                ('c3', 'Three', '3 Elm St', 'Taos', 'NM', '87571', 'Church flyer', NULL, 'approved', '2024-09-01')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                status, delivery_size_cords, scheduled_date
+            )
+            VALUES
+                ('w1', 'c1', 'One', '1 Elm St', 'Taos', 'NM', '87571', 'completed', 1.0, '2025-11-01'),
+                ('w2', 'c1', 'One', '1 Elm St', 'Taos', 'NM', '87571', 'completed', 0.5, '2026-01-10'),
+                ('w3', 'c1', 'One', '1 Elm St', 'Taos', 'NM', '87571', 'scheduled', 1.0, '2026-02-10'),
+                ('w4', 'c3', 'Three', '3 Elm St', 'Taos', 'NM', '87571', 'completed', 2.0, '2025-12-01')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let changed = link_client_agencies(&mut conn, None).await.unwrap();
+        drop(conn);
+        assert_eq!(changed.len(), 2);
+        let linked: Vec<Option<String>> =
+            sqlx::query_scalar("SELECT referring_agency_id FROM clients ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            linked,
+            vec![Some("a1".to_string()), Some("a1".to_string()), None]
+        );
+
+        let report = load_agency_referral_report(&pool, Some("2025"))
+            .await
+            .unwrap();
+        assert_eq!(report.len(), 2);
+        let agency = &report[0];
+        assert_eq!(agency.agency_name, "St. Vincent's Pantry");
+        assert_eq!(
+            (agency.referrals, agency.approvals, agency.denials),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            (
+                agency.clients_served,
+                agency.deliveries,
+                agency.cords_delivered
+            ),
+            (1, 2, 1.5)
+        );
+        // c3 was referred the season before but its delivery counts this season.
+        let unassigned = &report[1];
+        assert_eq!(unassigned.agency_id, None);
+        assert_eq!((unassigned.referrals, unassigned.cords_delivered), (0, 2.0));
+    }
+
+    #[tokio::test]
+    async fn stale_order_detection_skips_served_orders() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, telephone, gate_combo
+            )
+            VALUES ('c1', 'Ada Lovelace', '9 New Rd', 'Taos', 'NM', '87571', '(505) 555-0100', '')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, telephone, gate_combo, status
+            )
+            VALUES
+                ('open', 'c1', 'Ada Lovelace', '1 Old Rd', 'Taos', 'NM', '87571', '(505) 555-0100', NULL, 'scheduled'),
+                ('done', 'c1', 'Ada Lovelace', '1 Old Rd', 'Taos', 'NM', '87571', '(505) 555-0100', NULL, 'completed'),
+                ('dropped', 'c1', 'Ada Lovelace', '1 Old Rd', 'Taos', 'NM', '87571', '(505) 555-0100', NULL, 'Delivered'),
+                ('same', 'c1', 'Ada Lovelace', '9 New Rd ', 'Taos', 'NM', '87571', '(505) 555-0100', NULL, 'draft')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        let stale = find_stale_client_orders(&mut conn, "c1").await.unwrap();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].work_order_id, "open");
+        let fields: Vec<&str> = stale[0].fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, vec!["physical_address_line1"]);
+        assert_eq!(stale[0].fields[0].order_value.as_deref(), Some("1 Old Rd"));
+        assert_eq!(stale[0].fields[0].client_value.as_deref(), Some("9 New Rd"));
+
+        let mut redacted = find_stale_client_orders(&mut conn, "c1").await.unwrap();
+        redact_stale_orders(&mut redacted);
+        assert_eq!(redacted[0].fields[0].field, "physical_address_line1");
+        assert_eq!(redacted[0].fields[0].order_value.as_deref(), Some("Hidden"));
+        assert_eq!(
+            redacted[0].fields[0].client_value.as_deref(),
+            Some("Hidden")
+        );
+        assert!(find_stale_client_orders(&mut conn, "missing")
+            .await
+            .is_err());
+        drop(conn);
+
+        // Staff without HIPAA can push the new address out but only see it redacted.
+        let sync = ClientOrderSyncInput {
+            client_id: "c1".to_string(),
+            work_order_ids: None,
+            fields: None,
+        };
+        let synced = sync_client_orders(&pool, sync, "staff", "sam", false)
+            .await
+            .unwrap();
+        assert_eq!(synced[0].fields[0].order_value.as_deref(), Some("Hidden"));
+        assert_eq!(synced[0].fields[0].client_value.as_deref(), Some("Hidden"));
+        let line1: String =
+            sqlx::query_scalar("SELECT physical_address_line1 FROM work_orders WHERE id = 'open'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(line1, "9 New Rd");
+    }
+
+    #[tokio::test]
+    async fn client_work_orders_copy_fields_from_the_client_row() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                gate_combo, wood_size_label, default_mileage, approval_status
+            )
+            VALUES
+                ('ok', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571', '1234', '16in', 12.5, 'approved'),
+                ('pending', 'Pat Pending', '2 Elm St', 'Taos', 'NM', '87571', NULL, NULL, NULL, 'pending')
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let request = |client_id: &str| -> ClientWorkOrderInput {
+            serde_json::from_value(serde_json::json!({
+                "client_id": client_id,
+                "delivery_size_cords": 1.0,
+                "notes": "Back gate"
+            }))
+            .unwrap()
+        };
+
+        let err = build_client_work_order(&pool, request("pending"))
+            .await
+            .unwrap_err();
+        assert!(err.contains("approved"));
+        assert!(build_client_work_order(&pool, request("missing"))
+            .await
+            .is_err());
+
+        let order = build_client_work_order(&pool, request("ok")).await.unwrap();
+        let id = insert_work_order(&pool, order, "admin", "sam", "create_work_order_for_client")
+            .await
+            .unwrap()
+            .id;
+        let row: (String, String, Option<String>, Option<String>, Option<f64>, Option<String>) =
+            sqlx::query_as(
+                r#"
+                SELECT client_name, physical_address_line1, gate_combo, wood_size_label, mileage, notes
+                FROM work_orders WHERE id = ?
+                "#,
+            )
+            .bind(&id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                "Ada Lovelace".to_string(),
+                "1 Elm St".to_string(),
+                Some("1234".to_string()),
+                Some("16in".to_string()),
+                Some(12.5),
+                Some("Back gate".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn do_not_call_windows_validate_and_wrap_midnight() {
+        let windows = validate_do_not_call_windows(vec![
+            DoNotCallWindow {
+                days: vec!["FRI".to_string()],
+                start: "20:00".to_string(),
+                end: "8:00".to_string(),
+            },
+            DoNotCallWindow {
+                days: vec![],
+                start: "12:00".to_string(),
+                end: "13:00".to_string(),
+            },
+        ])
+        .unwrap();
+        assert_eq!(windows[0].days, vec!["fri".to_string()]);
+        assert_eq!(windows[0].end, "08:00");
+
+        let at = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        // 2025-10-03 is a Friday.
+        assert!(in_do_not_call_window(&windows, at("2025-10-03 21:30")));
+        assert!(in_do_not_call_window(&windows, at("2025-10-04 07:59")));
+        assert!(!in_do_not_call_window(&windows, at("2025-10-04 21:30")));
+        assert!(in_do_not_call_window(&windows, at("2025-10-06 12:15")));
+        assert!(!in_do_not_call_window(&windows, at("2025-10-06 13:00")));
+
+        assert!(validate_do_not_call_windows(vec![DoNotCallWindow {
+            days: vec!["someday".to_string()],
+            start: "09:00".to_string(),
+            end: "10:00".to_string(),
+        }])
+        .is_err());
+        assert!(validate_do_not_call_windows(vec![DoNotCallWindow {
+            days: vec![],
+            start: "09:00".to_string(),
+            end: "09:00".to_string(),
+        }])
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn work_order_edits_move_reservations_and_audit_fields() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, approval_status
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571', 'approved');
+            UPDATE inventory_items SET quantity_on_hand = 3
+            WHERE lower(name) LIKE '%split%firewood%';
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let order: WorkOrderInput = serde_json::from_value(serde_json::json!({
+            "client_id": "c1",
+            "client_name": "Ada Lovelace",
+            "physical_address_line1": "1 Elm St",
+            "physical_address_city": "Taos",
+            "physical_address_state": "NM",
+            "physical_address_postal_code": "87571",
+            "other_heat_source_gas": false,
+            "other_heat_source_electric": false,
+            "scheduled_date": "2025-11-01",
+            "status": "scheduled",
+            "delivery_size_cords": 1.0,
+            "gate_combo": "1234",
+            "notes": "Dog in the yard"
+        }))
+        .unwrap();
+        let id = insert_work_order(&pool, order, "admin", "sam", "create_work_order")
+            .await
+            .unwrap()
+            .id;
+        let reserved = || async {
+            sqlx::query_scalar::<_, f64>(
+                "SELECT SUM(reserved_quantity) FROM inventory_items WHERE is_deleted = 0",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap()
+        };
+        assert_eq!(reserved().await, 1.0);
+
+        let edit = |cords: f64| -> WorkOrderUpdateInput {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "directions": "Second drive past the mailbox",
+                "other_heat_source_gas": true,
+                "other_heat_source_electric": false,
+                "delivery_size_cords": cords
+            }))
+            .unwrap()
+        };
+        assert!(edit_work_order(&pool, edit(2.0), "volunteer", "vic", false)
+            .await
+            .is_err());
+        // Staff without HIPAA never saw the gate code or notes, so their NULLs leave them alone.
+        let changes = edit_work_order(&pool, edit(2.0), "staff", "sam", false)
+            .await
+            .unwrap();
+        let fields: Vec<&str> = changes.iter().map(|(field, _, _)| *field).collect();
+        assert_eq!(
+            fields,
+            ["directions", "other_heat_source_gas", "delivery_size_cords"]
+        );
+        assert_eq!(reserved().await, 2.0);
+        // Only 3 cords on hand, so a 4-cord order cannot be reserved and nothing changes.
+        assert!(edit_work_order(&pool, edit(4.0), "staff", "sam", false)
+            .await
+            .is_err());
+        assert_eq!(reserved().await, 2.0);
+
+        let audited: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE event = 'update_work_order' AND entity_id = ?",
+        )
+        .bind(&id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(audited, 3);
+        let kept: (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT gate_combo, notes FROM work_orders WHERE id = ?")
+                .bind(&id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(kept.0.as_deref(), Some("1234"));
+        assert_eq!(kept.1.as_deref(), Some("Dog in the yard"));
+
+        // Delivered orders still hold their reservation, so a correction moves it too.
+        sqlx::query("UPDATE work_orders SET status = 'delivered' WHERE id = ?")
+            .bind(&id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        edit_work_order(&pool, edit(1.5), "staff", "sam", false)
+            .await
+            .unwrap();
+        assert_eq!(reserved().await, 1.5);
+    }
+
+    #[tokio::test]
+    async fn cancelling_releases_wood_and_unpairs_the_partner() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, approval_status
+            )
+            VALUES
+                ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571', 'approved'),
+                ('c2', 'Grace Hopper', '2 Elm St', 'Taos', 'NM', '87571', 'approved');
+            UPDATE inventory_items SET quantity_on_hand = 3
+            WHERE lower(name) LIKE '%split%firewood%';
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let order = |client_id: &str, paired: Option<&str>| -> WorkOrderInput {
+            serde_json::from_value(serde_json::json!({
+                "client_id": client_id,
+                "client_name": client_id,
+                "physical_address_line1": "1 Elm St",
+                "physical_address_city": "Taos",
+                "physical_address_state": "NM",
+                "physical_address_postal_code": "87571",
+                "other_heat_source_gas": false,
+                "other_heat_source_electric": false,
+                "scheduled_date": "2025-11-01",
+                "status": "scheduled",
+                "delivery_size_label": "F-250 1/2",
+                "delivery_size_cords": 0.5,
+                "paired_order_id": paired
+            }))
+            .unwrap()
+        };
+        let first = insert_work_order(
+            &pool,
+            order("c1", None),
+            "admin",
+            "sam",
+            "create_work_order",
+        )
+        .await
+        .unwrap()
+        .id;
+        let second = insert_work_order(
+            &pool,
+            order("c2", Some(&first)),
+            "admin",
+            "sam",
+            "create_work_order",
+        )
+        .await
+        .unwrap()
+        .id;
+        let cancel = |code: &str| -> CancelWorkOrderInput {
+            serde_json::from_value(serde_json::json!({
+                "work_order_id": first,
+                "reason_code": code,
+                "return_to_waitlist": true
+            }))
+            .unwrap()
+        };
+        // The generic status command cannot cancel without a reason code.
+        let generic: WorkOrderStatusInput = serde_json::from_value(serde_json::json!({
+            "work_order_id": first,
+            "status": "cancelled"
+        }))
+        .unwrap();
+        assert!(change_work_order_status(&pool, generic, "admin", "sam")
+            .await
+            .is_err());
+        assert!(cancel_order(&pool, cancel("changed_mind"), "staff", "sam")
+            .await
+            .is_err());
+        assert!(cancel_order(&pool, cancel("other"), "staff", "sam")
+            .await
+            .is_err());
+
+        let result = cancel_order(&pool, cancel("client_request"), "staff", "sam")
+            .await
+            .unwrap();
+        assert_eq!(result.unpaired_order_id.as_deref(), Some(second.as_str()));
+        assert!(result.waitlist_entry_id.is_some());
+
+        let reserved: f64 = sqlx::query_scalar(
+            "SELECT SUM(reserved_quantity) FROM inventory_items WHERE is_deleted = 0",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(reserved, 0.5);
+        let pairs: Vec<Option<String>> =
+            sqlx::query_scalar("SELECT paired_order_id FROM work_orders ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(pairs, vec![None, None]);
+        let events: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM delivery_events WHERE work_order_id = ? AND is_deleted = 0",
+        )
+        .bind(&first)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(events, 0);
+        // Already cancelled, so there is no transition left to make.
+        assert!(cancel_order(&pool, cancel("duplicate"), "admin", "sam")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn assignments_resolve_names_to_user_ids() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, name, role, is_driver) VALUES
+                ('u1', 'Dana Driver', 'volunteer', 1),
+                ('u2', 'Lee Lead', 'lead', 0);
+            INSERT INTO auth_users (id, user_id, username, password)
+            VALUES ('a2', 'u2', 'lee', 'x');
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, approval_status
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571', 'approved');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let order: WorkOrderInput = serde_json::from_value(serde_json::json!({
+            "client_id": "c1",
+            "client_name": "Ada Lovelace",
+            "physical_address_line1": "1 Elm St",
+            "physical_address_city": "Taos",
+            "physical_address_state": "NM",
+            "physical_address_postal_code": "87571",
+            "other_heat_source_gas": false,
+            "other_heat_source_electric": false,
+            "assignees_json": "[\"dana driver\", \"LEE\", \"Cousin Ray\"]"
+        }))
+        .unwrap();
+        let id = insert_work_order(&pool, order, "admin", "sam", "create_work_order")
+            .await
+            .unwrap()
+            .id;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT user_id, assignment_role FROM work_order_assignments ORDER BY user_id",
+        )
+        .fetch_all(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("u1".to_string(), "driver".to_string()),
+                ("u2".to_string(), "lead".to_string())
+            ]
+        );
+
+        let assign = |user_id: &str, role: &str| AssignmentInput {
+            user_id: user_id.to_string(),
+            assignment_role: role.to_string(),
+        };
+        assert!(
+            replace_assignments(&mut conn, &id, &[assign("u1", "boss")], "sam")
+                .await
+                .is_err()
+        );
+        assert!(
+            replace_assignments(&mut conn, &id, &[assign("nobody", "helper")], "sam")
+                .await
+                .is_err()
+        );
+        replace_assignments(&mut conn, &id, &[assign("u2", "driver")], "sam")
+            .await
+            .unwrap();
+        assert_eq!(
+            load_assignment_summary(&mut conn, &id).await.unwrap(),
+            vec!["Lee Lead (driver)".to_string()]
+        );
+        drop(conn);
+
+        // Dana has no login, so only Lee's username finds the order.
+        let visible =
+            |username: &str| ListScope::for_work_orders("volunteer", username, false, false);
+        let rows = load_work_order_rows(&pool, &visible("Lee")).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].telephone, None);
+        assert!(load_work_order_rows(&pool, &visible("dana"))
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Events without an order find their crew through their own assignment rows.
+        let event: DeliveryEventInput = serde_json::from_value(serde_json::json!({
+            "title": "Splitting day",
+            "event_type": "workday",
+            "start_date": "2025-11-01",
+            "assigned_user_ids_json": "[\"Lee Lead\", \"Cousin Ray\"]"
+        }))
+        .unwrap();
+        insert_delivery_event(&pool, "e1", &event, "sam")
+            .await
+            .unwrap();
+        let events =
+            |username: &str| ListScope::for_delivery_events("volunteer", username, false, false);
+        let rows = load_delivery_event_rows(&pool, &events("lee"))
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].id, "e1");
+        assert!(load_delivery_event_rows(&pool, &events("cousin ray"))
+            .await
+            .unwrap()
+            .is_empty());
+
+        // A display name two people share assigns neither; their usernames still work.
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, name, role, is_driver) VALUES
+                ('u3', 'Sam Smith', 'volunteer', 1),
+                ('u4', 'Sam Smith', 'volunteer', 0);
+            INSERT INTO auth_users (id, user_id, username, password)
+            VALUES ('a4', 'u4', 'ssmith', 'x');
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert!(
+            sync_assignments_from_names(&mut conn, &id, &names(&["sam smith"]), "sam")
+                .await
+                .unwrap_err()
+                .contains("2 people are named")
+        );
+        sync_assignments_from_names(&mut conn, &id, &names(&["SSmith", "u3"]), "sam")
+            .await
+            .unwrap();
+        assert_eq!(
+            load_assignment_summary(&mut conn, &id).await.unwrap(),
+            vec![
+                "Sam Smith (driver)".to_string(),
+                "Sam Smith (helper)".to_string()
+            ]
+        );
+    }
 
-    async fn setup_auth_tables(pool: &SqlitePool) {
+    /// Synthetic 50k-order database; run with
+    /// `cargo test bench_scoped_lists -- --ignored --nocapture`.
+    #[tokio::test]
+    #[ignore]
+    async fn bench_scoped_lists_on_50k_orders() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
         sqlx::query(
             r#"
-            CREATE TABLE auth_users (
-                id TEXT PRIMARY KEY NOT NULL,
-                user_id TEXT NOT NULL,
-                username TEXT NOT NULL,
-                password TEXT NOT NULL,
-                created_at TEXT,
-                updated_at TEXT,
-                is_deleted INTEGER NOT NULL DEFAULT 0
+            INSERT INTO users (id, name, role, is_driver)
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 200)
+            SELECT 'u' || i, 'User ' || i, 'volunteer', i % 2 FROM n;
+            INSERT INTO auth_users (id, user_id, username, password)
+            SELECT 'a' || substr(id, 2), id, 'user' || substr(id, 2), 'x'
+            FROM users WHERE id GLOB 'u[0-9]*';
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, approval_status
             )
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 10000)
+            SELECT 'c' || i, 'Client ' || i, i || ' Elm St', 'Taos', 'NM', '87571', 'approved'
+            FROM n;
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, status, scheduled_date,
+                assignees_json
+            )
+            WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 50000)
+            SELECT 'w' || i, 'c' || (i % 10000 + 1), 'Client ' || (i % 10000 + 1), '1 Elm St',
+                   'Taos', 'NM', '87571', 'scheduled', date('2025-10-01', '+' || (i % 180) || ' days'),
+                   json_array('User ' || (i % 200 + 1))
+            FROM n;
+            INSERT INTO work_order_assignments (id, work_order_id, user_id, assignment_role)
+            SELECT 'x' || substr(id, 2), id, 'u' || (CAST(substr(id, 2) AS INTEGER) % 200 + 1), 'driver'
+            FROM work_orders;
+            INSERT INTO delivery_events (id, title, event_type, work_order_id, start_date)
+            SELECT 'e' || substr(id, 2), 'Delivery for ' || client_name, 'delivery', id, scheduled_date
+            FROM work_orders;
             "#,
         )
-        .execute(pool)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let time = |label: &str, started: std::time::Instant, rows: usize| {
+            println!(
+                "{:<28} {:>6} rows {:>8.1} ms",
+                label,
+                rows,
+                started.elapsed().as_secs_f64() * 1000.0
+            );
+        };
+        let admin = ListScope::for_work_orders("admin", "", false, false);
+        let volunteer = ListScope::for_work_orders("volunteer", "user7", false, false);
+        let started = std::time::Instant::now();
+        let all = load_work_order_rows(&pool, &admin).await.unwrap();
+        time("work orders (admin)", started, all.len());
+        let started = std::time::Instant::now();
+        let mine = load_work_order_rows(&pool, &volunteer).await.unwrap();
+        time("work orders (volunteer)", started, mine.len());
+        let started = std::time::Instant::now();
+        let clients = load_client_rows(
+            &pool,
+            &ListScope::for_clients("volunteer", "user7", false, true),
+        )
+        .await
+        .unwrap();
+        time("clients (driver)", started, clients.len());
+        let started = std::time::Instant::now();
+        let events = load_delivery_event_rows(
+            &pool,
+            &ListScope::for_delivery_events("volunteer", "user7", false, false),
+        )
         .await
         .unwrap();
+        time("delivery events (volunteer)", started, events.len());
+
+        assert_eq!(all.len(), 50_000);
+        assert_eq!(mine.len(), 250);
+        assert_eq!(clients.len(), 50);
+        assert_eq!(events.len(), 250);
+        assert!(mine.iter().all(|wo| wo.physical_address_line1.is_none()));
+    }
 
+    #[tokio::test]
+    async fn schedule_reconciliation_reports_and_audits_its_repairs() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
         sqlx::query(
             r#"
-            CREATE TABLE audit_logs (
-                id TEXT PRIMARY KEY NOT NULL,
-                event TEXT NOT NULL,
-                role TEXT,
-                actor TEXT,
-                entity TEXT,
-                entity_id TEXT,
-                field TEXT,
-                old_value TEXT,
-                new_value TEXT,
-                created_at TEXT NOT NULL
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571');
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                status, scheduled_date, delivery_size_cords
             )
+            VALUES
+                ('w1', 'c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571',
+                 'received', '2025-11-01', 1.0),
+                ('w2', 'c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571',
+                 'pending', '2025-11-02', 5.0),
+                ('w3', 'c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571',
+                 'cancelled', '2025-11-03', 1.0);
+            UPDATE inventory_items SET quantity_on_hand = 3
+            WHERE lower(name) LIKE '%split%firewood%';
             "#,
         )
-        .execute(pool)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Listing no longer repairs anything.
+        load_work_order_rows(&pool, &ListScope::default())
+            .await
+            .unwrap();
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM delivery_events")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(events, 0);
+
+        let report = run_schedule_reconciliation(&pool, "system", "system")
+            .await
+            .unwrap();
+        assert_eq!(report.scheduled, vec!["w1".to_string()]);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].work_order_id, "w2");
+        assert_eq!(report.events_created.len(), 2);
+
+        let statuses: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, status FROM work_orders ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(statuses[0].1, "scheduled");
+        assert_eq!(statuses[1].1, "pending");
+        let reserved: f64 = sqlx::query_scalar(
+            "SELECT reserved_quantity FROM inventory_items WHERE lower(name) LIKE '%split%firewood%'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(reserved, 1.0);
+        let history: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM work_order_status_history WHERE work_order_id = 'w1'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(history, 1);
+        let audited: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE event = 'reconcile_schedule'",
+        )
+        .fetch_one(&pool)
         .await
         .unwrap();
+        assert_eq!(audited, 3);
+
+        let again = run_schedule_reconciliation(&pool, "system", "system")
+            .await
+            .unwrap();
+        assert!(again.scheduled.is_empty() && again.events_created.is_empty());
+        assert_eq!(again.skipped.len(), 1);
     }
 
-    async fn setup_inventory_table(pool: &SqlitePool) {
+    #[tokio::test]
+    async fn pairing_validates_both_halves_and_keeps_them_together() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
         sqlx::query(
             r#"
-            CREATE TABLE inventory_items (
-                id TEXT PRIMARY KEY NOT NULL,
-                name TEXT NOT NULL,
-                unit TEXT NOT NULL,
-                quantity_on_hand REAL NOT NULL,
-                reserved_quantity REAL NOT NULL DEFAULT 0,
-                created_at TEXT NOT NULL,
-                updated_at TEXT,
-                is_deleted INTEGER NOT NULL DEFAULT 0
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code
             )
+            VALUES
+                ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571'),
+                ('c2', 'Grace Hopper', '2 Elm St', 'Taos', 'NM', '87571'),
+                ('c3', 'Alan Turing', '3 Elm St', 'Taos', 'NM', '87571');
+            INSERT INTO users (id, name, role, is_driver) VALUES ('d1', 'Dee Driver', 'volunteer', 1);
+            UPDATE inventory_items SET quantity_on_hand = 3
+            WHERE lower(name) LIKE '%split%firewood%';
             "#,
         )
-        .execute(pool)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let order = |client_id: &str, label: &str, date: Option<&str>| -> WorkOrderInput {
+            serde_json::from_value(serde_json::json!({
+                "client_id": client_id,
+                "client_name": client_id,
+                "physical_address_line1": "1 Elm St",
+                "physical_address_city": "Taos",
+                "physical_address_state": "NM",
+                "physical_address_postal_code": "87571",
+                "other_heat_source_gas": false,
+                "other_heat_source_electric": false,
+                "scheduled_date": date,
+                "status": if date.is_some() { "scheduled" } else { "received" },
+                "delivery_size_label": label,
+                "delivery_size_cords": 0.5,
+                "assignees_json": if date.is_some() { "[\"Dee Driver\"]" } else { "[]" }
+            }))
+            .unwrap()
+        };
+        let mut ids = Vec::new();
+        for input in [
+            order("c1", "Ford F-250 1/2", None),
+            order("c2", "Ford F-250 1/2", Some("2025-11-01")),
+            order("c3", "Ford F-250", None),
+            order("c3", "Ford F-250 1/2", None),
+        ] {
+            ids.push(
+                insert_work_order(&pool, input, "admin", "sam", "create_work_order")
+                    .await
+                    .unwrap()
+                    .id,
+            );
+        }
+        let pair = |a: &str, b: &str| PairWorkOrdersInput {
+            work_order_id: a.to_string(),
+            partner_id: b.to_string(),
+        };
+        assert!(pair_orders(&pool, &pair(&ids[0], &ids[0]), "staff", "sam")
+            .await
+            .is_err());
+        assert!(pair_orders(&pool, &pair(&ids[0], &ids[2]), "staff", "sam")
+            .await
+            .is_err());
+        assert!(
+            pair_orders(&pool, &pair(&ids[0], &ids[1]), "volunteer", "sam")
+                .await
+                .is_err()
+        );
+
+        pair_orders(&pool, &pair(&ids[0], &ids[1]), "staff", "sam")
+            .await
+            .unwrap();
+        let joined: (Option<String>, String, Option<String>) = sqlx::query_as(
+            "SELECT scheduled_date, status, paired_order_id FROM work_orders WHERE id = ?",
+        )
+        .bind(&ids[0])
+        .fetch_one(&pool)
         .await
         .unwrap();
+        assert_eq!(joined.0.as_deref(), Some("2025-11-01"));
+        assert_eq!(joined.1, "scheduled");
+        assert_eq!(joined.2.as_deref(), Some(ids[1].as_str()));
+        let mut conn = pool.acquire().await.unwrap();
+        assert_eq!(
+            load_assignment_summary(&mut conn, &ids[0]).await.unwrap(),
+            vec!["Dee Driver (driver)".to_string()]
+        );
+        // The order's display list and its delivery event's user ids follow the shared driver.
+        let shown: (String, String) = sqlx::query_as(
+            r#"
+            SELECT w.assignees_json, e.assigned_user_ids_json
+            FROM work_orders w
+            JOIN delivery_events e ON e.work_order_id = w.id
+            WHERE w.id = ?
+            "#,
+        )
+        .bind(&ids[0])
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(shown.0, "[\"Dee Driver\"]");
+        assert_eq!(shown.1, "[\"d1\"]");
+        drop(conn);
+        assert!(pair_orders(&pool, &pair(&ids[3], &ids[1]), "staff", "sam")
+            .await
+            .unwrap_err()
+            .contains("already paired"));
+        assert!(find_pair_issues(&pool).await.unwrap().is_empty());
+
+        sqlx::query("UPDATE work_orders SET scheduled_date = '2025-11-05' WHERE id = ?")
+            .bind(&ids[0])
+            .execute(&pool)
+            .await
+            .unwrap();
+        let issues = find_pair_issues(&pool).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue, "date_mismatch");
+
+        sqlx::query("UPDATE work_orders SET paired_order_id = NULL WHERE id = ?")
+            .bind(&ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+        let issues = find_pair_issues(&pool).await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue, "one_sided");
+        assert_eq!(issues[0].work_order_id, ids[0]);
+
+        assert_eq!(
+            unpair_orders(&pool, &ids[0], "staff", "sam").await.unwrap(),
+            ids[1]
+        );
+        assert!(unpair_orders(&pool, &ids[0], "staff", "sam").await.is_err());
+        assert!(find_pair_issues(&pool).await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn change_password_updates_hash() {
-        let pool = SqlitePool::connect(":memory:").await.unwrap();
-        setup_auth_tables(&pool).await;
-
-        let hashed = hash("oldpass", DEFAULT_COST).unwrap();
+    async fn short_deliveries_settle_actual_cords_and_backorder_the_rest() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
         sqlx::query(
             r#"
-            INSERT INTO auth_users (id, user_id, username, password, created_at, updated_at, is_deleted)
-            VALUES (?, ?, ?, ?, datetime('now'), datetime('now'), 0)
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, approval_status
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571', 'approved');
+            INSERT INTO users (id, name, role, is_driver) VALUES ('u1', 'Dee Driver', 'volunteer', 1);
+            INSERT INTO auth_users (id, user_id, username, password) VALUES ('a1', 'u1', 'dee', 'x');
+            UPDATE inventory_items SET quantity_on_hand = 3
+            WHERE lower(name) LIKE '%split%firewood%';
             "#,
         )
-        .bind("auth-id")
-        .bind("user-id")
-        .bind("tester")
-        .bind(&hashed)
         .execute(&pool)
         .await
         .unwrap();
+        let order: WorkOrderInput = serde_json::from_value(serde_json::json!({
+            "client_id": "c1",
+            "client_name": "Ada Lovelace",
+            "physical_address_line1": "1 Elm St",
+            "physical_address_city": "Taos",
+            "physical_address_state": "NM",
+            "physical_address_postal_code": "87571",
+            "other_heat_source_gas": false,
+            "other_heat_source_electric": false,
+            "scheduled_date": "2025-11-01",
+            "status": "scheduled",
+            "delivery_size_cords": 2.0,
+            "assignees_json": "[\"dee\"]"
+        }))
+        .unwrap();
+        let id = insert_work_order(&pool, order, "admin", "sam", "create_work_order")
+            .await
+            .unwrap()
+            .id;
+        let event_id: String =
+            sqlx::query_scalar("SELECT id FROM delivery_events WHERE work_order_id = ?")
+                .bind(&id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let record = |cords: f64| DeliveredCordsInput {
+            delivery_event_id: event_id.clone(),
+            delivered_cords: cords,
+        };
+        assert!(
+            record_delivery_quantity(&pool, &record(1.5), "volunteer", "dee", false)
+                .await
+                .is_err()
+        );
+        assert!(
+            record_delivery_quantity(&pool, &record(2.5), "volunteer", "dee", true)
+                .await
+                .is_err()
+        );
+        // Staff who are not on the crew cannot record for it.
+        assert!(
+            record_delivery_quantity(&pool, &record(1.5), "staff", "sam", false)
+                .await
+                .is_err()
+        );
+        record_delivery_quantity(&pool, &record(1.5), "volunteer", "dee", true)
+            .await
+            .unwrap();
+        assert!(create_backorder_order(
+            &pool,
+            BackorderInput {
+                work_order_id: id.clone(),
+                scheduled_date: None,
+                allotment_override_reason: None,
+            },
+            "staff",
+            "sam",
+        )
+        .await
+        .is_err());
+
+        let complete: WorkOrderStatusInput = serde_json::from_value(serde_json::json!({
+            "work_order_id": id,
+            "status": "completed",
+            "mileage": 12.0,
+            "work_hours": 2.0
+        }))
+        .unwrap();
+        change_work_order_status(&pool, complete, "admin", "sam")
+            .await
+            .unwrap();
+        let settled: f64 =
+            sqlx::query_scalar("SELECT delivered_cords FROM work_orders WHERE id = ?")
+                .bind(&id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(settled, 1.5);
+        let stock: (f64, f64) = sqlx::query_as(
+            "SELECT quantity_on_hand, reserved_quantity FROM inventory_items WHERE lower(name) LIKE '%split%firewood%'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(stock, (1.5, 0.0));
+        assert!(
+            record_delivery_quantity(&pool, &record(2.0), "admin", "sam", false)
+                .await
+                .is_err()
+        );
 
-        let input = ChangePasswordInput {
-            username: "tester".to_string(),
-            current_password: "oldpass".to_string(),
-            new_password: "newpass".to_string(),
+        let backorder = |date: Option<&str>| BackorderInput {
+            work_order_id: id.clone(),
+            scheduled_date: date.map(str::to_string),
+            allotment_override_reason: None,
         };
-        change_password_with_pool(&pool, input).await.unwrap();
+        let backorder_id =
+            create_backorder_order(&pool, backorder(Some("2025-11-08")), "staff", "sam")
+                .await
+                .unwrap();
+        let created: (f64, String, Option<String>) = sqlx::query_as(
+            "SELECT delivery_size_cords, status, backorder_of_id FROM work_orders WHERE id = ?",
+        )
+        .bind(&backorder_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(created, (0.5, "scheduled".to_string(), Some(id.clone())));
+        let reserved: f64 = sqlx::query_scalar(
+            "SELECT reserved_quantity FROM inventory_items WHERE lower(name) LIKE '%split%firewood%'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(reserved, 0.5);
+        assert!(
+            create_backorder_order(&pool, backorder(None), "staff", "sam")
+                .await
+                .is_err()
+        );
 
-        let row = sqlx::query!("SELECT password FROM auth_users WHERE username = ?", "tester")
-            .fetch_one(&pool)
+        let mut conn = pool.acquire().await.unwrap();
+        let check = check_allotment(&mut conn, "c1", None, Some("2025-11-15"), 0.0)
             .await
             .unwrap();
-        assert!(verify("newpass", &row.password).unwrap());
+        assert_eq!(check.used_cords, 2.0);
     }
 
     #[tokio::test]
-    async fn adjust_inventory_reserves_on_schedule() {
-        let pool = SqlitePool::connect(":memory:").await.unwrap();
-        setup_inventory_table(&pool).await;
+    async fn standing_orders_generate_once_per_date_within_limits() {
+        let d = |s: &str| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        // Every other Tuesday from the first one in the window.
+        assert_eq!(
+            standing_order_dates(
+                d("2025-10-01"),
+                d("2026-03-31"),
+                1,
+                2,
+                d("2025-11-03"),
+                d("2025-12-01"),
+            ),
+            vec![d("2025-11-04"), d("2025-11-18")]
+        );
 
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
         sqlx::query(
             r#"
-            INSERT INTO inventory_items (id, name, unit, quantity_on_hand, reserved_quantity, created_at, is_deleted)
-            VALUES (?, ?, ?, ?, ?, datetime('now'), 0)
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, approval_status
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571', 'approved');
+            INSERT INTO users (id, name, role, is_driver, availability_schedule)
+            VALUES ('u1', 'Dee Driver', 'volunteer', 1, '{"tue": true}');
+            INSERT INTO standing_orders (
+                id, client_id, cadence_weeks, preferred_weekday, delivery_size_cords,
+                window_start, window_end
+            )
+            VALUES ('s1', 'c1', 2, 'tue', 1.0, '2025-10-01', '2026-03-31'),
+                   ('s2', 'c1', 1, 'wed', 1.0, '2025-11-01', '2025-11-10');
+            UPDATE inventory_items SET quantity_on_hand = 1.5
+            WHERE lower(name) LIKE '%split%firewood%';
             "#,
         )
-        .bind("inv-1")
-        .bind("Firewood")
-        .bind("cords")
-        .bind(10.0)
-        .bind(0.0)
         .execute(&pool)
         .await
         .unwrap();
 
-        let mut tx = pool.begin().await.unwrap();
-        adjust_inventory_for_transition_tx(&mut tx, "received", "scheduled", 2.0)
+        let report =
+            generate_standing_orders_through(&pool, d("2025-11-03"), 28, "system", "scheduler")
+                .await
+                .unwrap();
+        assert_eq!(report.created.len(), 1);
+        assert_eq!(report.created[0].scheduled_date, "2025-11-04");
+        let skipped: Vec<(&str, &str)> = report
+            .skipped
+            .iter()
+            .map(|s| (s.standing_order_id.as_str(), s.scheduled_date.as_str()))
+            .collect();
+        // No driver on Wednesdays; only enough wood left for one load.
+        assert_eq!(skipped, vec![("s2", "2025-11-05"), ("s1", "2025-11-18")]);
+        let (status, standing): (String, Option<String>) =
+            sqlx::query_as("SELECT status, standing_order_id FROM work_orders WHERE id = ?")
+                .bind(&report.created[0].work_order_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "scheduled");
+        assert_eq!(standing.as_deref(), Some("s1"));
+
+        // Running again never doubles up a date, even once stock arrives.
+        sqlx::query(
+            "UPDATE inventory_items SET quantity_on_hand = 10 WHERE lower(name) LIKE '%split%firewood%'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let report =
+            generate_standing_orders_through(&pool, d("2025-11-03"), 28, "system", "scheduler")
+                .await
+                .unwrap();
+        assert_eq!(report.created.len(), 1);
+        assert_eq!(report.created[0].scheduled_date, "2025-11-18");
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM work_orders WHERE standing_order_id = 's1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[tokio::test]
+    async fn scheduling_refuses_overbooked_days_unless_a_lead_overrides() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
             .await
             .unwrap();
-        tx.commit().await.unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, name, role, is_driver, availability_schedule)
+            VALUES ('u1', 'Dee Driver', 'volunteer', 1, NULL),
+                   ('u2', 'Mo Monday', 'volunteer', 1, '{"mon": true}');
+            INSERT INTO vehicles (id, name, capacity_cords, trips_per_day)
+            VALUES ('v1', 'Flatbed', 2.0, 1),
+                   ('v2', 'Trailer', 1.0, 2);
+            INSERT INTO clients (
+                id, name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code, approval_status
+            )
+            VALUES ('c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571', 'approved');
+            UPDATE inventory_items SET quantity_on_hand = 10
+            WHERE lower(name) LIKE '%split%firewood%';
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let order: WorkOrderInput = serde_json::from_value(serde_json::json!({
+                "client_id": "c1",
+                "client_name": "Ada Lovelace",
+                "physical_address_line1": "1 Elm St",
+                "physical_address_city": "Taos",
+                "physical_address_state": "NM",
+                "physical_address_postal_code": "87571",
+                "other_heat_source_gas": false,
+                "other_heat_source_electric": false,
+                "status": "received",
+                "delivery_size_cords": 1.0
+            }))
+            .unwrap();
+            ids.push(
+                insert_work_order(&pool, order, "admin", "sam", "create_work_order")
+                    .await
+                    .unwrap()
+                    .id,
+            );
+        }
+        let schedule = |ids: Vec<String>, date: Option<&'static str>, role, reason| {
+            let pool = pool.clone();
+            async move { schedule_orders(&pool, &ids, date, reason, role, "sam", "test").await }
+        };
 
-        let row = sqlx::query!(
-            "SELECT reserved_quantity FROM inventory_items WHERE id = ?",
-            "inv-1"
+        // Tuesday: one driver, so only the flatbed goes out.
+        let report = schedule(vec![ids[0].clone()], Some("2025-11-04"), "staff", None)
+            .await
+            .unwrap();
+        let day = &report.days[0];
+        assert_eq!((day.vehicles_in_use, day.trip_slots), (1, 1));
+        assert_eq!((day.trips_booked, day.cords_booked), (1.0, 1.0));
+        assert!(!day.overbooked && report.warnings.is_empty());
+
+        assert!(
+            schedule(vec![ids[1].clone()], Some("2025-11-04"), "staff", None)
+                .await
+                .is_err()
+        );
+        assert!(schedule(
+            vec![ids[1].clone()],
+            Some("2025-11-04"),
+            "staff",
+            Some("cold snap")
+        )
+        .await
+        .is_err());
+        let (date, status): (Option<String>, String) =
+            sqlx::query_as("SELECT scheduled_date, status FROM work_orders WHERE id = ?")
+                .bind(&ids[1])
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!((date, status.as_str()), (None, "received"));
+
+        let report = schedule(
+            vec![ids[1].clone()],
+            Some("2025-11-04"),
+            "lead",
+            Some("cold snap"),
+        )
+        .await
+        .unwrap();
+        assert!(report.days[0].overbooked);
+        assert_eq!(report.warnings.len(), 1);
+        let overrides: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE field = 'capacity_override' AND new_value = 'cold snap'",
         )
         .fetch_one(&pool)
         .await
         .unwrap();
-        assert_eq!(row.reserved_quantity, 2.0);
+        assert_eq!(overrides, 1);
+
+        // Monday has both drivers: three trips and four cords between the two vehicles. A
+        // cancelled order left on the day (older rows may be capitalized) takes no room, and the
+        // UI's datetime-local value is counted against the whole day.
+        sqlx::query(
+            r#"
+            INSERT INTO work_orders (
+                id, client_id, client_name, physical_address_line1, physical_address_city,
+                physical_address_state, physical_address_postal_code,
+                status, scheduled_date, delivery_size_cords
+            )
+            VALUES ('w9', 'c1', 'Ada Lovelace', '1 Elm St', 'Taos', 'NM', '87571',
+                    'Cancelled', '2025-11-03', 2.0)
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let report = schedule(ids.clone(), Some("2025-11-03T09:30"), "staff", None)
+            .await
+            .unwrap();
+        let day = &report.days[0];
+        assert_eq!(day.date, "2025-11-03");
+        assert_eq!((day.trip_slots, day.capacity_cords), (3, 4.0));
+        assert_eq!(day.orders_booked, 2);
+        assert!(!day.overbooked);
+    }
+
+    #[tokio::test]
+    async fn import_clients_csv_reports_and_commits_atomically() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!().run(&pool).await.unwrap();
+
+        let csv_text = "First,Last,Street,Town,St,Zip,Phone\n\
+            Ada,Lovelace,1 Elm St,santa fe,nm,87501,5055550100\n\
+            Bad,Row,2 Oak St,Taos,XX,875,555\n\
+            Ada,Lovelace,1 Elm St,Santa Fe,NM,87501,\n";
+        let column_map: HashMap<String, String> = [
+            ("first_name", "First"),
+            ("last_name", "Last"),
+            ("physical_address_line1", "Street"),
+            ("physical_address_city", "Town"),
+            ("physical_address_state", "St"),
+            ("physical_address_postal_code", "Zip"),
+            ("telephone", "Phone"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let input = |dry_run: bool, text: &str| ClientImportInput {
+            csv_text: text.to_string(),
+            column_map: Some(column_map.clone()),
+            dry_run: Some(dry_run),
+            created_by_user_id: None,
+        };
+        let role = || Some("admin".to_string());
+
+        let report = import_clients_csv_with_pool(&pool, input(false, csv_text), role(), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            (
+                report.ready_count,
+                report.error_count,
+                report.duplicate_count
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(report.rows[1].errors.len(), 3);
+        assert!(!report.committed);
+
+        let clean = csv_text.replace("Bad,Row,2 Oak St,Taos,XX,875,555\n", "");
+        let report = import_clients_csv_with_pool(&pool, input(false, &clean), role(), None)
+            .await
+            .unwrap();
+        assert!(report.committed);
+        let phone: Option<String> = sqlx::query_scalar("SELECT telephone FROM clients")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(phone.as_deref(), Some("(505) 555-0100"));
+
+        let report = import_clients_csv_with_pool(&pool, input(true, &clean), role(), None)
+            .await
+            .unwrap();
+        assert_eq!(report.duplicate_count, 2);
     }
 }
 
@@ -3641,10 +15030,21 @@ fn main() -> Result<()> {
     let app = tauri::Builder::default()
         .setup(|app| {
             let database_url = resolve_database_url();
+            let data_dir = app.path().app_data_dir()?;
+            let attachments_dir = data_dir.join("attachments");
+            std::fs::create_dir_all(&attachments_dir)?;
             tauri::async_runtime::block_on(async {
                 let pool = init_pool(&database_url).await?;
                 seed_default_logins(&pool).await?;
-                app.manage(AppState { pool });
+                seed_bundled_zip_centroids(&pool)
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                jobs::start(pool.clone(), data_dir.clone());
+                app.manage(AppState {
+                    pool,
+                    data_dir,
+                    attachments_dir,
+                });
                 Ok::<(), anyhow::Error>(())
             })?;
             Ok(())
@@ -3654,18 +15054,86 @@ fn main() -> Result<()> {
             create_client,
             list_clients,
             check_client_conflict,
+            import_clients_csv,
+            export_mailing_list,
+            list_stale_client_orders,
+            sync_client_to_open_orders,
+            list_client_emergency_contacts,
+            save_client_emergency_contact,
+            delete_client_emergency_contact,
+            get_client_communication_preferences,
+            save_client_communication_preferences,
+            get_driver_run_sheet,
+            list_agencies,
+            save_agency,
+            delete_agency,
+            list_agency_values,
+            normalize_agency_values,
+            get_agency_referral_report,
+            get_distance_settings,
+            update_distance_settings,
+            import_zip_centroids,
+            list_client_mileage_comparison,
+            get_client_delivery_summary,
+            list_client_delivery_summaries,
+            create_client_contact,
+            list_client_contacts,
+            get_client_timeline,
+            list_allotment_rules,
+            save_allotment_rule,
+            delete_allotment_rule,
+            check_client_allotment,
+            add_waitlist_entry,
+            remove_waitlist_entry,
+            list_waitlist,
+            get_waitlist_settings,
+            update_waitlist_settings,
+            promote_waitlist,
+            add_attachment,
+            list_attachments,
+            get_attachment_content,
+            delete_attachment,
+            verify_attachments,
+            cleanup_orphan_attachments,
             update_client,
             delete_client,
+            anonymize_client,
+            list_erasure_certificates,
             delete_user,
             create_inventory_item,
             list_inventory_items,
             update_inventory_item,
             delete_inventory_item,
             create_work_order,
+            create_work_order_for_client,
             list_work_orders,
+            reconcile_schedule,
+            list_scheduled_jobs,
+            list_job_runs,
+            run_scheduled_job,
+            update_scheduled_job,
             update_work_order_assignees,
+            list_my_assignments,
             update_work_order_schedule,
+            bulk_schedule_work_orders,
+            get_delivery_capacity,
+            list_vehicles,
+            save_vehicle,
+            delete_vehicle,
             update_work_order_status,
+            list_work_order_status_history,
+            list_allowed_status_transitions,
+            update_work_order,
+            cancel_work_order,
+            pair_work_orders,
+            unpair_work_order,
+            list_pair_integrity_issues,
+            record_delivered_cords,
+            create_backorder,
+            list_standing_orders,
+            save_standing_order,
+            delete_standing_order,
+            generate_standing_orders,
             create_delivery_event,
             list_delivery_events,
             list_users,
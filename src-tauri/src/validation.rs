@@ -0,0 +1,142 @@
+//! Address/contact validation rules shared with the frontend.
+//! Ported from `src/utils/format.ts` and `src/utils/validation.ts`; keep the two in step.
+
+const US_STATES: [&str; 51] = [
+    "AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "FL", "GA", "HI", "ID", "IL", "IN", "IA", "KS",
+    "KY", "LA", "ME", "MD", "MA", "MI", "MN", "MS", "MO", "MT", "NE", "NV", "NH", "NJ", "NM", "NY",
+    "NC", "ND", "OH", "OK", "OR", "PA", "RI", "SC", "SD", "TN", "TX", "UT", "VT", "VA", "WA", "WV",
+    "WI", "WY", "DC", // District of Columbia
+];
+
+pub fn normalize_phone(value: &str) -> String {
+    let digits: String = value
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .take(10)
+        .collect();
+    if digits.len() == 10 {
+        return format!("({}) {}-{}", &digits[0..3], &digits[3..6], &digits[6..]);
+    }
+    value.trim().to_string()
+}
+
+pub fn is_valid_phone(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 14
+        && bytes[0] == b'('
+        && bytes[4] == b')'
+        && bytes[5] == b' '
+        && bytes[9] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| ![0, 4, 5, 9].contains(i))
+            .all(|(_, b)| b.is_ascii_digit())
+}
+
+pub fn normalize_state(value: &str) -> String {
+    value.trim().to_uppercase()
+}
+
+pub fn is_valid_state(value: &str) -> bool {
+    let normalized = normalize_state(value);
+    US_STATES.contains(&normalized.as_str())
+}
+
+pub fn normalize_postal(value: &str) -> String {
+    value.trim().to_string()
+}
+
+pub fn is_valid_postal(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    let zip5 = |b: &[u8]| b.iter().all(|c| c.is_ascii_digit());
+    match bytes.len() {
+        5 => zip5(bytes),
+        10 => zip5(&bytes[0..5]) && bytes[5] == b'-' && zip5(&bytes[6..]),
+        _ => false,
+    }
+}
+
+pub fn init_cap_city(value: &str) -> String {
+    value
+        .split(' ')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => {
+                    first.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase()
+                }
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Mirrors `normalizeAndValidatePhone`: blank values pass, anything else must be (###) ###-####.
+pub fn normalize_and_validate_phone(value: &str) -> (String, Option<String>) {
+    let normalized = normalize_phone(value);
+    if !normalized.is_empty() && !is_valid_phone(&normalized) {
+        return (
+            normalized,
+            Some("Phone must use (###) ###-#### format.".to_string()),
+        );
+    }
+    (normalized, None)
+}
+
+pub fn normalize_and_validate_state(value: &str, label: &str) -> (String, Option<String>) {
+    let normalized = normalize_state(value);
+    if !is_valid_state(&normalized) {
+        return (
+            normalized,
+            Some(format!("{} must be a valid 2-letter US state code.", label)),
+        );
+    }
+    (normalized, None)
+}
+
+pub fn normalize_and_validate_postal(
+    value: &str,
+    label: &str,
+    allow_blank: bool,
+) -> (String, Option<String>) {
+    let normalized = normalize_postal(value);
+    if allow_blank && normalized.is_empty() {
+        return (normalized, None);
+    }
+    if !is_valid_postal(&normalized) {
+        return (
+            normalized,
+            Some(format!(
+                "{} must be 5 digits or 5+4 (##### or #####-####).",
+                label
+            )),
+        );
+    }
+    (normalized, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_frontend_rules() {
+        assert_eq!(normalize_phone("505.555.0100"), "(505) 555-0100");
+        assert!(normalize_and_validate_phone("555-0100").1.is_some());
+        assert!(normalize_and_validate_phone("").1.is_none());
+        assert_eq!(
+            normalize_and_validate_state(" nm ", "State"),
+            ("NM".to_string(), None)
+        );
+        assert!(normalize_and_validate_state("XX", "State").1.is_some());
+        assert!(is_valid_postal("87501") && is_valid_postal("87501-1234"));
+        assert!(!is_valid_postal("8750") && !is_valid_postal("87501 1234"));
+        assert!(normalize_and_validate_postal("", "Mailing ZIP", true)
+            .1
+            .is_none());
+        assert_eq!(init_cap_city("  santa   FE "), "Santa Fe");
+    }
+}
@@ -0,0 +1,224 @@
+//! Work order lifecycle: the statuses an order can be in, which moves between them are allowed,
+//! who may make each move and what has to be filled in first.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkOrderStatus {
+    Draft,
+    Received,
+    Scheduled,
+    InProgress,
+    Delivered,
+    Completed,
+    Issue,
+    Cancelled,
+    PickedUp,
+}
+
+impl WorkOrderStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WorkOrderStatus::Draft => "draft",
+            WorkOrderStatus::Received => "received",
+            WorkOrderStatus::Scheduled => "scheduled",
+            WorkOrderStatus::InProgress => "in_progress",
+            WorkOrderStatus::Delivered => "delivered",
+            WorkOrderStatus::Completed => "completed",
+            WorkOrderStatus::Issue => "issue",
+            WorkOrderStatus::Cancelled => "cancelled",
+            WorkOrderStatus::PickedUp => "picked_up",
+        }
+    }
+
+    /// Also accepts the older stored values: "pending" was used for received orders and
+    /// "rescheduled" for orders that had been given a new date.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "draft" => Ok(WorkOrderStatus::Draft),
+            "received" | "pending" => Ok(WorkOrderStatus::Received),
+            "scheduled" | "rescheduled" => Ok(WorkOrderStatus::Scheduled),
+            "in_progress" => Ok(WorkOrderStatus::InProgress),
+            "delivered" => Ok(WorkOrderStatus::Delivered),
+            "completed" => Ok(WorkOrderStatus::Completed),
+            "issue" => Ok(WorkOrderStatus::Issue),
+            "cancelled" => Ok(WorkOrderStatus::Cancelled),
+            "picked_up" => Ok(WorkOrderStatus::PickedUp),
+            other => Err(format!("Unknown work order status '{}'", other)),
+        }
+    }
+}
+
+/// Who is asking, as far as status changes are concerned. Anyone acting with the driver flag
+/// is treated as a driver, whatever their role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusActor {
+    Admin,
+    Lead,
+    Staff,
+    Driver,
+}
+
+impl StatusActor {
+    pub fn from_role(role: &str, is_driver: bool) -> Option<Self> {
+        if is_driver {
+            return Some(StatusActor::Driver);
+        }
+        match role {
+            "admin" => Some(StatusActor::Admin),
+            "lead" => Some(StatusActor::Lead),
+            "staff" | "employee" => Some(StatusActor::Staff),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Requirement {
+    ScheduledDate,
+    Mileage,
+    WorkHours,
+}
+
+impl Requirement {
+    pub fn message(self) -> &'static str {
+        match self {
+            Requirement::ScheduledDate => "a scheduled date",
+            Requirement::Mileage => "mileage",
+            Requirement::WorkHours => "work hours",
+        }
+    }
+}
+
+pub struct Transition {
+    pub from: WorkOrderStatus,
+    pub to: WorkOrderStatus,
+    pub actors: &'static [StatusActor],
+    pub requires: &'static [Requirement],
+}
+
+const OFFICE: &[StatusActor] = &[StatusActor::Admin, StatusActor::Lead, StatusActor::Staff];
+const SUPERVISORS: &[StatusActor] = &[StatusActor::Admin, StatusActor::Lead];
+const CREW: &[StatusActor] = &[
+    StatusActor::Admin,
+    StatusActor::Lead,
+    StatusActor::Staff,
+    StatusActor::Driver,
+];
+const CLOSE: &[Requirement] = &[Requirement::Mileage, Requirement::WorkHours];
+
+macro_rules! transition {
+    ($from:ident => $to:ident, $actors:expr, $requires:expr) => {
+        Transition {
+            from: WorkOrderStatus::$from,
+            to: WorkOrderStatus::$to,
+            actors: $actors,
+            requires: $requires,
+        }
+    };
+}
+
+pub const TRANSITIONS: &[Transition] = &[
+    transition!(Draft => Received, OFFICE, &[]),
+    transition!(Draft => Scheduled, OFFICE, &[Requirement::ScheduledDate]),
+    transition!(Draft => PickedUp, OFFICE, &[]),
+    transition!(Draft => Cancelled, OFFICE, &[]),
+    transition!(Received => Draft, OFFICE, &[]),
+    transition!(Received => Scheduled, OFFICE, &[Requirement::ScheduledDate]),
+    transition!(Received => PickedUp, OFFICE, &[]),
+    transition!(Received => Cancelled, OFFICE, &[]),
+    transition!(Scheduled => Draft, OFFICE, &[]),
+    transition!(Scheduled => Received, OFFICE, &[]),
+    transition!(Scheduled => InProgress, CREW, &[]),
+    transition!(Scheduled => Delivered, CREW, &[]),
+    transition!(Scheduled => Issue, CREW, &[]),
+    transition!(Scheduled => PickedUp, OFFICE, &[]),
+    transition!(Scheduled => Completed, SUPERVISORS, CLOSE),
+    transition!(Scheduled => Cancelled, OFFICE, &[]),
+    transition!(InProgress => Scheduled, OFFICE, &[]),
+    transition!(InProgress => Delivered, CREW, &[]),
+    transition!(InProgress => Issue, CREW, &[]),
+    transition!(InProgress => Completed, SUPERVISORS, CLOSE),
+    transition!(InProgress => Cancelled, SUPERVISORS, &[]),
+    transition!(Delivered => Issue, CREW, &[]),
+    transition!(Delivered => Completed, SUPERVISORS, CLOSE),
+    transition!(Issue => Scheduled, OFFICE, &[Requirement::ScheduledDate]),
+    transition!(Issue => InProgress, CREW, &[]),
+    transition!(Issue => Delivered, CREW, &[]),
+    transition!(Issue => Completed, SUPERVISORS, CLOSE),
+    transition!(Issue => Cancelled, OFFICE, &[]),
+    // Reinstating starts over as a draft so wood is reserved again only when rescheduled.
+    transition!(Cancelled => Draft, SUPERVISORS, &[]),
+];
+
+pub fn find_transition(from: WorkOrderStatus, to: WorkOrderStatus) -> Option<&'static Transition> {
+    TRANSITIONS.iter().find(|t| t.from == from && t.to == to)
+}
+
+/// Checks that `actor` may move an order from `from` to `to`, returning what the move requires.
+pub fn authorize_transition(
+    from: WorkOrderStatus,
+    to: WorkOrderStatus,
+    actor: StatusActor,
+) -> Result<&'static [Requirement], String> {
+    let transition = find_transition(from, to).ok_or_else(|| {
+        format!(
+            "A work order cannot move from {} to {}",
+            from.as_str(),
+            to.as_str()
+        )
+    })?;
+    if !transition.actors.contains(&actor) {
+        return Err(format!(
+            "{:?} users cannot move a work order from {} to {}",
+            actor,
+            from.as_str(),
+            to.as_str()
+        ));
+    }
+    Ok(transition.requires)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_table_enforces_roles_and_requirements() {
+        use WorkOrderStatus::*;
+        assert_eq!(WorkOrderStatus::parse("Pending").unwrap(), Received);
+        assert_eq!(WorkOrderStatus::parse("rescheduled").unwrap(), Scheduled);
+        assert!(WorkOrderStatus::parse("lost").is_err());
+
+        assert_eq!(
+            authorize_transition(Draft, Scheduled, StatusActor::Staff).unwrap(),
+            &[Requirement::ScheduledDate]
+        );
+        assert!(authorize_transition(Scheduled, InProgress, StatusActor::Driver).is_ok());
+        assert!(authorize_transition(Delivered, Completed, StatusActor::Driver).is_err());
+        assert!(authorize_transition(Delivered, Completed, StatusActor::Staff).is_err());
+        assert_eq!(
+            authorize_transition(Delivered, Completed, StatusActor::Lead).unwrap(),
+            CLOSE
+        );
+        assert!(authorize_transition(Completed, Scheduled, StatusActor::Admin).is_err());
+        assert!(authorize_transition(Draft, Delivered, StatusActor::Admin).is_err());
+        assert_eq!(
+            StatusActor::from_role("admin", true),
+            Some(StatusActor::Driver)
+        );
+        assert_eq!(StatusActor::from_role("volunteer", false), None);
+
+        // Completed and picked-up orders are final; every other status has a way out.
+        for status in [
+            Draft, Received, Scheduled, InProgress, Delivered, Issue, Cancelled,
+        ] {
+            assert!(TRANSITIONS.iter().any(|t| t.from == status));
+        }
+        assert!(!TRANSITIONS
+            .iter()
+            .any(|t| t.from == Completed || t.from == PickedUp));
+    }
+}
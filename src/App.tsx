@@ -260,6 +260,8 @@ function App() {
   const [newWorkOrderEntryDate, setNewWorkOrderEntryDate] = useState<string>("");
   const [clientError, setClientError] = useState<string | null>(null);
   const [workOrderError, setWorkOrderError] = useState<string | null>(null);
+  // Warn-mode allotment rules save the order but hand back a warning to show here.
+  const [allotmentWarning, setAllotmentWarning] = useState<string | null>(null);
   const [progressEdits, setProgressEdits] = useState<
     Record<string, { status: string; mileage: string; hours: string }>
   >({});
@@ -514,6 +516,7 @@ function App() {
     pickup_height: "",
     pickup_units: "ft",
     paired_order_id: "",
+    allotment_override_reason: "",
   });
 
   const [workOrderNewClientEnabled, setWorkOrderNewClientEnabled] = useState(false);
@@ -748,7 +751,7 @@ function App() {
   const isStaffLike = session?.role === "staff" || session?.role === "employee";
   const canManage = isAdmin || isLead;
   const isDriver = session?.isDriver ?? false;
-  const canCreateWorkOrders = isAdmin || isStaffLike;
+  const canCreateWorkOrders = isAdmin || isLead || isStaffLike;
   const workerDetailValues = workerEdit ?? selectedWorker;
   const canViewPII =
     isAdmin || (isLead && session.hipaaCertified);
@@ -761,6 +764,7 @@ function App() {
     if (!session) return [];
     const uname = (session.username ?? "").toLowerCase();
     const displayName = (session.name ?? "").toLowerCase();
+    const userId = (session.userId ?? "").toLowerCase();
     return deliveries.filter((d) => {
       let assigned: string[] = [];
       try {
@@ -773,7 +777,11 @@ function App() {
       } catch {
         assigned = [];
       }
-      return assigned.includes(uname) || (displayName && assigned.includes(displayName));
+      return (
+        assigned.includes(uname) ||
+        (userId && assigned.includes(userId)) ||
+        (displayName && assigned.includes(displayName))
+      );
     });
   }, [deliveries, session]);
   const profileWorkByDate = useMemo(() => {
@@ -1188,6 +1196,7 @@ function App() {
     if (!session?.username) return { hours: 0, deliveries: 0, woodCreditCords: 0 };
     const uname = session.username.toLowerCase();
     const displayLower = (session.name ?? "").toLowerCase();
+    const userIdLower = (session.userId ?? "").toLowerCase();
     const defaultHours = 1.5;
     const milesPerHourFactor = 0.75 / 60; // 0.75 minutes per mile => hours per mile
     const mileageByWorkOrder: Record<string, number | undefined> = {};
@@ -1211,7 +1220,9 @@ function App() {
         assigned = [];
       }
       const matched =
-        assigned.includes(uname) || (displayLower.length > 0 && assigned.includes(displayLower));
+        assigned.includes(uname) ||
+        (userIdLower.length > 0 && assigned.includes(userIdLower)) ||
+        (displayLower.length > 0 && assigned.includes(displayLower));
       if (matched) {
         totalDeliveries += 1;
         const miles = d.work_order_id ? mileageByWorkOrder[d.work_order_id] : undefined;
@@ -1258,6 +1269,7 @@ function App() {
     const today = new Date();
     const uname = (session.username ?? "").toLowerCase();
     const displayName = (session.name ?? "").toLowerCase();
+    const userId = (session.userId ?? "").toLowerCase();
     return deliveries
       .filter((d) => (d.event_type ?? "").toLowerCase() === "delivery")
       .filter((d) => {
@@ -1279,7 +1291,11 @@ function App() {
         } catch {
           assigned = [];
         }
-        return assigned.includes(uname) || (displayName && assigned.includes(displayName));
+        return (
+          assigned.includes(uname) ||
+          (userId && assigned.includes(userId)) ||
+          (displayName && assigned.includes(displayName))
+        );
       })
       .map((d) => ({
         delivery: d,
@@ -2394,6 +2410,7 @@ function App() {
                                       },
                                       role: session?.role ?? null,
                                       actor: session?.username ?? null,
+                                      hipaa_certified: session?.hipaaCertified ?? false,
                                     });
                                   } else {
                                     await invokeTauri("create_client", { input: payload });
@@ -2879,7 +2896,8 @@ function App() {
                                 if (Array.isArray(arr)) {
                                   return arr.some((a) =>
                                     typeof a === "string"
-                                      ? a.toLowerCase() === (session.username ?? "").toLowerCase()
+                                      ? a.toLowerCase() === (session.username ?? "").toLowerCase() ||
+                                        a === session.userId
                                       : false,
                                   );
                                 }
@@ -3207,6 +3225,7 @@ function App() {
                                     pickup_height: "",
                                     pickup_units: "ft",
                                     paired_order_id: "",
+                                    allotment_override_reason: "",
                                   });
                                   setNewWorkOrderId(crypto.randomUUID());
                                   setNewWorkOrderEntryDate(new Date().toISOString());
@@ -3851,6 +3870,21 @@ function App() {
 
                 {activeTab === "Work Orders" && (
                   <div className="stack">
+                    {allotmentWarning && (
+                      <div
+                        className="pill"
+                        style={{ display: "block", background: "#fff4e5", color: "#8a5300" }}
+                      >
+                        {allotmentWarning}{" "}
+                        <button
+                          type="button"
+                          className="ghost"
+                          onClick={() => setAllotmentWarning(null)}
+                        >
+                          Dismiss
+                        </button>
+                      </div>
+                    )}
                     {isDriver && (
                       <div className="card muted">
                         <div className="list-head">
@@ -3940,7 +3974,9 @@ function App() {
                                   };
                                   setBusy(true);
                                   try {
-                                    await invokeTauri("update_work_order_status", {
+                                    const result = await invokeTauri<{
+                                      allotment_warning: string | null;
+                                    }>("update_work_order_status", {
                                       input: {
                                         work_order_id: workOrder.id,
                                         status: edit.status,
@@ -3950,6 +3986,7 @@ function App() {
                                       role: session?.role ?? null,
                                       actor: session?.username ?? null,
                                     });
+                                    setAllotmentWarning(result.allotment_warning);
                                     await loadWorkOrders();
                                   } finally {
                                     setBusy(false);
@@ -4000,6 +4037,7 @@ function App() {
                                 pickup_height: "",
                                 pickup_units: "ft",
                                 paired_order_id: "",
+                                allotment_override_reason: "",
                               });
                               setWorkOrderNewClientEnabled(false);
                               setWorkOrderNewClient({
@@ -4029,7 +4067,7 @@ function App() {
                         <h3>Schedule work order</h3>
                       </div>
                       {!canCreateWorkOrders && (
-                        <p className="muted">Only staff/employee or admins can add work orders.</p>
+                        <p className="muted">Only staff/employee, leads or admins can add work orders.</p>
                       )}
                       {showWorkOrderForm ? (
                         <>
@@ -4294,7 +4332,7 @@ function App() {
                               }
 
                               if (!canCreateWorkOrders) {
-                                setWorkOrderError("Only staff/employee or admin can create work orders.");
+                                setWorkOrderError("Only staff/employee, leads or admins can create work orders.");
                                 return;
                               }
 
@@ -4385,7 +4423,10 @@ function App() {
                               setBusy(true);
                               try {
                                 try {
-                                  await invokeTauri("create_work_order", {
+                                  const saved = await invokeTauri<{
+                                    id: string;
+                                    allotment_warning: string | null;
+                                  }>("create_work_order", {
                                     input: {
                                       client_id: targetClient.id,
                                       client_name: targetClient.name,
@@ -4446,9 +4487,12 @@ function App() {
                                       created_by_display:
                                         session?.name ?? session?.username ?? null,
                                       paired_order_id: workOrderForm.paired_order_id || null,
+                                      allotment_override_reason:
+                                        workOrderForm.allotment_override_reason.trim() || null,
                                     },
                                     role: session?.role ?? null,
                                   });
+                                  setAllotmentWarning(saved.allotment_warning);
                                 } catch (e: any) {
                                   console.error(e);
                                   setWorkOrderError(
@@ -4484,6 +4528,7 @@ function App() {
                                   pickup_height: "",
                                   pickup_units: "ft",
                                   paired_order_id: "",
+                                  allotment_override_reason: "",
                                 });
                                 setWorkOrderNewClient({
                                   first_name: "",
@@ -5133,6 +5178,21 @@ function App() {
                                   }
                               />
                             </label>
+                            {canManage && (
+                              <label>
+                                Allotment override reason
+                                <input
+                                  value={workOrderForm.allotment_override_reason}
+                                  onChange={(e) =>
+                                    setWorkOrderForm({
+                                      ...workOrderForm,
+                                      allotment_override_reason: e.target.value,
+                                    })
+                                  }
+                                  placeholder="Only needed when the client is over their allotment"
+                                />
+                              </label>
+                            )}
                             <div className="span-2">
                               <label className="checkbox">
                                 <input
@@ -5453,7 +5513,14 @@ function App() {
                                           <option value="scheduled">scheduled</option>
                                           <option value="in_progress">in_progress</option>
                                           <option value="completed">completed</option>
-                                          <option value="cancelled">cancelled</option>
+                                          <optgroup label="cancel (reason)">
+                                            <option value="cancel:client_request">client request</option>
+                                            <option value="cancel:no_longer_eligible">no longer eligible</option>
+                                            <option value="cancel:unreachable">unreachable</option>
+                                            <option value="cancel:duplicate">duplicate</option>
+                                            <option value="cancel:weather">weather</option>
+                                            <option value="cancel:out_of_stock">out of stock</option>
+                                          </optgroup>
                                         </select>
                                       )}
                                       <button
@@ -5490,7 +5557,22 @@ function App() {
                                           }
                                           setBusy(true);
                                           try {
-                                            await invokeTauri("update_work_order_status", {
+                                            if (edit.status.startsWith("cancel:")) {
+                                              // Cancelling records a reason code, so it has its own command.
+                                              await invokeTauri("cancel_work_order", {
+                                                input: {
+                                                  work_order_id: wo.id,
+                                                  reason_code: edit.status.slice("cancel:".length),
+                                                },
+                                                role: session?.role ?? null,
+                                                actor: session?.username ?? null,
+                                              });
+                                              await loadWorkOrders();
+                                              return;
+                                            }
+                                            const result = await invokeTauri<{
+                                              allotment_warning: string | null;
+                                            }>("update_work_order_status", {
                                               input: {
                                                 work_order_id: wo.id,
                                                 status:
@@ -5507,6 +5589,7 @@ function App() {
                                               role: session?.role ?? null,
                                               actor: session?.username ?? null,
                                             });
+                                            setAllotmentWarning(result.allotment_warning);
                                             await loadWorkOrders();
                                           } finally {
                                             setBusy(false);